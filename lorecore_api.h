@@ -1,13 +1,49 @@
 /* Warning, this file is autogenerated by cbindgen. Don't modify this manually. */
 
+/**
+ * Returned by all fallible C api functions.
+ * The message of the last error is available through `lore_last_error`.
+ */
+typedef enum LoreErrorCode {
+  LORE_ERROR_CODE_OK = 0,
+  LORE_ERROR_CODE_FILE_ERROR = 1,
+  LORE_ERROR_CODE_INPUT_ERROR = 2,
+  LORE_ERROR_CODE_SQL_ERROR = 3,
+} LoreErrorCode;
+
 typedef struct Day Day;
 
+/**
+ * An open lore database. C clients only ever see pointers to it.
+ */
+typedef struct LoreDatabaseHandle LoreDatabaseHandle;
+
 typedef struct CEntityColumn {
   const char *label;
   const char *descriptor;
   const char *description;
 } CEntityColumn;
 
+/**
+ * A null `text` matches everything.
+ */
+typedef struct CSqlSearchText {
+  const char *text;
+  bool is_exact;
+} CSqlSearchText;
+
+typedef struct CEntityColumnSearchParams {
+  struct CSqlSearchText label;
+  struct CSqlSearchText descriptor;
+} CEntityColumnSearchParams;
+
+typedef struct CEntityRelationship {
+  const char *parent;
+  const char *child;
+  const char *role;
+  uint32_t ordinal;
+} CEntityRelationship;
+
 typedef struct CHistoryItem {
   int64_t timestamp;
   int32_t year;
@@ -16,93 +52,533 @@ typedef struct CHistoryItem {
   const char *properties;
 } CHistoryItem;
 
-typedef struct CEntityRelationship {
-  const char *parent;
-  const char *child;
-  const char *role;
-} CEntityRelationship;
+typedef struct CEntity {
+  const char *label;
+  const struct CEntityColumn *columns;
+  intptr_t num_columns;
+  const struct CEntityRelationship *parents;
+  intptr_t num_parents;
+  const struct CEntityRelationship *children;
+  intptr_t num_children;
+  const struct CHistoryItem *history;
+  intptr_t num_history;
+} CEntity;
+
+/**
+ * The `year`, `day` and `timestamp` members are only used if the corresponding `has_` flag is set.
+ */
+typedef struct CHistoryItemSearchParams {
+  bool has_year;
+  int32_t year;
+  bool has_day;
+  uint32_t day;
+  bool has_timestamp;
+  int64_t timestamp;
+  struct CSqlSearchText content;
+} CHistoryItemSearchParams;
+
+typedef struct CRelationshipSearchParams {
+  struct CSqlSearchText parent;
+  struct CSqlSearchText child;
+} CRelationshipSearchParams;
+
+
 
 
 
 /**
+ * Opens the database at `db_path`, creating and migrating it if necessary.
+ *
  * # Safety
  *
  * `db_path` must be a valid C string.
+ * `handle` must be a valid pointer to allocated memory of a `LoreDatabaseHandle` pointer.
+ * On success, it is set to a handle that must be closed with `lore_close`.
+ */
+enum LoreErrorCode lore_open(const char *db_path, struct LoreDatabaseHandle **handle);
+
+/**
+ * Closes the database, rolling back any transaction that is still open.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open`, or null.
+ * It must not be used after this call.
+ */
+void lore_close(struct LoreDatabaseHandle *handle);
+
+/**
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ */
+enum LoreErrorCode lore_begin_transaction(const struct LoreDatabaseHandle *handle);
+
+/**
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ */
+enum LoreErrorCode lore_commit_transaction(const struct LoreDatabaseHandle *handle);
+
+/**
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ */
+enum LoreErrorCode lore_rollback_transaction(const struct LoreDatabaseHandle *handle);
+
+/**
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
  * `columns` must be a valid pointer to an array of `CEntityColumn`s.
  * `size` must correspond to the length of the array.
  */
-const char *write_entity_columns(const char *db_path,
-                                 const struct CEntityColumn *columns,
-                                 intptr_t size);
+enum LoreErrorCode write_entity_columns(const struct LoreDatabaseHandle *handle,
+                                        const struct CEntityColumn *columns,
+                                        intptr_t size);
 
 /**
+ * Sets `size` to the number of entity columns matching `params`.
+ * A null `params` matches all entity columns.
+ *
  * # Safety
  *
- * `db_path` must be a valid C string.
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `params` must be a valid pointer to a `CEntityColumnSearchParams` or null.
  * `size` must be a valid pointer to allocated memory of `isize`.
  */
-const char *get_number_of_entity_columns(const char *db_path, intptr_t *size);
+enum LoreErrorCode get_number_of_entity_columns(const struct LoreDatabaseHandle *handle,
+                                                const struct CEntityColumnSearchParams *params,
+                                                intptr_t *size);
 
 /**
+ * Fills `columns` with all entity columns matching `params` and sets `size` to their number.
+ * A null `params` matches all entity columns.
+ * If there are more than `capacity` of them, nothing is written and an error is returned.
+ *
  * # Safety
  *
- * `db_path` must be a valid C string.
- * `columns` must be a valid pointer to an array of `CEntityColumn`s.
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `params` must be a valid pointer to a `CEntityColumnSearchParams` or null.
+ * `columns` must be a valid pointer to an array of at least `capacity` `CEntityColumn`s.
+ * `size` must be a valid pointer to allocated memory of `isize`.
  */
-const char *read_entity_columns(const char *db_path, struct CEntityColumn *columns);
+enum LoreErrorCode read_entity_columns(const struct LoreDatabaseHandle *handle,
+                                       const struct CEntityColumnSearchParams *params,
+                                       struct CEntityColumn *columns,
+                                       intptr_t capacity,
+                                       intptr_t *size);
 
 /**
  * # Safety
  *
- * `db_path` must be a valid C string.
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `label` must be a valid C string.
+ * `entity` must be a valid pointer to allocated memory of a `CEntity`.
+ */
+enum LoreErrorCode read_entity(const struct LoreDatabaseHandle *handle,
+                               const char *label,
+                               struct CEntity *entity);
+
+/**
+ * Changes the label of all columns of the entity `old_label` to `new_label`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `old_label` and `new_label` must be valid C strings.
+ */
+enum LoreErrorCode relabel_entity(const struct LoreDatabaseHandle *handle,
+                                  const char *old_label,
+                                  const char *new_label);
+
+/**
+ * Deletes all columns of the entity `label`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `label` must be a valid C string.
+ */
+enum LoreErrorCode delete_entity(const struct LoreDatabaseHandle *handle, const char *label);
+
+/**
+ * Renames the descriptor `old_descriptor` of the entity `label` to `new_descriptor`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `label`, `old_descriptor` and `new_descriptor` must be valid C strings.
+ */
+enum LoreErrorCode change_entity_descriptor(const struct LoreDatabaseHandle *handle,
+                                            const char *label,
+                                            const char *old_descriptor,
+                                            const char *new_descriptor);
+
+/**
+ * Deletes the column `descriptor` of the entity `label`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `label` and `descriptor` must be valid C strings.
+ */
+enum LoreErrorCode delete_entity_column(const struct LoreDatabaseHandle *handle,
+                                        const char *label,
+                                        const char *descriptor);
+
+/**
+ * Sets the description of the column `descriptor` of the entity `label`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `label`, `descriptor` and `new_description` must be valid C strings.
+ */
+enum LoreErrorCode change_entity_description(const struct LoreDatabaseHandle *handle,
+                                             const char *label,
+                                             const char *descriptor,
+                                             const char *new_description);
+
+/**
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
  * `items` must be a valid pointer to an array of `CHistoryItem`s.
  * `size` must correspond to the length of the array.
  */
-const char *write_history_items(const char *db_path,
-                                const struct CHistoryItem *items,
-                                intptr_t size);
+enum LoreErrorCode write_history_items(const struct LoreDatabaseHandle *handle,
+                                       const struct CHistoryItem *items,
+                                       intptr_t size);
 
 /**
+ * Sets `size` to the number of history items matching `params`.
+ * A null `params` matches all history items.
+ *
  * # Safety
  *
- * `db_path` must be a valid C string.
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `params` must be a valid pointer to a `CHistoryItemSearchParams` or null.
  * `size` must be a valid pointer to allocated memory of `isize`.
  */
-const char *get_number_of_history_items(const char *db_path, intptr_t *size);
+enum LoreErrorCode get_number_of_history_items(const struct LoreDatabaseHandle *handle,
+                                               const struct CHistoryItemSearchParams *params,
+                                               intptr_t *size);
 
 /**
+ * Fills `items` with all history items matching `params` and sets `size` to their number.
+ * A null `params` matches all history items.
+ * If there are more than `capacity` of them, nothing is written and an error is returned.
+ *
  * # Safety
  *
- * `db_path` must be a valid C string.
- * `items` must be a valid pointer to an array of `CHistoryItem`s.
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `params` must be a valid pointer to a `CHistoryItemSearchParams` or null.
+ * `items` must be a valid pointer to an array of at least `capacity` `CHistoryItem`s.
+ * `size` must be a valid pointer to allocated memory of `isize`.
  */
-const char *read_history_items(const char *db_path, struct CHistoryItem *items);
+enum LoreErrorCode read_history_items(const struct LoreDatabaseHandle *handle,
+                                      const struct CHistoryItemSearchParams *params,
+                                      struct CHistoryItem *items,
+                                      intptr_t capacity,
+                                      intptr_t *size);
 
 /**
+ * Moves the history item with `timestamp` to `year` and `day`. A `day` of 0 means no day.
+ *
  * # Safety
  *
- * `db_path` must be a valid C string.
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ */
+enum LoreErrorCode redate_history_item(const struct LoreDatabaseHandle *handle,
+                                       int64_t timestamp,
+                                       int32_t year,
+                                       uint32_t day);
+
+/**
+ * Deletes the history item with `timestamp`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ */
+enum LoreErrorCode delete_history_item(const struct LoreDatabaseHandle *handle, int64_t timestamp);
+
+/**
+ * Sets the content of the history item with `timestamp`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `content` must be a valid C string.
+ */
+enum LoreErrorCode change_history_item_content(const struct LoreDatabaseHandle *handle,
+                                               int64_t timestamp,
+                                               const char *content);
+
+/**
+ * Sets the properties of the history item with `timestamp`, given as JSON.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `properties` must be a valid C string.
+ */
+enum LoreErrorCode change_history_item_properties(const struct LoreDatabaseHandle *handle,
+                                                  int64_t timestamp,
+                                                  const char *properties);
+
+/**
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
  * `relationships` must be a valid pointer to an array of `CEntityRelationship`s.
  * `size` must correspond to the length of the array.
  */
-const char *write_relationships(const char *db_path,
-                                const struct CEntityRelationship *relationships,
-                                intptr_t size);
+enum LoreErrorCode write_relationships(const struct LoreDatabaseHandle *handle,
+                                       const struct CEntityRelationship *relationships,
+                                       intptr_t size);
 
 /**
+ * Sets `size` to the number of relationships matching `params`.
+ * A null `params` matches all relationships.
+ *
  * # Safety
  *
- * `db_path` must be a valid C string.
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `params` must be a valid pointer to a `CRelationshipSearchParams` or null.
  * `size` must be a valid pointer to allocated memory of `isize`.
  */
-const char *get_number_of_relationships(const char *db_path, intptr_t *size);
+enum LoreErrorCode get_number_of_relationships(const struct LoreDatabaseHandle *handle,
+                                               const struct CRelationshipSearchParams *params,
+                                               intptr_t *size);
 
 /**
+ * Fills `relationships` with all relationships matching `params` and sets `size` to their number.
+ * A null `params` matches all relationships.
+ * If there are more than `capacity` of them, nothing is written and an error is returned.
+ *
  * # Safety
  *
- * `db_path` must be a valid C string.
- * `relationships` must be a valid pointer to an array of `CEntityRelationship`s.
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `params` must be a valid pointer to a `CRelationshipSearchParams` or null.
+ * `relationships` must be a valid pointer to an array of at least `capacity` `CEntityRelationship`s.
+ * `size` must be a valid pointer to allocated memory of `isize`.
  */
-const char *read_relationships(const char *db_path, struct CEntityRelationship *relationships);
+enum LoreErrorCode read_relationships(const struct LoreDatabaseHandle *handle,
+                                      const struct CRelationshipSearchParams *params,
+                                      struct CEntityRelationship *relationships,
+                                      intptr_t capacity,
+                                      intptr_t *size);
 
 int64_t get_current_timestamp(void);
+
+/**
+ * Sets the role of `relationship`, which is matched by parent, child and role.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `relationship` must be a valid pointer to a `CEntityRelationship`.
+ * `new_role` must be a valid C string.
+ */
+enum LoreErrorCode change_relationship_role(const struct LoreDatabaseHandle *handle,
+                                            const struct CEntityRelationship *relationship,
+                                            const char *new_role);
+
+/**
+ * Deletes `relationship`, which is matched by parent, child and role.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `relationship` must be a valid pointer to a `CEntityRelationship`.
+ */
+enum LoreErrorCode delete_relationship(const struct LoreDatabaseHandle *handle,
+                                       const struct CEntityRelationship *relationship);
+
+/**
+ * Reads the distinct labels of entity columns into `labels`, see `free_strings`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `labels` must be a valid pointer to an array of at least `capacity` C strings.
+ * `size` must be a valid pointer to allocated memory of `isize`.
+ */
+enum LoreErrorCode read_distinct_labels(const struct LoreDatabaseHandle *handle,
+                                        const char **labels,
+                                        intptr_t capacity,
+                                        intptr_t *size);
+
+/**
+ * Reads the distinct descriptors of entity columns into `descriptors`, see `free_strings`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `descriptors` must be a valid pointer to an array of at least `capacity` C strings.
+ * `size` must be a valid pointer to allocated memory of `isize`.
+ */
+enum LoreErrorCode read_distinct_descriptors(const struct LoreDatabaseHandle *handle,
+                                             const char **descriptors,
+                                             intptr_t capacity,
+                                             intptr_t *size);
+
+/**
+ * Reads the distinct years of history items, in ascending order, into `years` and sets `size` to
+ * their number. If there are more than `capacity` years, nothing is written and an error is
+ * returned.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `years` must be a valid pointer to an array of at least `capacity` `i32`s.
+ * `size` must be a valid pointer to allocated memory of `isize`.
+ */
+enum LoreErrorCode read_distinct_years(const struct LoreDatabaseHandle *handle,
+                                       int32_t *years,
+                                       intptr_t capacity,
+                                       intptr_t *size);
+
+/**
+ * Reads the distinct days of history items, in ascending order, into `days` and sets `size` to
+ * their number. If there are more than `capacity` days, nothing is written and an error is
+ * returned. A day of 0 means that some history items have no day.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `days` must be a valid pointer to an array of at least `capacity` `u32`s.
+ * `size` must be a valid pointer to allocated memory of `isize`.
+ */
+enum LoreErrorCode read_distinct_days(const struct LoreDatabaseHandle *handle,
+                                      uint32_t *days,
+                                      intptr_t capacity,
+                                      intptr_t *size);
+
+/**
+ * Reads the distinct parents of relationships into `parents`, see `free_strings`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `parents` must be a valid pointer to an array of at least `capacity` C strings.
+ * `size` must be a valid pointer to allocated memory of `isize`.
+ */
+enum LoreErrorCode read_distinct_parents(const struct LoreDatabaseHandle *handle,
+                                         const char **parents,
+                                         intptr_t capacity,
+                                         intptr_t *size);
+
+/**
+ * Reads the distinct children of relationships into `children`, see `free_strings`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `children` must be a valid pointer to an array of at least `capacity` C strings.
+ * `size` must be a valid pointer to allocated memory of `isize`.
+ */
+enum LoreErrorCode read_distinct_children(const struct LoreDatabaseHandle *handle,
+                                          const char **children,
+                                          intptr_t capacity,
+                                          intptr_t *size);
+
+/**
+ * Reads the distinct non-empty roles of relationships into `roles`, see `free_strings`.
+ *
+ * # Safety
+ *
+ * `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+ * `roles` must be a valid pointer to an array of at least `capacity` C strings.
+ * `size` must be a valid pointer to allocated memory of `isize`.
+ */
+enum LoreErrorCode read_distinct_roles(const struct LoreDatabaseHandle *handle,
+                                       const char **roles,
+                                       intptr_t capacity,
+                                       intptr_t *size);
+
+/**
+ * # Safety
+ *
+ * `string` must be a string returned by this library, or null.
+ * It must not be used after this call.
+ */
+void free_string(const char *string);
+
+/**
+ * Frees the strings that the `read_distinct_*` functions have written into `strings`.
+ * The array itself is owned by the caller.
+ *
+ * The `read_distinct_*` functions write the distinct values, in ascending order, into a buffer of
+ * `capacity` entries owned by the caller and set `size` to their number. If there are more than
+ * `capacity` values, nothing is written and an error is returned, so that the call can be repeated
+ * with a larger buffer. Labels, descriptors, parents, children and roles are written as strings
+ * allocated by this library, which have to be freed with this function.
+ *
+ * # Safety
+ *
+ * `strings` must be a valid pointer to an array of C strings filled by this library.
+ * `size` must correspond to the length of the array.
+ * The strings must not be used after this call.
+ */
+void free_strings(const char **strings, intptr_t size);
+
+/**
+ * Frees the strings that `read_entity_columns` has written into `columns`.
+ * The array itself is owned by the caller.
+ *
+ * # Safety
+ *
+ * `columns` must be a valid pointer to an array of `CEntityColumn`s filled by this library.
+ * `size` must correspond to the length of the array.
+ * The strings of the columns must not be used after this call.
+ */
+void free_entity_columns(struct CEntityColumn *columns, intptr_t size);
+
+/**
+ * Frees the strings that `read_history_items` has written into `items`.
+ * The array itself is owned by the caller.
+ *
+ * # Safety
+ *
+ * `items` must be a valid pointer to an array of `CHistoryItem`s filled by this library.
+ * `size` must correspond to the length of the array.
+ * The strings of the items must not be used after this call.
+ */
+void free_history_items(struct CHistoryItem *items, intptr_t size);
+
+/**
+ * Frees the strings that `read_relationships` has written into `relationships`.
+ * The array itself is owned by the caller.
+ *
+ * # Safety
+ *
+ * `relationships` must be a valid pointer to an array of `CEntityRelationship`s filled by this library.
+ * `size` must correspond to the length of the array.
+ * The strings of the relationships must not be used after this call.
+ */
+void free_relationships(struct CEntityRelationship *relationships,
+                        intptr_t size);
+
+/**
+ * Frees the strings and arrays that `read_entity` has written into `entity`.
+ * The struct itself is owned by the caller.
+ *
+ * # Safety
+ *
+ * `entity` must be a valid pointer to a `CEntity` filled by this library.
+ * Its members must not be used after this call.
+ */
+void free_entity(struct CEntity *entity);
+
+/**
+ * Returns the message of the error reported by the last C api call on this thread, or an empty
+ * string if that call succeeded.
+ * The string is owned by the library. It stays valid until the next C api call on this thread and
+ * must not be freed.
+ */
+const char *lore_last_error(void);
@@ -0,0 +1 @@
+ALTER TABLE relationships ADD COLUMN ordinal INTEGER NOT NULL DEFAULT 0;
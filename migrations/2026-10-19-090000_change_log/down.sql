@@ -0,0 +1 @@
+DROP TABLE IF EXISTS change_log;
\ No newline at end of file
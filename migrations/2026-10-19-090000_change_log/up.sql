@@ -0,0 +1,8 @@
+CREATE TABLE change_log (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  timestamp BIGINT NOT NULL,
+  author TEXT,
+  operation TEXT NOT NULL,
+  old_values TEXT NOT NULL,
+  new_values TEXT NOT NULL
+);
\ No newline at end of file
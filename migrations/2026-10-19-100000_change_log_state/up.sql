@@ -0,0 +1 @@
+ALTER TABLE change_log ADD COLUMN state INTEGER NOT NULL DEFAULT 0;
\ No newline at end of file
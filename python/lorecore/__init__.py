@@ -0,0 +1,12 @@
+"""Python bindings for the lorecore library, built on its C api."""
+
+from .database import LoreDatabase, current_timestamp
+from .types import (Entity, EntityColumn, EntityColumnSearchParams, EntityRelationship, FileError,
+                    HistoryItem, HistoryItemSearchParams, InputError, LoreCoreError,
+                    RelationshipSearchParams, SearchText, SqlError)
+
+__all__ = [
+    "Entity", "EntityColumn", "EntityColumnSearchParams", "EntityRelationship", "FileError",
+    "HistoryItem", "HistoryItemSearchParams", "InputError", "LoreCoreError", "LoreDatabase",
+    "RelationshipSearchParams", "SearchText", "SqlError", "current_timestamp",
+]
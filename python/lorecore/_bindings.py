@@ -0,0 +1,190 @@
+"""ctypes declarations mirroring lorecore_api.h.
+
+This module is private. Use the classes exported by the lorecore package instead.
+"""
+
+import ctypes
+import os
+import sys
+
+
+def get_lib_name() -> str:
+    """Return the file name of the lorecore library on the current platform."""
+    if sys.platform.startswith('win'):
+        return "lorecore.dll"
+    if sys.platform.startswith('linux'):
+        return "liblorecore.so"
+    if sys.platform.startswith('darwin'):
+        return "liblorecore.dylib"
+    raise ValueError("Unsupported OS")
+
+
+def find_lib_path() -> str:
+    """Find the lorecore library.
+
+    The environment variable LORECORE_LIB takes precedence. Otherwise, the package folder and the
+    cargo target folders of the repository are searched.
+
+    Raises:
+        FileNotFoundError: If the library does not exist in any of the expected locations
+    """
+    from_env = os.environ.get("LORECORE_LIB")
+    if from_env:
+        return from_env
+    lib_name = get_lib_name()
+    package_dir = os.path.dirname(os.path.abspath(__file__))
+    repo_dir = os.path.dirname(os.path.dirname(package_dir))
+    for folder in (package_dir,
+                   os.path.join(repo_dir, "target", "debug"),
+                   os.path.join(repo_dir, "target", "debug", "deps"),
+                   os.path.join(repo_dir, "target", "release"),
+                   os.path.join(repo_dir, "target", "release", "deps")):
+        path = os.path.join(folder, lib_name)
+        if os.path.isfile(path):
+            return path
+    raise FileNotFoundError("Could not find " + lib_name + ", consider setting LORECORE_LIB")
+
+
+class CEntityColumn(ctypes.Structure):
+    _fields_ = [("label", ctypes.c_char_p),
+                ("descriptor", ctypes.c_char_p),
+                ("description", ctypes.c_char_p)]
+
+
+class CHistoryItem(ctypes.Structure):
+    _fields_ = [("timestamp", ctypes.c_int64),
+                ("year", ctypes.c_int32),
+                ("day", ctypes.c_uint32),
+                ("content", ctypes.c_char_p),
+                ("properties", ctypes.c_char_p)]
+
+
+class CEntityRelationship(ctypes.Structure):
+    _fields_ = [("parent", ctypes.c_char_p),
+                ("child", ctypes.c_char_p),
+                ("role", ctypes.c_char_p),
+                ("ordinal", ctypes.c_uint32)]
+
+
+class CEntity(ctypes.Structure):
+    _fields_ = [("label", ctypes.c_char_p),
+                ("columns", ctypes.POINTER(CEntityColumn)),
+                ("num_columns", ctypes.c_ssize_t),
+                ("parents", ctypes.POINTER(CEntityRelationship)),
+                ("num_parents", ctypes.c_ssize_t),
+                ("children", ctypes.POINTER(CEntityRelationship)),
+                ("num_children", ctypes.c_ssize_t),
+                ("history", ctypes.POINTER(CHistoryItem)),
+                ("num_history", ctypes.c_ssize_t)]
+
+
+class CSqlSearchText(ctypes.Structure):
+    _fields_ = [("text", ctypes.c_char_p),
+                ("is_exact", ctypes.c_bool)]
+
+
+class CEntityColumnSearchParams(ctypes.Structure):
+    _fields_ = [("label", CSqlSearchText),
+                ("descriptor", CSqlSearchText)]
+
+
+class CHistoryItemSearchParams(ctypes.Structure):
+    _fields_ = [("has_year", ctypes.c_bool),
+                ("year", ctypes.c_int32),
+                ("has_day", ctypes.c_bool),
+                ("day", ctypes.c_uint32),
+                ("has_timestamp", ctypes.c_bool),
+                ("timestamp", ctypes.c_int64),
+                ("content", CSqlSearchText)]
+
+
+class CRelationshipSearchParams(ctypes.Structure):
+    _fields_ = [("parent", CSqlSearchText),
+                ("child", CSqlSearchText)]
+
+
+class LoreDatabaseHandle(ctypes.Structure):
+    pass
+
+
+LORE_OK = 0
+LORE_FILE_ERROR = 1
+LORE_INPUT_ERROR = 2
+LORE_SQL_ERROR = 3
+
+_HANDLE = ctypes.POINTER(LoreDatabaseHandle)
+_STRING = ctypes.c_char_p
+_SIZE = ctypes.c_ssize_t
+_SIZE_PTR = ctypes.POINTER(ctypes.c_ssize_t)
+
+_SIGNATURES = {
+    "lore_open": ([_STRING, ctypes.POINTER(_HANDLE)], ctypes.c_int),
+    "lore_close": ([_HANDLE], None),
+    "lore_begin_transaction": ([_HANDLE], ctypes.c_int),
+    "lore_commit_transaction": ([_HANDLE], ctypes.c_int),
+    "lore_rollback_transaction": ([_HANDLE], ctypes.c_int),
+    "lore_last_error": ([], _STRING),
+    "write_entity_columns": ([_HANDLE, ctypes.POINTER(CEntityColumn), _SIZE], ctypes.c_int),
+    "get_number_of_entity_columns":
+        ([_HANDLE, ctypes.POINTER(CEntityColumnSearchParams), _SIZE_PTR], ctypes.c_int),
+    "read_entity_columns":
+        ([_HANDLE, ctypes.POINTER(CEntityColumnSearchParams), ctypes.POINTER(CEntityColumn),
+          _SIZE, _SIZE_PTR], ctypes.c_int),
+    "read_entity": ([_HANDLE, _STRING, ctypes.POINTER(CEntity)], ctypes.c_int),
+    "relabel_entity": ([_HANDLE, _STRING, _STRING], ctypes.c_int),
+    "delete_entity": ([_HANDLE, _STRING], ctypes.c_int),
+    "change_entity_descriptor": ([_HANDLE, _STRING, _STRING, _STRING], ctypes.c_int),
+    "delete_entity_column": ([_HANDLE, _STRING, _STRING], ctypes.c_int),
+    "change_entity_description": ([_HANDLE, _STRING, _STRING, _STRING], ctypes.c_int),
+    "write_history_items": ([_HANDLE, ctypes.POINTER(CHistoryItem), _SIZE], ctypes.c_int),
+    "get_number_of_history_items":
+        ([_HANDLE, ctypes.POINTER(CHistoryItemSearchParams), _SIZE_PTR], ctypes.c_int),
+    "read_history_items":
+        ([_HANDLE, ctypes.POINTER(CHistoryItemSearchParams), ctypes.POINTER(CHistoryItem),
+          _SIZE, _SIZE_PTR], ctypes.c_int),
+    "redate_history_item": ([_HANDLE, ctypes.c_int64, ctypes.c_int32, ctypes.c_uint32],
+                            ctypes.c_int),
+    "delete_history_item": ([_HANDLE, ctypes.c_int64], ctypes.c_int),
+    "change_history_item_content": ([_HANDLE, ctypes.c_int64, _STRING], ctypes.c_int),
+    "change_history_item_properties": ([_HANDLE, ctypes.c_int64, _STRING], ctypes.c_int),
+    "write_relationships": ([_HANDLE, ctypes.POINTER(CEntityRelationship), _SIZE],
+                            ctypes.c_int),
+    "get_number_of_relationships":
+        ([_HANDLE, ctypes.POINTER(CRelationshipSearchParams), _SIZE_PTR], ctypes.c_int),
+    "read_relationships":
+        ([_HANDLE, ctypes.POINTER(CRelationshipSearchParams),
+          ctypes.POINTER(CEntityRelationship), _SIZE, _SIZE_PTR], ctypes.c_int),
+    "change_relationship_role": ([_HANDLE, ctypes.POINTER(CEntityRelationship), _STRING],
+                                 ctypes.c_int),
+    "delete_relationship": ([_HANDLE, ctypes.POINTER(CEntityRelationship)], ctypes.c_int),
+    "read_distinct_labels": ([_HANDLE, ctypes.POINTER(_STRING), _SIZE, _SIZE_PTR], ctypes.c_int),
+    "read_distinct_descriptors":
+        ([_HANDLE, ctypes.POINTER(_STRING), _SIZE, _SIZE_PTR], ctypes.c_int),
+    "read_distinct_years":
+        ([_HANDLE, ctypes.POINTER(ctypes.c_int32), _SIZE, _SIZE_PTR], ctypes.c_int),
+    "read_distinct_days":
+        ([_HANDLE, ctypes.POINTER(ctypes.c_uint32), _SIZE, _SIZE_PTR], ctypes.c_int),
+    "read_distinct_parents": ([_HANDLE, ctypes.POINTER(_STRING), _SIZE, _SIZE_PTR], ctypes.c_int),
+    "read_distinct_children":
+        ([_HANDLE, ctypes.POINTER(_STRING), _SIZE, _SIZE_PTR], ctypes.c_int),
+    "read_distinct_roles": ([_HANDLE, ctypes.POINTER(_STRING), _SIZE, _SIZE_PTR], ctypes.c_int),
+    "get_current_timestamp": ([], ctypes.c_int64),
+    "free_entity_columns": ([ctypes.POINTER(CEntityColumn), _SIZE], None),
+    "free_history_items": ([ctypes.POINTER(CHistoryItem), _SIZE], None),
+    "free_relationships": ([ctypes.POINTER(CEntityRelationship), _SIZE], None),
+    "free_entity": ([ctypes.POINTER(CEntity)], None),
+    "free_strings": ([ctypes.POINTER(_STRING), _SIZE], None),
+}
+
+
+def load_library() -> ctypes.CDLL:
+    """Load the lorecore library and declare the signatures of its functions."""
+    lib = ctypes.CDLL(find_lib_path())
+    for name, (argtypes, restype) in _SIGNATURES.items():
+        function = getattr(lib, name)
+        function.argtypes = argtypes
+        function.restype = restype
+    return lib
+
+
+lib = load_library()
@@ -0,0 +1,254 @@
+"""The LoreDatabase class wrapping a database handle of the lorecore library."""
+
+import ctypes
+import json
+from typing import Iterable, List, Optional
+
+from . import _bindings as b
+from .types import (Entity, EntityColumn, EntityColumnSearchParams, EntityRelationship,
+                    FileError, HistoryItem, HistoryItemSearchParams, InputError, LoreCoreError,
+                    RelationshipSearchParams, SearchText, SqlError)
+
+_ERRORS = {
+    b.LORE_FILE_ERROR: FileError,
+    b.LORE_INPUT_ERROR: InputError,
+    b.LORE_SQL_ERROR: SqlError,
+}
+
+
+def _check(code: int) -> None:
+    if code == b.LORE_OK:
+        return
+    message = b.lib.lore_last_error().decode('utf-8')
+    raise _ERRORS.get(code, LoreCoreError)(message)
+
+
+def _encode(text: Optional[str]) -> Optional[bytes]:
+    return None if text is None else text.encode('utf-8')
+
+
+def _decode(text: Optional[bytes]) -> str:
+    return "" if text is None else text.decode('utf-8')
+
+
+def current_timestamp() -> int:
+    """Return a timestamp suitable for a new history item."""
+    return b.lib.get_current_timestamp()
+
+
+def _to_c_search_text(text: Optional[SearchText]) -> b.CSqlSearchText:
+    if text is None:
+        return b.CSqlSearchText(None, False)
+    return b.CSqlSearchText(_encode(text.text), text.is_exact)
+
+
+def _to_c_column(column: EntityColumn) -> b.CEntityColumn:
+    return b.CEntityColumn(_encode(column.label), _encode(column.descriptor),
+                           _encode(column.description))
+
+
+def _from_c_column(column: b.CEntityColumn) -> EntityColumn:
+    return EntityColumn(_decode(column.label), _decode(column.descriptor),
+                        _decode(column.description))
+
+
+def _to_c_item(item: HistoryItem) -> b.CHistoryItem:
+    return b.CHistoryItem(item.timestamp, item.year, item.day or 0, _encode(item.content),
+                          _encode(json.dumps(item.properties)))
+
+
+def _from_c_item(item: b.CHistoryItem) -> HistoryItem:
+    properties = json.loads(_decode(item.properties) or "{}")
+    return HistoryItem(item.timestamp, item.year, _decode(item.content), item.day or None,
+                       properties)
+
+
+def _to_c_relationship(rel: EntityRelationship) -> b.CEntityRelationship:
+    return b.CEntityRelationship(_encode(rel.parent), _encode(rel.child), _encode(rel.role),
+                                 rel.ordinal or 0)
+
+
+def _from_c_relationship(rel: b.CEntityRelationship) -> EntityRelationship:
+    return EntityRelationship(_decode(rel.parent), _decode(rel.child), _decode(rel.role),
+                              rel.ordinal or None)
+
+
+class LoreDatabase:
+    """An open lore database. Use it as a context manager, or call close() when done."""
+
+    def __init__(self, path: str):
+        self._handle = ctypes.POINTER(b.LoreDatabaseHandle)()
+        _check(b.lib.lore_open(_encode(str(path)), ctypes.byref(self._handle)))
+
+    def close(self) -> None:
+        if self._handle:
+            b.lib.lore_close(self._handle)
+            self._handle = ctypes.POINTER(b.LoreDatabaseHandle)()
+
+    def __enter__(self) -> "LoreDatabase":
+        return self
+
+    def __exit__(self, exc_type, exc_value, traceback) -> None:
+        self.close()
+
+    def begin_transaction(self) -> None:
+        _check(b.lib.lore_begin_transaction(self._handle))
+
+    def commit_transaction(self) -> None:
+        _check(b.lib.lore_commit_transaction(self._handle))
+
+    def rollback_transaction(self) -> None:
+        _check(b.lib.lore_rollback_transaction(self._handle))
+
+    def _fill_buffer(self, read, c_type, capacity: int):
+        """Call read with growing buffers until all entries fit into one."""
+        size = ctypes.c_ssize_t(capacity)
+        while True:
+            capacity = size.value
+            buffer = (c_type * capacity)()
+            code = read(buffer, capacity, ctypes.byref(size))
+            if code == b.LORE_OK:
+                return buffer, size.value
+            if size.value <= capacity:
+                _check(code)
+
+    def _read(self, count, read, free, c_type, convert, params) -> list:
+        size = ctypes.c_ssize_t(0)
+        _check(count(self._handle, params, ctypes.byref(size)))
+        buffer, size = self._fill_buffer(
+            lambda *args: read(self._handle, params, *args), c_type, size.value)
+        try:
+            return [convert(entry) for entry in buffer[:size]]
+        finally:
+            free(buffer, size)
+
+    def _read_distinct(self, read, c_type, convert) -> list:
+        buffer, size = self._fill_buffer(lambda *args: read(self._handle, *args), c_type, 0)
+        try:
+            return [convert(value) for value in buffer[:size]]
+        finally:
+            if c_type is ctypes.c_char_p:
+                b.lib.free_strings(buffer, size)
+
+    def write_entity_columns(self, columns: Iterable[EntityColumn]) -> None:
+        columns = [_to_c_column(column) for column in columns]
+        array = (b.CEntityColumn * len(columns))(*columns)
+        _check(b.lib.write_entity_columns(self._handle, array, len(columns)))
+
+    def read_entity_columns(self, params: Optional[EntityColumnSearchParams] = None
+                            ) -> List[EntityColumn]:
+        c_params = None
+        if params is not None:
+            c_params = ctypes.byref(b.CEntityColumnSearchParams(
+                _to_c_search_text(params.label), _to_c_search_text(params.descriptor)))
+        return self._read(b.lib.get_number_of_entity_columns, b.lib.read_entity_columns,
+                          b.lib.free_entity_columns, b.CEntityColumn, _from_c_column, c_params)
+
+    def read_entity(self, label: str) -> Entity:
+        """Raises InputError if there is no entity with this label."""
+        entity = b.CEntity()
+        _check(b.lib.read_entity(self._handle, _encode(label), ctypes.byref(entity)))
+        try:
+            return Entity(
+                _decode(entity.label),
+                [_from_c_column(entity.columns[i]) for i in range(entity.num_columns)],
+                [_from_c_relationship(entity.parents[i]) for i in range(entity.num_parents)],
+                [_from_c_relationship(entity.children[i]) for i in range(entity.num_children)],
+                [_from_c_item(entity.history[i]) for i in range(entity.num_history)])
+        finally:
+            b.lib.free_entity(ctypes.byref(entity))
+
+    def relabel_entity(self, old_label: str, new_label: str) -> None:
+        _check(b.lib.relabel_entity(self._handle, _encode(old_label), _encode(new_label)))
+
+    def delete_entity(self, label: str) -> None:
+        _check(b.lib.delete_entity(self._handle, _encode(label)))
+
+    def change_entity_descriptor(self, label: str, old_descriptor: str,
+                                 new_descriptor: str) -> None:
+        _check(b.lib.change_entity_descriptor(self._handle, _encode(label),
+                                              _encode(old_descriptor), _encode(new_descriptor)))
+
+    def delete_entity_column(self, label: str, descriptor: str) -> None:
+        _check(b.lib.delete_entity_column(self._handle, _encode(label), _encode(descriptor)))
+
+    def change_entity_description(self, label: str, descriptor: str,
+                                  new_description: str) -> None:
+        _check(b.lib.change_entity_description(self._handle, _encode(label), _encode(descriptor),
+                                               _encode(new_description)))
+
+    def write_history_items(self, items: Iterable[HistoryItem]) -> None:
+        items = [_to_c_item(item) for item in items]
+        array = (b.CHistoryItem * len(items))(*items)
+        _check(b.lib.write_history_items(self._handle, array, len(items)))
+
+    def read_history_items(self, params: Optional[HistoryItemSearchParams] = None
+                           ) -> List[HistoryItem]:
+        c_params = None
+        if params is not None:
+            c_params = ctypes.byref(b.CHistoryItemSearchParams(
+                params.year is not None, params.year or 0,
+                params.day is not None, params.day or 0,
+                params.timestamp is not None, params.timestamp or 0,
+                _to_c_search_text(params.content)))
+        return self._read(b.lib.get_number_of_history_items, b.lib.read_history_items,
+                          b.lib.free_history_items, b.CHistoryItem, _from_c_item, c_params)
+
+    def redate_history_item(self, timestamp: int, year: int, day: Optional[int] = None) -> None:
+        _check(b.lib.redate_history_item(self._handle, timestamp, year, day or 0))
+
+    def delete_history_item(self, timestamp: int) -> None:
+        _check(b.lib.delete_history_item(self._handle, timestamp))
+
+    def change_history_item_content(self, timestamp: int, content: str) -> None:
+        _check(b.lib.change_history_item_content(self._handle, timestamp, _encode(content)))
+
+    def change_history_item_properties(self, timestamp: int, properties: dict) -> None:
+        _check(b.lib.change_history_item_properties(self._handle, timestamp,
+                                                    _encode(json.dumps(properties))))
+
+    def write_relationships(self, relationships: Iterable[EntityRelationship]) -> None:
+        rels = [_to_c_relationship(rel) for rel in relationships]
+        array = (b.CEntityRelationship * len(rels))(*rels)
+        _check(b.lib.write_relationships(self._handle, array, len(rels)))
+
+    def read_relationships(self, params: Optional[RelationshipSearchParams] = None
+                           ) -> List[EntityRelationship]:
+        c_params = None
+        if params is not None:
+            c_params = ctypes.byref(b.CRelationshipSearchParams(
+                _to_c_search_text(params.parent), _to_c_search_text(params.child)))
+        return self._read(b.lib.get_number_of_relationships, b.lib.read_relationships,
+                          b.lib.free_relationships, b.CEntityRelationship,
+                          _from_c_relationship, c_params)
+
+    def change_relationship_role(self, relationship: EntityRelationship, new_role: str) -> None:
+        rel = _to_c_relationship(relationship)
+        _check(b.lib.change_relationship_role(self._handle, ctypes.byref(rel),
+                                              _encode(new_role)))
+
+    def delete_relationship(self, relationship: EntityRelationship) -> None:
+        rel = _to_c_relationship(relationship)
+        _check(b.lib.delete_relationship(self._handle, ctypes.byref(rel)))
+
+    def read_distinct_labels(self) -> List[str]:
+        return self._read_distinct(b.lib.read_distinct_labels, ctypes.c_char_p, _decode)
+
+    def read_distinct_descriptors(self) -> List[str]:
+        return self._read_distinct(b.lib.read_distinct_descriptors, ctypes.c_char_p, _decode)
+
+    def read_distinct_years(self) -> List[int]:
+        return self._read_distinct(b.lib.read_distinct_years, ctypes.c_int32, int)
+
+    def read_distinct_days(self) -> List[Optional[int]]:
+        return self._read_distinct(b.lib.read_distinct_days, ctypes.c_uint32,
+                                   lambda day: day or None)
+
+    def read_distinct_parents(self) -> List[str]:
+        return self._read_distinct(b.lib.read_distinct_parents, ctypes.c_char_p, _decode)
+
+    def read_distinct_children(self) -> List[str]:
+        return self._read_distinct(b.lib.read_distinct_children, ctypes.c_char_p, _decode)
+
+    def read_distinct_roles(self) -> List[str]:
+        return self._read_distinct(b.lib.read_distinct_roles, ctypes.c_char_p, _decode)
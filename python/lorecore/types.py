@@ -0,0 +1,88 @@
+"""Pythonic counterparts of the lorecore record types and search parameters."""
+
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional
+
+
+class LoreCoreError(Exception):
+    """Base class of all errors reported by the lorecore library."""
+
+
+class FileError(LoreCoreError):
+    pass
+
+
+class InputError(LoreCoreError):
+    pass
+
+
+class SqlError(LoreCoreError):
+    pass
+
+
+@dataclass(frozen=True)
+class EntityColumn:
+    label: str
+    descriptor: str
+    description: str = ""
+
+
+@dataclass(frozen=True)
+class HistoryItem:
+    timestamp: int
+    year: int
+    content: str
+    day: Optional[int] = None
+    properties: Dict[str, Any] = field(default_factory=dict, hash=False)
+
+
+@dataclass(frozen=True)
+class EntityRelationship:
+    parent: str
+    child: str
+    role: str = ""
+    ordinal: Optional[int] = None
+
+
+@dataclass(frozen=True)
+class Entity:
+    label: str
+    descriptors: List[EntityColumn]
+    parents: List[EntityRelationship]
+    children: List[EntityRelationship]
+    history: List[HistoryItem]
+
+
+@dataclass(frozen=True)
+class SearchText:
+    """Matches a text exactly, or partially with `*` as wildcard."""
+    text: str
+    is_exact: bool = False
+
+    @staticmethod
+    def exact(text: str) -> "SearchText":
+        return SearchText(text, True)
+
+    @staticmethod
+    def partial(text: str) -> "SearchText":
+        return SearchText(text, False)
+
+
+@dataclass(frozen=True)
+class EntityColumnSearchParams:
+    label: Optional[SearchText] = None
+    descriptor: Optional[SearchText] = None
+
+
+@dataclass(frozen=True)
+class HistoryItemSearchParams:
+    year: Optional[int] = None
+    day: Optional[int] = None
+    timestamp: Optional[int] = None
+    content: Optional[SearchText] = None
+
+
+@dataclass(frozen=True)
+class RelationshipSearchParams:
+    parent: Optional[SearchText] = None
+    child: Optional[SearchText] = None
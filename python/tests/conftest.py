@@ -0,0 +1,14 @@
+import os
+import sys
+
+import pytest
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from lorecore import LoreDatabase  # noqa: E402
+
+
+@pytest.fixture
+def db(tmp_path):
+    with LoreDatabase(tmp_path / "test.db") as database:
+        yield database
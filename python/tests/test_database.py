@@ -0,0 +1,118 @@
+import pytest
+
+from lorecore import (EntityColumn, EntityColumnSearchParams, EntityRelationship, HistoryItem,
+                      HistoryItemSearchParams, InputError, LoreDatabase,
+                      RelationshipSearchParams, SearchText, SqlError, current_timestamp)
+
+
+def test_entity_columns_roundtrip(db):
+    columns = [EntityColumn("testlabel1", "testdescriptor", "testdescription"),
+               EntityColumn("testlabel2", "testdescriptor", "")]
+    db.write_entity_columns(columns)
+    assert sorted(db.read_entity_columns(), key=lambda c: c.label) == columns
+
+
+def test_entity_column_search(db):
+    db.write_entity_columns([EntityColumn("testlabel1", "a"), EntityColumn("testlabel2", "b"),
+                             EntityColumn("other", "a")])
+    exact = EntityColumnSearchParams(label=SearchText.exact("testlabel2"))
+    assert [c.label for c in db.read_entity_columns(exact)] == ["testlabel2"]
+    partial = EntityColumnSearchParams(label=SearchText.partial("test*"),
+                                       descriptor=SearchText.exact("a"))
+    assert [c.label for c in db.read_entity_columns(partial)] == ["testlabel1"]
+
+
+def test_entity_updates(db):
+    db.write_entity_columns([EntityColumn("testlabel", "a", "x"), EntityColumn("testlabel", "b")])
+    db.relabel_entity("testlabel", "newlabel")
+    db.change_entity_descriptor("newlabel", "a", "c")
+    db.change_entity_description("newlabel", "c", "y")
+    db.delete_entity_column("newlabel", "b")
+    assert db.read_entity_columns() == [EntityColumn("newlabel", "c", "y")]
+    db.delete_entity("newlabel")
+    assert db.read_entity_columns() == []
+
+
+def test_history_items_roundtrip(db):
+    items = [HistoryItem(current_timestamp(), 2020, "testcontent1"),
+             HistoryItem(current_timestamp(), -5, "testcontent2", day=3,
+                         properties={"is_secret": True})]
+    db.write_history_items(items)
+    assert sorted(db.read_history_items(), key=lambda i: i.year) == sorted(items,
+                                                                            key=lambda i: i.year)
+
+
+def test_history_item_search_and_updates(db):
+    db.write_history_items([HistoryItem(1, 2020, "a"), HistoryItem(2, 2021, "b", day=4)])
+    assert [i.timestamp for i in db.read_history_items(HistoryItemSearchParams(year=2021))] == [2]
+    assert [i.timestamp for i in db.read_history_items(HistoryItemSearchParams(day=4))] == [2]
+    db.redate_history_item(1, 2022, 7)
+    db.change_history_item_content(1, "changed")
+    db.change_history_item_properties(1, {"is_secret": True})
+    changed = db.read_history_items(HistoryItemSearchParams(timestamp=1))
+    assert changed == [HistoryItem(1, 2022, "changed", 7, {"is_secret": True})]
+    db.delete_history_item(1)
+    assert [i.timestamp for i in db.read_history_items()] == [2]
+
+
+def test_relationships(db):
+    rels = [EntityRelationship("parent", "child1", "daughter", 1),
+            EntityRelationship("parent", "child2")]
+    db.write_relationships(rels)
+    assert db.read_relationships() == rels
+    search = RelationshipSearchParams(child=SearchText.partial("child2"))
+    assert db.read_relationships(search) == [rels[1]]
+    db.change_relationship_role(rels[1], "son")
+    assert db.read_relationships(search)[0].role == "son"
+    db.delete_relationship(EntityRelationship("parent", "child2", "son"))
+    assert db.read_relationships() == [rels[0]]
+
+
+def test_read_entity(db):
+    db.write_entity_columns([EntityColumn("testlabel", "descriptor", "description")])
+    db.write_relationships([EntityRelationship("testparent", "testlabel")])
+    db.write_history_items([HistoryItem(1, 2020, "About \\entityref{testlabel}.")])
+    entity = db.read_entity("testlabel")
+    assert entity.label == "testlabel"
+    assert entity.descriptors == [EntityColumn("testlabel", "descriptor", "description")]
+    assert [rel.parent for rel in entity.parents] == ["testparent"]
+    assert entity.children == []
+    assert [item.timestamp for item in entity.history] == [1]
+    with pytest.raises(InputError):
+        db.read_entity("fununu")
+
+
+def test_distinct_values(db):
+    db.write_entity_columns([EntityColumn("b", "x"), EntityColumn("a", "x")])
+    db.write_history_items([HistoryItem(1, 2021, "c"), HistoryItem(2, 2020, "c", day=2)])
+    db.write_relationships([EntityRelationship("p", "c1", "r"), EntityRelationship("p", "c2")])
+    assert db.read_distinct_labels() == ["a", "b"]
+    assert db.read_distinct_descriptors() == ["x"]
+    assert db.read_distinct_years() == [2020, 2021]
+    assert db.read_distinct_days() == [None, 2]
+    assert db.read_distinct_parents() == ["p"]
+    assert db.read_distinct_children() == ["c1", "c2"]
+    assert db.read_distinct_roles() == ["r"]
+
+
+def test_transactions(db):
+    db.begin_transaction()
+    db.write_entity_columns([EntityColumn("testlabel", "descriptor")])
+    db.rollback_transaction()
+    assert db.read_entity_columns() == []
+    with pytest.raises(InputError):
+        db.commit_transaction()
+
+
+def test_errors_carry_the_message(db):
+    db.write_entity_columns([EntityColumn("testlabel", "descriptor")])
+    with pytest.raises(SqlError) as error:
+        db.write_entity_columns([EntityColumn("testlabel", "descriptor")])
+    assert "UNIQUE" in str(error.value)
+
+
+def test_closed_database_raises(tmp_path):
+    database = LoreDatabase(tmp_path / "test.db")
+    database.close()
+    with pytest.raises(InputError):
+        database.read_entity_columns()
@@ -1,186 +1,745 @@
-use crate::timestamp::current_timestamp;
+use crate::{sql::lore_database::LoreDatabase, timestamp::current_timestamp};
 
 use super::{
-    auxil::char_ptr,
+    auxil::{
+        FreeMembers, c_array_to_slice, char_pointer_to_string, fill_c_buffer, fill_c_buffer_with,
+        free_char_pointer, string_to_char_pointer,
+    },
     c_types::*,
-    write_database::{c_write_entity_column, c_write_history_item, c_write_relationship},
+    error::{LoreErrorCode, to_error_code},
+    handle::{LoreDatabaseHandle, handle_to_database},
+    read_database::{
+        c_read_entity, c_read_entity_columns, c_read_history_items, c_read_relationships,
+    },
+    update_database::*,
+    write_database::{c_write_entity_columns, c_write_history_items, c_write_relationships},
 };
 
+/// Opens the database at `db_path`, creating and migrating it if necessary.
+///
 /// # Safety
 ///
 /// `db_path` must be a valid C string.
+/// `handle` must be a valid pointer to allocated memory of a `LoreDatabaseHandle` pointer.
+/// On success, it is set to a handle that must be closed with `lore_close`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lore_open(
+    db_path: *const libc::c_char,
+    handle: *mut *mut LoreDatabaseHandle,
+) -> LoreErrorCode {
+    let db = char_pointer_to_string(db_path).and_then(|path| LoreDatabase::open(path.into()));
+    to_error_code(
+        db.map(|db| unsafe { *handle = Box::into_raw(Box::new(LoreDatabaseHandle { db })) }),
+    )
+}
+
+/// Closes the database, rolling back any transaction that is still open.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open`, or null.
+/// It must not be used after this call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lore_close(handle: *mut LoreDatabaseHandle) {
+    if !handle.is_null() {
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lore_begin_transaction(
+    handle: *const LoreDatabaseHandle,
+) -> LoreErrorCode {
+    to_error_code(unsafe { handle_to_database(handle) }.and_then(|db| db.begin_transaction()))
+}
+
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lore_commit_transaction(
+    handle: *const LoreDatabaseHandle,
+) -> LoreErrorCode {
+    to_error_code(unsafe { handle_to_database(handle) }.and_then(|db| db.commit_transaction()))
+}
+
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lore_rollback_transaction(
+    handle: *const LoreDatabaseHandle,
+) -> LoreErrorCode {
+    to_error_code(unsafe { handle_to_database(handle) }.and_then(|db| db.rollback_transaction()))
+}
+
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
 /// `columns` must be a valid pointer to an array of `CEntityColumn`s.
 /// `size` must correspond to the length of the array.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn write_entity_columns(
-    db_path: *const libc::c_char,
+    handle: *const LoreDatabaseHandle,
     columns: *const CEntityColumn,
     size: isize,
-) -> *const libc::c_char {
-    for i in 0..size {
-        let column = unsafe { &*columns.offset(i) };
-        if let Err(e) = c_write_entity_column(db_path, column) {
-            return char_ptr(&e.to_string());
-        }
-    }
-    char_ptr("")
+) -> LoreErrorCode {
+    let columns = unsafe { c_array_to_slice(columns, size) };
+    to_error_code(
+        unsafe { handle_to_database(handle) }.and_then(|db| c_write_entity_columns(db, columns)),
+    )
 }
 
+/// Sets `size` to the number of entity columns matching `params`.
+/// A null `params` matches all entity columns.
+///
 /// # Safety
 ///
-/// `db_path` must be a valid C string.
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `params` must be a valid pointer to a `CEntityColumnSearchParams` or null.
 /// `size` must be a valid pointer to allocated memory of `isize`.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn get_number_of_entity_columns(
-    db_path: *const libc::c_char,
+    handle: *const LoreDatabaseHandle,
+    params: *const CEntityColumnSearchParams,
     size: *mut isize,
-) -> *const libc::c_char {
-    match super::read_database::c_read_entity_columns(db_path) {
-        Ok(cols) => {
-            unsafe { *size = cols.len() as isize };
-            char_ptr("")
-        }
-        Err(e) => char_ptr(&e.to_string()),
-    }
+) -> LoreErrorCode {
+    let params = unsafe { params.as_ref() };
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_read_entity_columns(db, params))
+            .map(|cols| unsafe { *size = cols.len() as isize }),
+    )
 }
 
+/// Fills `columns` with all entity columns matching `params` and sets `size` to their number.
+/// A null `params` matches all entity columns.
+/// If there are more than `capacity` of them, nothing is written and an error is returned.
+///
 /// # Safety
 ///
-/// `db_path` must be a valid C string.
-/// `columns` must be a valid pointer to an array of `CEntityColumn`s.
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `params` must be a valid pointer to a `CEntityColumnSearchParams` or null.
+/// `columns` must be a valid pointer to an array of at least `capacity` `CEntityColumn`s.
+/// `size` must be a valid pointer to allocated memory of `isize`.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn read_entity_columns(
-    db_path: *const libc::c_char,
+    handle: *const LoreDatabaseHandle,
+    params: *const CEntityColumnSearchParams,
     columns: *mut CEntityColumn,
-) -> *const libc::c_char {
-    match super::read_database::c_read_entity_columns(db_path) {
-        Ok(database_entries) => {
-            for (i, _) in database_entries.iter().enumerate() {
-                unsafe { *columns.add(i) = database_entries[i].clone() };
-            }
-            char_ptr("")
-        }
-        Err(e) => char_ptr(&e.to_string()),
-    }
+    capacity: isize,
+    size: *mut isize,
+) -> LoreErrorCode {
+    let params = unsafe { params.as_ref() };
+    let result = unsafe { handle_to_database(handle) }
+        .and_then(|db| c_read_entity_columns(db, params))
+        .and_then(|entries| unsafe { fill_c_buffer(entries, columns, capacity, size) });
+    to_error_code(result)
 }
 
 /// # Safety
 ///
-/// `db_path` must be a valid C string.
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `label` must be a valid C string.
+/// `entity` must be a valid pointer to allocated memory of a `CEntity`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn read_entity(
+    handle: *const LoreDatabaseHandle,
+    label: *const libc::c_char,
+    entity: *mut CEntity,
+) -> LoreErrorCode {
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_read_entity(db, label))
+            .map(|database_entity| unsafe { *entity = database_entity }),
+    )
+}
+
+/// Changes the label of all columns of the entity `old_label` to `new_label`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `old_label` and `new_label` must be valid C strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn relabel_entity(
+    handle: *const LoreDatabaseHandle,
+    old_label: *const libc::c_char,
+    new_label: *const libc::c_char,
+) -> LoreErrorCode {
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_relabel_entity(db, old_label, new_label)),
+    )
+}
+
+/// Deletes all columns of the entity `label`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `label` must be a valid C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delete_entity(
+    handle: *const LoreDatabaseHandle,
+    label: *const libc::c_char,
+) -> LoreErrorCode {
+    to_error_code(unsafe { handle_to_database(handle) }.and_then(|db| c_delete_entity(db, label)))
+}
+
+/// Renames the descriptor `old_descriptor` of the entity `label` to `new_descriptor`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `label`, `old_descriptor` and `new_descriptor` must be valid C strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn change_entity_descriptor(
+    handle: *const LoreDatabaseHandle,
+    label: *const libc::c_char,
+    old_descriptor: *const libc::c_char,
+    new_descriptor: *const libc::c_char,
+) -> LoreErrorCode {
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_change_entity_descriptor(db, label, old_descriptor, new_descriptor)),
+    )
+}
+
+/// Deletes the column `descriptor` of the entity `label`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `label` and `descriptor` must be valid C strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delete_entity_column(
+    handle: *const LoreDatabaseHandle,
+    label: *const libc::c_char,
+    descriptor: *const libc::c_char,
+) -> LoreErrorCode {
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_delete_entity_column(db, label, descriptor)),
+    )
+}
+
+/// Sets the description of the column `descriptor` of the entity `label`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `label`, `descriptor` and `new_description` must be valid C strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn change_entity_description(
+    handle: *const LoreDatabaseHandle,
+    label: *const libc::c_char,
+    descriptor: *const libc::c_char,
+    new_description: *const libc::c_char,
+) -> LoreErrorCode {
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_change_entity_description(db, label, descriptor, new_description)),
+    )
+}
+
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
 /// `items` must be a valid pointer to an array of `CHistoryItem`s.
 /// `size` must correspond to the length of the array.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn write_history_items(
-    db_path: *const libc::c_char,
+    handle: *const LoreDatabaseHandle,
     items: *const CHistoryItem,
     size: isize,
-) -> *const libc::c_char {
-    for i in 0..size {
-        let item = unsafe { &*items.offset(i) };
-        if let Err(e) = c_write_history_item(db_path, item) {
-            return char_ptr(&e.to_string());
-        }
-    }
-    char_ptr("")
+) -> LoreErrorCode {
+    let items = unsafe { c_array_to_slice(items, size) };
+    to_error_code(
+        unsafe { handle_to_database(handle) }.and_then(|db| c_write_history_items(db, items)),
+    )
 }
 
+/// Sets `size` to the number of history items matching `params`.
+/// A null `params` matches all history items.
+///
 /// # Safety
 ///
-/// `db_path` must be a valid C string.
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `params` must be a valid pointer to a `CHistoryItemSearchParams` or null.
 /// `size` must be a valid pointer to allocated memory of `isize`.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn get_number_of_history_items(
-    db_path: *const libc::c_char,
+    handle: *const LoreDatabaseHandle,
+    params: *const CHistoryItemSearchParams,
     size: *mut isize,
-) -> *const libc::c_char {
-    match super::read_database::c_read_history_items(db_path) {
-        Ok(items) => {
-            unsafe { *size = items.len() as isize };
-            char_ptr("")
-        }
-        Err(e) => char_ptr(&e.to_string()),
-    }
+) -> LoreErrorCode {
+    let params = unsafe { params.as_ref() };
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_read_history_items(db, params))
+            .map(|items| unsafe { *size = items.len() as isize }),
+    )
 }
 
+/// Fills `items` with all history items matching `params` and sets `size` to their number.
+/// A null `params` matches all history items.
+/// If there are more than `capacity` of them, nothing is written and an error is returned.
+///
 /// # Safety
 ///
-/// `db_path` must be a valid C string.
-/// `items` must be a valid pointer to an array of `CHistoryItem`s.
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `params` must be a valid pointer to a `CHistoryItemSearchParams` or null.
+/// `items` must be a valid pointer to an array of at least `capacity` `CHistoryItem`s.
+/// `size` must be a valid pointer to allocated memory of `isize`.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn read_history_items(
-    db_path: *const libc::c_char,
+    handle: *const LoreDatabaseHandle,
+    params: *const CHistoryItemSearchParams,
     items: *mut CHistoryItem,
-) -> *const libc::c_char {
-    match super::read_database::c_read_history_items(db_path) {
-        Ok(database_entries) => {
-            for (i, _) in database_entries.iter().enumerate() {
-                unsafe { *items.add(i) = database_entries[i].clone() };
-            }
-            char_ptr("")
-        }
-        Err(e) => char_ptr(&e.to_string()),
-    }
+    capacity: isize,
+    size: *mut isize,
+) -> LoreErrorCode {
+    let params = unsafe { params.as_ref() };
+    let result = unsafe { handle_to_database(handle) }
+        .and_then(|db| c_read_history_items(db, params))
+        .and_then(|entries| unsafe { fill_c_buffer(entries, items, capacity, size) });
+    to_error_code(result)
 }
 
+/// Moves the history item with `timestamp` to `year` and `day`. A `day` of 0 means no day.
+///
 /// # Safety
 ///
-/// `db_path` must be a valid C string.
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redate_history_item(
+    handle: *const LoreDatabaseHandle,
+    timestamp: i64,
+    year: i32,
+    day: u32,
+) -> LoreErrorCode {
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_redate_history_item(db, timestamp, year, day)),
+    )
+}
+
+/// Deletes the history item with `timestamp`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delete_history_item(
+    handle: *const LoreDatabaseHandle,
+    timestamp: i64,
+) -> LoreErrorCode {
+    to_error_code(
+        unsafe { handle_to_database(handle) }.and_then(|db| c_delete_history_item(db, timestamp)),
+    )
+}
+
+/// Sets the content of the history item with `timestamp`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `content` must be a valid C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn change_history_item_content(
+    handle: *const LoreDatabaseHandle,
+    timestamp: i64,
+    content: *const libc::c_char,
+) -> LoreErrorCode {
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_change_history_item_content(db, timestamp, content)),
+    )
+}
+
+/// Sets the properties of the history item with `timestamp`, given as JSON.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `properties` must be a valid C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn change_history_item_properties(
+    handle: *const LoreDatabaseHandle,
+    timestamp: i64,
+    properties: *const libc::c_char,
+) -> LoreErrorCode {
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_change_history_item_properties(db, timestamp, properties)),
+    )
+}
+
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
 /// `relationships` must be a valid pointer to an array of `CEntityRelationship`s.
 /// `size` must correspond to the length of the array.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn write_relationships(
-    db_path: *const libc::c_char,
+    handle: *const LoreDatabaseHandle,
     relationships: *const CEntityRelationship,
     size: isize,
-) -> *const libc::c_char {
-    for i in 0..size {
-        let rel = unsafe { &*relationships.offset(i) };
-        if let Err(e) = c_write_relationship(db_path, rel) {
-            return char_ptr(&e.to_string());
-        }
-    }
-    char_ptr("")
+) -> LoreErrorCode {
+    let relationships = unsafe { c_array_to_slice(relationships, size) };
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_write_relationships(db, relationships)),
+    )
 }
 
+/// Sets `size` to the number of relationships matching `params`.
+/// A null `params` matches all relationships.
+///
 /// # Safety
 ///
-/// `db_path` must be a valid C string.
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `params` must be a valid pointer to a `CRelationshipSearchParams` or null.
 /// `size` must be a valid pointer to allocated memory of `isize`.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn get_number_of_relationships(
-    db_path: *const libc::c_char,
+    handle: *const LoreDatabaseHandle,
+    params: *const CRelationshipSearchParams,
     size: *mut isize,
-) -> *const libc::c_char {
-    match super::read_database::c_read_relationships(db_path) {
-        Ok(relationships) => {
-            unsafe { *size = relationships.len() as isize };
-            char_ptr("")
-        }
-        Err(e) => char_ptr(&e.to_string()),
-    }
+) -> LoreErrorCode {
+    let params = unsafe { params.as_ref() };
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_read_relationships(db, params))
+            .map(|relationships| unsafe { *size = relationships.len() as isize }),
+    )
 }
 
+/// Fills `relationships` with all relationships matching `params` and sets `size` to their number.
+/// A null `params` matches all relationships.
+/// If there are more than `capacity` of them, nothing is written and an error is returned.
+///
 /// # Safety
 ///
-/// `db_path` must be a valid C string.
-/// `relationships` must be a valid pointer to an array of `CEntityRelationship`s.
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `params` must be a valid pointer to a `CRelationshipSearchParams` or null.
+/// `relationships` must be a valid pointer to an array of at least `capacity` `CEntityRelationship`s.
+/// `size` must be a valid pointer to allocated memory of `isize`.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn read_relationships(
-    db_path: *const libc::c_char,
+    handle: *const LoreDatabaseHandle,
+    params: *const CRelationshipSearchParams,
     relationships: *mut CEntityRelationship,
-) -> *const libc::c_char {
-    match super::read_database::c_read_relationships(db_path) {
-        Ok(database_entries) => {
-            for (i, _) in database_entries.iter().enumerate() {
-                unsafe { *relationships.add(i) = database_entries[i].clone() };
-            }
-            char_ptr("")
-        }
-        Err(e) => char_ptr(&e.to_string()),
-    }
+    capacity: isize,
+    size: *mut isize,
+) -> LoreErrorCode {
+    let params = unsafe { params.as_ref() };
+    let result = unsafe { handle_to_database(handle) }
+        .and_then(|db| c_read_relationships(db, params))
+        .and_then(|entries| unsafe { fill_c_buffer(entries, relationships, capacity, size) });
+    to_error_code(result)
 }
 
 #[unsafe(no_mangle)]
 pub extern "C" fn get_current_timestamp() -> i64 {
     current_timestamp().to_int()
 }
+
+/// Sets the role of `relationship`, which is matched by parent, child and role.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `relationship` must be a valid pointer to a `CEntityRelationship`.
+/// `new_role` must be a valid C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn change_relationship_role(
+    handle: *const LoreDatabaseHandle,
+    relationship: *const CEntityRelationship,
+    new_role: *const libc::c_char,
+) -> LoreErrorCode {
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_change_relationship_role(db, relationship, new_role)),
+    )
+}
+
+/// Deletes `relationship`, which is matched by parent, child and role.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `relationship` must be a valid pointer to a `CEntityRelationship`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delete_relationship(
+    handle: *const LoreDatabaseHandle,
+    relationship: *const CEntityRelationship,
+) -> LoreErrorCode {
+    to_error_code(
+        unsafe { handle_to_database(handle) }
+            .and_then(|db| c_delete_relationship(db, relationship)),
+    )
+}
+
+/// Reads the distinct labels of entity columns into `labels`, see `free_strings`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `labels` must be a valid pointer to an array of at least `capacity` C strings.
+/// `size` must be a valid pointer to allocated memory of `isize`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn read_distinct_labels(
+    handle: *const LoreDatabaseHandle,
+    labels: *mut *const libc::c_char,
+    capacity: isize,
+    size: *mut isize,
+) -> LoreErrorCode {
+    let result = unsafe { handle_to_database(handle) }
+        .and_then(|db| db.read_distinct_labels())
+        .and_then(|values| unsafe {
+            fill_c_buffer_with(values, labels, capacity, size, |value| {
+                Ok(string_to_char_pointer(value.to_str()))
+            })
+        });
+    to_error_code(result)
+}
+
+/// Reads the distinct descriptors of entity columns into `descriptors`, see `free_strings`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `descriptors` must be a valid pointer to an array of at least `capacity` C strings.
+/// `size` must be a valid pointer to allocated memory of `isize`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn read_distinct_descriptors(
+    handle: *const LoreDatabaseHandle,
+    descriptors: *mut *const libc::c_char,
+    capacity: isize,
+    size: *mut isize,
+) -> LoreErrorCode {
+    let result = unsafe { handle_to_database(handle) }
+        .and_then(|db| db.read_distinct_descriptors())
+        .and_then(|values| unsafe {
+            fill_c_buffer_with(values, descriptors, capacity, size, |value| {
+                Ok(string_to_char_pointer(value.to_str()))
+            })
+        });
+    to_error_code(result)
+}
+
+/// Reads the distinct years of history items, in ascending order, into `years` and sets `size` to
+/// their number. If there are more than `capacity` years, nothing is written and an error is
+/// returned.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `years` must be a valid pointer to an array of at least `capacity` `i32`s.
+/// `size` must be a valid pointer to allocated memory of `isize`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn read_distinct_years(
+    handle: *const LoreDatabaseHandle,
+    years: *mut i32,
+    capacity: isize,
+    size: *mut isize,
+) -> LoreErrorCode {
+    let result = unsafe { handle_to_database(handle) }
+        .and_then(|db| db.read_distinct_years())
+        .and_then(|values| unsafe {
+            fill_c_buffer_with(values, years, capacity, size, |value| Ok(value.to_int()))
+        });
+    to_error_code(result)
+}
+
+/// Reads the distinct days of history items, in ascending order, into `days` and sets `size` to
+/// their number. If there are more than `capacity` days, nothing is written and an error is
+/// returned. A day of 0 means that some history items have no day.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `days` must be a valid pointer to an array of at least `capacity` `u32`s.
+/// `size` must be a valid pointer to allocated memory of `isize`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn read_distinct_days(
+    handle: *const LoreDatabaseHandle,
+    days: *mut u32,
+    capacity: isize,
+    size: *mut isize,
+) -> LoreErrorCode {
+    let result = unsafe { handle_to_database(handle) }
+        .and_then(|db| db.read_distinct_days())
+        .and_then(|values| unsafe {
+            fill_c_buffer_with(values, days, capacity, size, |value| Ok(value.to_int()))
+        });
+    to_error_code(result)
+}
+
+/// Reads the distinct parents of relationships into `parents`, see `free_strings`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `parents` must be a valid pointer to an array of at least `capacity` C strings.
+/// `size` must be a valid pointer to allocated memory of `isize`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn read_distinct_parents(
+    handle: *const LoreDatabaseHandle,
+    parents: *mut *const libc::c_char,
+    capacity: isize,
+    size: *mut isize,
+) -> LoreErrorCode {
+    let result = unsafe { handle_to_database(handle) }
+        .and_then(|db| db.read_distinct_parents())
+        .and_then(|values| unsafe {
+            fill_c_buffer_with(values, parents, capacity, size, |value| {
+                Ok(string_to_char_pointer(value.to_str()))
+            })
+        });
+    to_error_code(result)
+}
+
+/// Reads the distinct children of relationships into `children`, see `free_strings`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `children` must be a valid pointer to an array of at least `capacity` C strings.
+/// `size` must be a valid pointer to allocated memory of `isize`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn read_distinct_children(
+    handle: *const LoreDatabaseHandle,
+    children: *mut *const libc::c_char,
+    capacity: isize,
+    size: *mut isize,
+) -> LoreErrorCode {
+    let result = unsafe { handle_to_database(handle) }
+        .and_then(|db| db.read_distinct_children())
+        .and_then(|values| unsafe {
+            fill_c_buffer_with(values, children, capacity, size, |value| {
+                Ok(string_to_char_pointer(value.to_str()))
+            })
+        });
+    to_error_code(result)
+}
+
+/// Reads the distinct non-empty roles of relationships into `roles`, see `free_strings`.
+///
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet.
+/// `roles` must be a valid pointer to an array of at least `capacity` C strings.
+/// `size` must be a valid pointer to allocated memory of `isize`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn read_distinct_roles(
+    handle: *const LoreDatabaseHandle,
+    roles: *mut *const libc::c_char,
+    capacity: isize,
+    size: *mut isize,
+) -> LoreErrorCode {
+    let result = unsafe { handle_to_database(handle) }
+        .and_then(|db| db.read_distinct_roles())
+        .and_then(|values| unsafe {
+            fill_c_buffer_with(values, roles, capacity, size, |value| {
+                Ok(string_to_char_pointer(value.to_str()))
+            })
+        });
+    to_error_code(result)
+}
+
+/// # Safety
+///
+/// `string` must be a string returned by this library, or null.
+/// It must not be used after this call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free_string(string: *const libc::c_char) {
+    unsafe { free_char_pointer(string) };
+}
+
+/// Frees the strings that the `read_distinct_*` functions have written into `strings`.
+/// The array itself is owned by the caller.
+///
+/// The `read_distinct_*` functions write the distinct values, in ascending order, into a buffer of
+/// `capacity` entries owned by the caller and set `size` to their number. If there are more than
+/// `capacity` values, nothing is written and an error is returned, so that the call can be repeated
+/// with a larger buffer. Labels, descriptors, parents, children and roles are written as strings
+/// allocated by this library, which have to be freed with this function.
+///
+/// # Safety
+///
+/// `strings` must be a valid pointer to an array of C strings filled by this library.
+/// `size` must correspond to the length of the array.
+/// The strings must not be used after this call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free_strings(strings: *mut *const libc::c_char, size: isize) {
+    for i in 0..size {
+        unsafe {
+            let string = strings.offset(i);
+            free_char_pointer(*string);
+            *string = std::ptr::null();
+        }
+    }
+}
+
+/// Frees the strings that `read_entity_columns` has written into `columns`.
+/// The array itself is owned by the caller.
+///
+/// # Safety
+///
+/// `columns` must be a valid pointer to an array of `CEntityColumn`s filled by this library.
+/// `size` must correspond to the length of the array.
+/// The strings of the columns must not be used after this call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free_entity_columns(columns: *mut CEntityColumn, size: isize) {
+    for i in 0..size {
+        unsafe { (*columns.offset(i)).free_members() };
+    }
+}
+
+/// Frees the strings that `read_history_items` has written into `items`.
+/// The array itself is owned by the caller.
+///
+/// # Safety
+///
+/// `items` must be a valid pointer to an array of `CHistoryItem`s filled by this library.
+/// `size` must correspond to the length of the array.
+/// The strings of the items must not be used after this call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free_history_items(items: *mut CHistoryItem, size: isize) {
+    for i in 0..size {
+        unsafe { (*items.offset(i)).free_members() };
+    }
+}
+
+/// Frees the strings that `read_relationships` has written into `relationships`.
+/// The array itself is owned by the caller.
+///
+/// # Safety
+///
+/// `relationships` must be a valid pointer to an array of `CEntityRelationship`s filled by this library.
+/// `size` must correspond to the length of the array.
+/// The strings of the relationships must not be used after this call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free_relationships(relationships: *mut CEntityRelationship, size: isize) {
+    for i in 0..size {
+        unsafe { (*relationships.offset(i)).free_members() };
+    }
+}
+
+/// Frees the strings and arrays that `read_entity` has written into `entity`.
+/// The struct itself is owned by the caller.
+///
+/// # Safety
+///
+/// `entity` must be a valid pointer to a `CEntity` filled by this library.
+/// Its members must not be used after this call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free_entity(entity: *mut CEntity) {
+    unsafe { (*entity).free_members() };
+}
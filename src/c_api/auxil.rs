@@ -27,14 +27,141 @@ pub(super) fn string_to_char_pointer(string: &str) -> *const libc::c_char {
     CString::new(string).unwrap_or_default().into_raw()
 }
 
-pub(super) fn char_ptr(message: &str) -> *const libc::c_char {
-    CString::new(message).unwrap_or_default().into_raw()
+/// # Safety
+///
+/// `string` must have been created by `string_to_char_pointer`, or be null.
+/// It must not be used after this call.
+pub(super) unsafe fn free_char_pointer(string: *const libc::c_char) {
+    if !string.is_null() {
+        drop(unsafe { CString::from_raw(string as *mut libc::c_char) });
+    }
+}
+
+/// C values whose members may point to memory allocated by this library.
+pub(crate) trait FreeMembers {
+    /// # Safety
+    ///
+    /// The members must have been allocated by this library and must not be used afterwards.
+    unsafe fn free_members(&mut self);
+}
+
+impl FreeMembers for *const libc::c_char {
+    unsafe fn free_members(&mut self) {
+        unsafe { free_char_pointer(*self) };
+        *self = std::ptr::null();
+    }
+}
+
+impl FreeMembers for i32 {
+    unsafe fn free_members(&mut self) {}
+}
+
+impl FreeMembers for u32 {
+    unsafe fn free_members(&mut self) {}
+}
+
+/// Converts all `values`, or none: if one conversion fails, the members of the values converted
+/// before are freed again.
+pub(super) fn convert_all<T, C, F>(values: Vec<T>, convert: F) -> Result<Vec<C>, LoreCoreError>
+where
+    C: FreeMembers,
+    F: Fn(T) -> Result<C, LoreCoreError>,
+{
+    let mut converted = Vec::with_capacity(values.len());
+    for value in values {
+        match convert(value) {
+            Ok(c_value) => converted.push(c_value),
+            Err(e) => {
+                for c_value in converted.iter_mut() {
+                    unsafe { c_value.free_members() };
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok(converted)
+}
+
+pub(super) fn vec_to_c_array<T>(vec: Vec<T>) -> *const T {
+    Box::into_raw(vec.into_boxed_slice()) as *const T
+}
+
+/// # Safety
+///
+/// `array` must be a valid pointer to an array of `size` elements of type `T`, or null if `size` is 0.
+pub(super) unsafe fn c_array_to_slice<'a, T>(array: *const T, size: isize) -> &'a [T] {
+    if array.is_null() || size <= 0 {
+        return &[];
+    }
+    unsafe { std::slice::from_raw_parts(array, size as usize) }
+}
+
+/// # Safety
+///
+/// `array` must have been created by `vec_to_c_array` from a vector of `size` elements, or be null.
+/// It must not be used after this call.
+pub(super) unsafe fn free_c_array<T>(array: *const T, size: isize) {
+    if !array.is_null() {
+        let slice = std::ptr::slice_from_raw_parts_mut(array as *mut T, size.max(0) as usize);
+        drop(unsafe { Box::from_raw(slice) });
+    }
+}
+
+/// Converts `values` into `buffer`, unless there are more than `capacity` of them.
+/// In any case, `size` is set to the number of values.
+///
+/// # Safety
+///
+/// `buffer` must be a valid pointer to an array of at least `capacity` elements of type `C`.
+/// `size` must be a valid pointer to allocated memory of `isize`.
+pub(super) unsafe fn fill_c_buffer<T, C>(
+    values: Vec<T>,
+    buffer: *mut C,
+    capacity: isize,
+    size: *mut isize,
+) -> Result<(), LoreCoreError>
+where
+    C: TryFrom<T, Error = LoreCoreError> + FreeMembers,
+{
+    unsafe { fill_c_buffer_with(values, buffer, capacity, size, C::try_from) }
+}
+
+/// Like `fill_c_buffer`, but for types that need a custom conversion, such as strings.
+///
+/// # Safety
+///
+/// See `fill_c_buffer`.
+pub(super) unsafe fn fill_c_buffer_with<T, C, F>(
+    values: Vec<T>,
+    buffer: *mut C,
+    capacity: isize,
+    size: *mut isize,
+    convert: F,
+) -> Result<(), LoreCoreError>
+where
+    C: FreeMembers,
+    F: Fn(T) -> Result<C, LoreCoreError>,
+{
+    if !size.is_null() {
+        unsafe { *size = values.len() as isize };
+    }
+    if values.len() as isize > capacity {
+        return Err(LoreCoreError::InputError(format!(
+            "Buffer of capacity {} is too small for {} entries.",
+            capacity,
+            values.len()
+        )));
+    }
+    for (i, c_value) in convert_all(values, convert)?.into_iter().enumerate() {
+        unsafe { buffer.add(i).write(c_value) };
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::ptr;
+    use std::{cell::Cell, ptr};
 
     #[test]
     fn test_char_pointer_to_string() {
@@ -70,6 +197,108 @@ mod tests {
         assert_eq!(result.unwrap(), string);
     }
 
+    #[test]
+    fn test_vec_to_c_array() {
+        let vec = vec![1, 2, 3];
+        let array = vec_to_c_array(vec.clone());
+        let slice = unsafe { c_array_to_slice(array, vec.len() as isize) };
+        assert_eq!(slice, vec.as_slice());
+    }
+
+    #[test]
+    fn test_free_char_pointer() {
+        let char_pointer = string_to_char_pointer("Eyjafjallajökull!");
+        unsafe { free_char_pointer(char_pointer) };
+        unsafe { free_char_pointer(ptr::null()) };
+    }
+
+    #[test]
+    fn test_free_c_array() {
+        let array = vec_to_c_array(vec![1, 2, 3]);
+        unsafe { free_c_array(array, 3) };
+        unsafe { free_c_array::<i32>(ptr::null(), 0) };
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Wrapper(i32);
+
+    impl FreeMembers for Wrapper {
+        unsafe fn free_members(&mut self) {}
+    }
+
+    impl TryFrom<i32> for Wrapper {
+        type Error = LoreCoreError;
+
+        fn try_from(value: i32) -> Result<Self, Self::Error> {
+            Ok(Wrapper(value))
+        }
+    }
+
+    #[test]
+    fn test_fill_c_buffer() {
+        let mut buffer = [Wrapper(0), Wrapper(0), Wrapper(0)];
+        let mut size = 0;
+        let result = unsafe { fill_c_buffer(vec![1, 2], buffer.as_mut_ptr(), 3, &mut size) };
+        assert!(result.is_ok());
+        assert_eq!(size, 2);
+        assert_eq!(buffer, [Wrapper(1), Wrapper(2), Wrapper(0)]);
+    }
+
+    #[test]
+    fn test_fill_too_small_c_buffer() {
+        let mut buffer = [Wrapper(0)];
+        let mut size = 0;
+        let result = unsafe { fill_c_buffer(vec![1, 2], buffer.as_mut_ptr(), 1, &mut size) };
+        assert!(result.is_err());
+        assert_eq!(size, 2);
+        assert_eq!(buffer, [Wrapper(0)]);
+    }
+
+    struct CountsFrees<'a>(&'a Cell<usize>);
+
+    impl FreeMembers for CountsFrees<'_> {
+        unsafe fn free_members(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn failed_conversion_frees_converted_values() {
+        let frees = Cell::new(0);
+        let result = convert_all(vec![1, 2, -1, 3], |value| match value < 0 {
+            true => Err(LoreCoreError::InputError("Negative value.".to_string())),
+            false => Ok(CountsFrees(&frees)),
+        });
+        assert!(result.is_err());
+        assert_eq!(frees.get(), 2);
+    }
+
+    #[test]
+    fn failed_conversion_leaves_buffer_untouched() {
+        let mut buffer: [*const libc::c_char; 3] = [ptr::null(); 3];
+        let mut size = 0;
+        let result = unsafe {
+            fill_c_buffer_with(
+                vec!["a", "", "b\0"],
+                buffer.as_mut_ptr(),
+                3,
+                &mut size,
+                |s| match s.contains('\0') {
+                    true => Err(LoreCoreError::InputError("Nul byte.".to_string())),
+                    false => Ok(string_to_char_pointer(s)),
+                },
+            )
+        };
+        assert!(result.is_err());
+        assert!(buffer.iter().all(|string| string.is_null()));
+    }
+
+    #[test]
+    fn test_null_array_to_slice() {
+        let slice: &[i32] = unsafe { c_array_to_slice(ptr::null(), 3) };
+        assert!(slice.is_empty());
+    }
+
     #[test]
     fn test_string_to_char_pointer() {
         let string = "Eyjafjallajökull!";
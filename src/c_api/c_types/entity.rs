@@ -1,9 +1,16 @@
 use crate::{
-    c_api::auxil::{char_pointer_to_string, string_to_char_pointer},
+    c_api::auxil::{
+        FreeMembers, c_array_to_slice, char_pointer_to_string, convert_all, free_c_array,
+        free_char_pointer, string_to_char_pointer, vec_to_c_array,
+    },
     errors::LoreCoreError,
     types::*,
 };
 
+use std::ptr;
+
+use super::{history::CHistoryItem, relationship::CEntityRelationship};
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct CEntityColumn {
@@ -12,6 +19,19 @@ pub struct CEntityColumn {
     pub description: *const libc::c_char,
 }
 
+impl FreeMembers for CEntityColumn {
+    unsafe fn free_members(&mut self) {
+        unsafe {
+            free_char_pointer(self.label);
+            free_char_pointer(self.descriptor);
+            free_char_pointer(self.description);
+        }
+        self.label = ptr::null();
+        self.descriptor = ptr::null();
+        self.description = ptr::null();
+    }
+}
+
 fn to_c_entity_column(column: &EntityColumn) -> Result<CEntityColumn, LoreCoreError> {
     Ok(CEntityColumn {
         label: string_to_char_pointer(column.label.to_str()),
@@ -44,6 +64,153 @@ impl TryFrom<&CEntityColumn> for EntityColumn {
     }
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct CEntity {
+    pub label: *const libc::c_char,
+    pub columns: *const CEntityColumn,
+    pub num_columns: isize,
+    pub parents: *const CEntityRelationship,
+    pub num_parents: isize,
+    pub children: *const CEntityRelationship,
+    pub num_children: isize,
+    pub history: *const CHistoryItem,
+    pub num_history: isize,
+}
+
+/// # Safety
+///
+/// `array` must have been created by `to_c_array` with `size` elements, or be null.
+unsafe fn free_c_array_with_members<C>(
+    array: *const C,
+    size: isize,
+    free_members: unsafe fn(&mut C),
+) {
+    if array.is_null() {
+        return;
+    }
+    for i in 0..size {
+        unsafe { free_members(&mut *(array as *mut C).offset(i)) };
+    }
+    unsafe { free_c_array(array, size) };
+}
+
+impl FreeMembers for CEntity {
+    unsafe fn free_members(&mut self) {
+        unsafe {
+            free_char_pointer(self.label);
+            free_c_array_with_members(self.columns, self.num_columns, CEntityColumn::free_members);
+            free_c_array_with_members(
+                self.parents,
+                self.num_parents,
+                CEntityRelationship::free_members,
+            );
+            free_c_array_with_members(
+                self.children,
+                self.num_children,
+                CEntityRelationship::free_members,
+            );
+            free_c_array_with_members(self.history, self.num_history, CHistoryItem::free_members);
+        }
+        self.label = ptr::null();
+        self.columns = ptr::null();
+        self.num_columns = 0;
+        self.parents = ptr::null();
+        self.num_parents = 0;
+        self.children = ptr::null();
+        self.num_children = 0;
+        self.history = ptr::null();
+        self.num_history = 0;
+    }
+}
+
+fn to_c_array<T, C>(values: Vec<T>) -> Result<(*const C, isize), LoreCoreError>
+where
+    C: TryFrom<T, Error = LoreCoreError> + FreeMembers,
+{
+    let c_values = convert_all(values, C::try_from)?;
+    let size = c_values.len() as isize;
+    Ok((vec_to_c_array(c_values), size))
+}
+
+fn to_c_entity(entity: Entity) -> Result<CEntity, LoreCoreError> {
+    let label = entity.label;
+    let columns: Vec<EntityColumn> = entity
+        .descriptors
+        .into_iter()
+        .map(|(descriptor, description)| EntityColumn {
+            label: label.clone(),
+            descriptor,
+            description,
+        })
+        .collect();
+    let mut c_entity = CEntity {
+        label: string_to_char_pointer(label.to_str()),
+        columns: ptr::null(),
+        num_columns: 0,
+        parents: ptr::null(),
+        num_parents: 0,
+        children: ptr::null(),
+        num_children: 0,
+        history: ptr::null(),
+        num_history: 0,
+    };
+    // Arrays are attached one by one, so that a failing conversion can free those built before.
+    let attach_arrays = || {
+        (c_entity.columns, c_entity.num_columns) = to_c_array(columns)?;
+        (c_entity.parents, c_entity.num_parents) = to_c_array(entity.parents)?;
+        (c_entity.children, c_entity.num_children) = to_c_array(entity.children)?;
+        (c_entity.history, c_entity.num_history) = to_c_array(entity.history)?;
+        Ok(())
+    };
+    match attach_arrays() {
+        Ok(()) => Ok(c_entity),
+        Err(e) => {
+            unsafe { c_entity.free_members() };
+            Err(e)
+        }
+    }
+}
+
+impl TryFrom<Entity> for CEntity {
+    type Error = LoreCoreError;
+
+    fn try_from(value: Entity) -> Result<Self, Self::Error> {
+        to_c_entity(value)
+    }
+}
+
+fn from_c_array<'a, C, T>(array: *const C, size: isize) -> Result<Vec<T>, LoreCoreError>
+where
+    C: 'a,
+    T: TryFrom<&'a C, Error = LoreCoreError>,
+{
+    let slice = unsafe { c_array_to_slice(array, size) };
+    slice.iter().map(T::try_from).collect()
+}
+
+fn to_entity(entity: &CEntity) -> Result<Entity, LoreCoreError> {
+    let columns: Vec<EntityColumn> = from_c_array(entity.columns, entity.num_columns)?;
+    Ok(Entity {
+        label: char_pointer_to_string(entity.label)?.into(),
+        descriptors: columns
+            .into_iter()
+            .map(|col| (col.descriptor, col.description))
+            .collect(),
+        parents: from_c_array(entity.parents, entity.num_parents)?,
+        children: from_c_array(entity.children, entity.num_children)?,
+        history: from_c_array(entity.history, entity.num_history)?,
+    })
+}
+
+impl TryFrom<&CEntity> for Entity {
+    type Error = LoreCoreError;
+
+    fn try_from(value: &CEntity) -> Result<Self, Self::Error> {
+        to_entity(value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,4 +239,51 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn entity_roundtrips() {
+        let relationship = EntityRelationship {
+            parent: "testparent".into(),
+            child: "testlabel".into(),
+            role: "testrole".into(),
+            ordinal: 2.into(),
+        };
+        let entity_before = Entity {
+            label: "testlabel".into(),
+            descriptors: vec![
+                ("testdescriptor".into(), "Test description".into()),
+                ("_protected".into(), Description::NONE),
+            ],
+            parents: vec![relationship],
+            children: Vec::new(),
+            history: vec![HistoryItem {
+                timestamp: 12.into(),
+                year: 2021.into(),
+                day: 3.into(),
+                content: "\\entityref{testlabel}".into(),
+                properties: HistoryItemProperties::none(),
+            }],
+        };
+        let c_entity = to_c_entity(entity_before.clone()).unwrap();
+        assert_eq!(c_entity.num_columns, 2);
+        assert_eq!(c_entity.num_children, 0);
+        let entity_after = to_entity(&c_entity).unwrap();
+        assert_eq!(entity_before, entity_after);
+    }
+
+    #[test]
+    fn freeing_entity_resets_members() {
+        let entity = Entity {
+            label: "testlabel".into(),
+            descriptors: vec![("testdescriptor".into(), "Test description".into())],
+            parents: Vec::new(),
+            children: Vec::new(),
+            history: Vec::new(),
+        };
+        let mut c_entity = to_c_entity(entity).unwrap();
+        unsafe { c_entity.free_members() };
+        assert!(c_entity.label.is_null());
+        assert!(c_entity.columns.is_null());
+        assert_eq!(c_entity.num_columns, 0);
+    }
 }
@@ -1,5 +1,7 @@
 use crate::{
-    c_api::auxil::{char_pointer_to_string, string_to_char_pointer},
+    c_api::auxil::{
+        FreeMembers, char_pointer_to_string, free_char_pointer, string_to_char_pointer,
+    },
     errors::LoreCoreError,
     types::*,
 };
@@ -14,6 +16,17 @@ pub struct CHistoryItem {
     pub properties: *const libc::c_char,
 }
 
+impl FreeMembers for CHistoryItem {
+    unsafe fn free_members(&mut self) {
+        unsafe {
+            free_char_pointer(self.content);
+            free_char_pointer(self.properties);
+        }
+        self.content = std::ptr::null();
+        self.properties = std::ptr::null();
+    }
+}
+
 fn to_c_history_item(item: &HistoryItem) -> Result<CHistoryItem, LoreCoreError> {
     Ok(CHistoryItem {
         timestamp: item.timestamp.to_int(),
@@ -1,10 +1,14 @@
 //! This module contains the types used in the C API.
-//! The only types for members are integers and c_char pointers.
+//! The only types for members are integers, c_char pointers and pointers to arrays of these types.
 
 pub(crate) mod entity;
 pub(crate) mod history;
 pub(crate) mod relationship;
+pub(crate) mod search_params;
 
-pub use entity::CEntityColumn;
+pub use entity::{CEntity, CEntityColumn};
 pub use history::CHistoryItem;
 pub use relationship::CEntityRelationship;
+pub use search_params::{
+    CEntityColumnSearchParams, CHistoryItemSearchParams, CRelationshipSearchParams,
+};
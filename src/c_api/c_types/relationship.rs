@@ -1,5 +1,7 @@
 use crate::{
-    c_api::auxil::{char_pointer_to_string, string_to_char_pointer},
+    c_api::auxil::{
+        FreeMembers, char_pointer_to_string, free_char_pointer, string_to_char_pointer,
+    },
     errors::LoreCoreError,
     types::*,
 };
@@ -10,6 +12,20 @@ pub struct CEntityRelationship {
     pub parent: *const libc::c_char,
     pub child: *const libc::c_char,
     pub role: *const libc::c_char,
+    pub ordinal: u32,
+}
+
+impl FreeMembers for CEntityRelationship {
+    unsafe fn free_members(&mut self) {
+        unsafe {
+            free_char_pointer(self.parent);
+            free_char_pointer(self.child);
+            free_char_pointer(self.role);
+        }
+        self.parent = std::ptr::null();
+        self.child = std::ptr::null();
+        self.role = std::ptr::null();
+    }
 }
 
 fn to_c_relationship(rel: &EntityRelationship) -> Result<CEntityRelationship, LoreCoreError> {
@@ -17,6 +33,7 @@ fn to_c_relationship(rel: &EntityRelationship) -> Result<CEntityRelationship, Lo
         parent: string_to_char_pointer(rel.parent.to_str()),
         child: string_to_char_pointer(rel.child.to_str()),
         role: string_to_char_pointer(rel.role.to_str()),
+        ordinal: rel.ordinal.to_int(),
     })
 }
 
@@ -33,6 +50,7 @@ fn to_relationship(rel: &CEntityRelationship) -> Result<EntityRelationship, Lore
         parent: char_pointer_to_string(rel.parent)?.into(),
         child: char_pointer_to_string(rel.child)?.into(),
         role: char_pointer_to_string(rel.role)?.into(),
+        ordinal: rel.ordinal.into(),
     })
 }
 
@@ -57,17 +75,21 @@ mod tests {
             Some("Test role".to_string()),
             Some("\\_\"'%$&!{[]}".to_string()),
         ];
+        let ordinals = vec![Ordinal::NONE, 3.into()];
         for parent in &parents {
             for child in &children {
                 for role in &roles {
-                    let rel_before = EntityRelationship {
-                        parent: (*parent).into(),
-                        child: (*child).into(),
-                        role: role.clone().into(),
-                    };
-                    let c_rel = to_c_relationship(&rel_before).unwrap();
-                    let rel_after = to_relationship(&c_rel).unwrap();
-                    assert_eq!(rel_before, rel_after);
+                    for ordinal in &ordinals {
+                        let rel_before = EntityRelationship {
+                            parent: (*parent).into(),
+                            child: (*child).into(),
+                            role: role.clone().into(),
+                            ordinal: *ordinal,
+                        };
+                        let c_rel = to_c_relationship(&rel_before).unwrap();
+                        let rel_after = to_relationship(&c_rel).unwrap();
+                        assert_eq!(rel_before, rel_after);
+                    }
                 }
             }
         }
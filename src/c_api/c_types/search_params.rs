@@ -0,0 +1,148 @@
+use crate::{
+    c_api::auxil::char_pointer_to_string,
+    errors::LoreCoreError,
+    sql::search_params::{
+        EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams, SqlSearchText,
+    },
+};
+
+/// A null `text` matches everything.
+#[repr(C)]
+#[derive(Clone)]
+pub struct CSqlSearchText {
+    pub text: *const libc::c_char,
+    pub is_exact: bool,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct CEntityColumnSearchParams {
+    pub label: CSqlSearchText,
+    pub descriptor: CSqlSearchText,
+}
+
+/// The `year`, `day` and `timestamp` members are only used if the corresponding `has_` flag is set.
+#[repr(C)]
+#[derive(Clone)]
+pub struct CHistoryItemSearchParams {
+    pub has_year: bool,
+    pub year: i32,
+    pub has_day: bool,
+    pub day: u32,
+    pub has_timestamp: bool,
+    pub timestamp: i64,
+    pub content: CSqlSearchText,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct CRelationshipSearchParams {
+    pub parent: CSqlSearchText,
+    pub child: CSqlSearchText,
+}
+
+fn to_search_text(text: &CSqlSearchText) -> Result<SqlSearchText, LoreCoreError> {
+    if text.text.is_null() {
+        return Ok(SqlSearchText::empty());
+    }
+    let string = char_pointer_to_string(text.text)?;
+    if text.is_exact {
+        Ok(SqlSearchText::exact(&string))
+    } else {
+        Ok(SqlSearchText::partial(&string))
+    }
+}
+
+impl TryFrom<&CSqlSearchText> for SqlSearchText {
+    type Error = LoreCoreError;
+
+    fn try_from(value: &CSqlSearchText) -> Result<Self, Self::Error> {
+        to_search_text(value)
+    }
+}
+
+impl TryFrom<&CEntityColumnSearchParams> for EntityColumnSearchParams {
+    type Error = LoreCoreError;
+
+    fn try_from(value: &CEntityColumnSearchParams) -> Result<Self, Self::Error> {
+        Ok(EntityColumnSearchParams::new(
+            Some((&value.label).try_into()?),
+            Some((&value.descriptor).try_into()?),
+        ))
+    }
+}
+
+impl TryFrom<&CHistoryItemSearchParams> for HistoryItemSearchParams {
+    type Error = LoreCoreError;
+
+    fn try_from(value: &CHistoryItemSearchParams) -> Result<Self, Self::Error> {
+        Ok(HistoryItemSearchParams::new(
+            value.has_year.then(|| value.year.into()),
+            value.has_day.then(|| value.day.into()),
+            value.has_timestamp.then(|| value.timestamp.into()),
+            Some((&value.content).try_into()?),
+        ))
+    }
+}
+
+impl TryFrom<&CRelationshipSearchParams> for RelationshipSearchParams {
+    type Error = LoreCoreError;
+
+    fn try_from(value: &CRelationshipSearchParams) -> Result<Self, Self::Error> {
+        Ok(RelationshipSearchParams::new(
+            Some((&value.parent).try_into()?),
+            Some((&value.child).try_into()?),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::c_api::auxil::{free_char_pointer, string_to_char_pointer};
+
+    #[test]
+    fn null_search_text_is_empty() {
+        let text = CSqlSearchText {
+            text: std::ptr::null(),
+            is_exact: true,
+        };
+        let text = to_search_text(&text).unwrap();
+        assert!(!text.is_some());
+    }
+
+    #[test]
+    fn search_text_keeps_exactness() {
+        for is_exact in [true, false] {
+            let c_text = CSqlSearchText {
+                text: string_to_char_pointer("test*text"),
+                is_exact,
+            };
+            let text = to_search_text(&c_text).unwrap();
+            unsafe { free_char_pointer(c_text.text) };
+            assert_eq!(text.is_exact, is_exact);
+            assert_eq!(text.exact_text(), "test*text");
+        }
+    }
+
+    #[test]
+    fn history_item_search_params_respect_flags() {
+        let empty_text = CSqlSearchText {
+            text: std::ptr::null(),
+            is_exact: false,
+        };
+        let c_params = CHistoryItemSearchParams {
+            has_year: true,
+            year: 0,
+            has_day: false,
+            day: 5,
+            has_timestamp: false,
+            timestamp: 7,
+            content: empty_text,
+        };
+        let params: HistoryItemSearchParams = (&c_params).try_into().unwrap();
+        assert_eq!(params.year, Some(0.into()));
+        assert_eq!(params.day, None);
+        assert_eq!(params.timestamp, None);
+    }
+}
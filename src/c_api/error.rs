@@ -0,0 +1,96 @@
+use std::{cell::RefCell, ffi::CString};
+
+use crate::errors::LoreCoreError;
+
+/// Returned by all fallible C api functions.
+/// The message of the last error is available through `lore_last_error`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoreErrorCode {
+    Ok = 0,
+    FileError = 1,
+    InputError = 2,
+    SqlError = 3,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+impl From<&LoreCoreError> for LoreErrorCode {
+    fn from(value: &LoreCoreError) -> Self {
+        match value {
+            LoreCoreError::FileError(_) => LoreErrorCode::FileError,
+            LoreCoreError::InputError(_) => LoreErrorCode::InputError,
+            LoreCoreError::SqlError(_) => LoreErrorCode::SqlError,
+        }
+    }
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+}
+
+/// Records the outcome of a C api call for `lore_last_error` and returns its error code.
+pub(super) fn to_error_code(result: Result<(), LoreCoreError>) -> LoreErrorCode {
+    match result {
+        Ok(()) => {
+            set_last_error("");
+            LoreErrorCode::Ok
+        }
+        Err(e) => {
+            let message = match &e {
+                LoreCoreError::FileError(message)
+                | LoreCoreError::InputError(message)
+                | LoreCoreError::SqlError(message) => message,
+            };
+            set_last_error(message);
+            (&e).into()
+        }
+    }
+}
+
+/// Returns the message of the error reported by the last C api call on this thread, or an empty
+/// string if that call succeeded.
+/// The string is owned by the library. It stays valid until the next C api call on this thread and
+/// must not be freed.
+#[unsafe(no_mangle)]
+pub extern "C" fn lore_last_error() -> *const libc::c_char {
+    LAST_ERROR.with(|last_error| last_error.borrow().as_ptr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(lore_last_error()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn error_kind_and_message_are_recorded() {
+        let code = to_error_code(Err(LoreCoreError::InputError("bad input".to_string())));
+        assert_eq!(code, LoreErrorCode::InputError);
+        assert_eq!(last_error(), "bad input");
+    }
+
+    #[test]
+    fn success_clears_the_last_error() {
+        to_error_code(Err(LoreCoreError::SqlError("bad query".to_string())));
+        assert_eq!(to_error_code(Ok(())), LoreErrorCode::Ok);
+        assert_eq!(last_error(), "");
+    }
+
+    #[test]
+    fn last_error_is_thread_local() {
+        to_error_code(Err(LoreCoreError::FileError("bad file".to_string())));
+        let other_thread = std::thread::spawn(last_error).join().unwrap();
+        assert_eq!(other_thread, "");
+        assert_eq!(last_error(), "bad file");
+    }
+}
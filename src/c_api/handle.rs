@@ -0,0 +1,20 @@
+use crate::{errors::LoreCoreError, sql::lore_database::LoreDatabase};
+
+/// An open lore database. C clients only ever see pointers to it.
+pub struct LoreDatabaseHandle {
+    pub(super) db: LoreDatabase,
+}
+
+/// # Safety
+///
+/// `handle` must be a pointer returned by `lore_open` that has not been closed yet, or null.
+pub(super) unsafe fn handle_to_database<'a>(
+    handle: *const LoreDatabaseHandle,
+) -> Result<&'a LoreDatabase, LoreCoreError> {
+    if handle.is_null() {
+        return Err(LoreCoreError::InputError(
+            "Database handle is null.".to_string(),
+        ));
+    }
+    Ok(unsafe { &(*handle).db })
+}
@@ -1,5 +1,8 @@
 pub mod api;
 mod auxil;
 mod c_types;
+pub mod error;
+pub mod handle;
 mod read_database;
+mod update_database;
 mod write_database;
@@ -7,43 +7,52 @@ use crate::{
             EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams,
         },
     },
+    store::LoreStore,
+    types::*,
 };
 
 pub(super) fn c_read_entity_columns(
-    db_path: *const libc::c_char,
-) -> Result<Vec<CEntityColumn>, LoreCoreError> {
-    let db_path = char_pointer_to_string(db_path)?;
-    let db = LoreDatabase::open(db_path.into())?;
-    let mut columns = Vec::new();
-    let database_entity_columns = db.read_entity_columns(EntityColumnSearchParams::empty())?;
-    for col in database_entity_columns {
-        columns.push(col.try_into()?);
+    db: &LoreDatabase,
+    params: Option<&CEntityColumnSearchParams>,
+) -> Result<Vec<EntityColumn>, LoreCoreError> {
+    let params = match params {
+        Some(params) => params.try_into()?,
+        None => EntityColumnSearchParams::empty(),
+    };
+    db.read_entity_columns(params)
+}
+
+pub(super) fn c_read_entity(
+    db: &LoreDatabase,
+    label: *const libc::c_char,
+) -> Result<CEntity, LoreCoreError> {
+    let label = char_pointer_to_string(label)?;
+    match db.read_entity(&label.as_str().into())? {
+        Some(entity) => entity.try_into(),
+        None => Err(LoreCoreError::InputError(
+            "No entity with label \"".to_string() + &label + "\" found.",
+        )),
     }
-    Ok(columns)
 }
 
 pub(super) fn c_read_history_items(
-    db_path: *const libc::c_char,
-) -> Result<Vec<CHistoryItem>, LoreCoreError> {
-    let db_path = char_pointer_to_string(db_path)?;
-    let db = LoreDatabase::open(db_path.into())?;
-    let mut items = Vec::new();
-    let history_columns = db.read_history_items(HistoryItemSearchParams::empty())?;
-    for col in history_columns {
-        items.push(col.try_into()?);
-    }
-    Ok(items)
+    db: &LoreDatabase,
+    params: Option<&CHistoryItemSearchParams>,
+) -> Result<Vec<HistoryItem>, LoreCoreError> {
+    let params = match params {
+        Some(params) => params.try_into()?,
+        None => HistoryItemSearchParams::empty(),
+    };
+    db.read_history_items(params)
 }
 
 pub(super) fn c_read_relationships(
-    db_path: *const libc::c_char,
-) -> Result<Vec<CEntityRelationship>, LoreCoreError> {
-    let db_path = char_pointer_to_string(db_path)?;
-    let db = LoreDatabase::open(db_path.into())?;
-    let mut relationships = Vec::new();
-    let relationship_columns = db.read_relationships(RelationshipSearchParams::empty())?;
-    for col in relationship_columns {
-        relationships.push(col.try_into()?);
-    }
-    Ok(relationships)
+    db: &LoreDatabase,
+    params: Option<&CRelationshipSearchParams>,
+) -> Result<Vec<EntityRelationship>, LoreCoreError> {
+    let params = match params {
+        Some(params) => params.try_into()?,
+        None => RelationshipSearchParams::empty(),
+    };
+    db.read_relationships(params)
 }
@@ -0,0 +1,115 @@
+use super::{auxil::char_pointer_to_string, c_types::*};
+use crate::{errors::LoreCoreError, sql::lore_database::LoreDatabase, types::*};
+
+pub(super) fn c_relabel_entity(
+    db: &LoreDatabase,
+    old_label: *const libc::c_char,
+    new_label: *const libc::c_char,
+) -> Result<(), LoreCoreError> {
+    let old_label: Label = char_pointer_to_string(old_label)?.into();
+    let new_label: Label = char_pointer_to_string(new_label)?.into();
+    db.relabel_entity(&old_label, &new_label)
+}
+
+pub(super) fn c_delete_entity(
+    db: &LoreDatabase,
+    label: *const libc::c_char,
+) -> Result<(), LoreCoreError> {
+    db.delete_entity(char_pointer_to_string(label)?.into())
+}
+
+pub(super) fn c_change_entity_descriptor(
+    db: &LoreDatabase,
+    label: *const libc::c_char,
+    old_descriptor: *const libc::c_char,
+    new_descriptor: *const libc::c_char,
+) -> Result<(), LoreCoreError> {
+    let label: Label = char_pointer_to_string(label)?.into();
+    let old_descriptor: Descriptor = char_pointer_to_string(old_descriptor)?.into();
+    let new_descriptor: Descriptor = char_pointer_to_string(new_descriptor)?.into();
+    db.change_entity_descriptor((&label, old_descriptor), &new_descriptor)
+}
+
+pub(super) fn c_delete_entity_column(
+    db: &LoreDatabase,
+    label: *const libc::c_char,
+    descriptor: *const libc::c_char,
+) -> Result<(), LoreCoreError> {
+    let label: Label = char_pointer_to_string(label)?.into();
+    let descriptor: Descriptor = char_pointer_to_string(descriptor)?.into();
+    db.delete_entity_column((label, descriptor))
+}
+
+pub(super) fn c_change_entity_description(
+    db: &LoreDatabase,
+    label: *const libc::c_char,
+    descriptor: *const libc::c_char,
+    new_description: *const libc::c_char,
+) -> Result<(), LoreCoreError> {
+    let label: Label = char_pointer_to_string(label)?.into();
+    let descriptor: Descriptor = char_pointer_to_string(descriptor)?.into();
+    let new_description: Description = char_pointer_to_string(new_description)?.into();
+    db.change_entity_description((&label, &descriptor), &new_description)
+}
+
+pub(super) fn c_redate_history_item(
+    db: &LoreDatabase,
+    timestamp: i64,
+    year: i32,
+    day: u32,
+) -> Result<(), LoreCoreError> {
+    db.redate_history_item(timestamp.into(), year.into(), day.into())
+}
+
+pub(super) fn c_delete_history_item(
+    db: &LoreDatabase,
+    timestamp: i64,
+) -> Result<(), LoreCoreError> {
+    db.delete_history_item(timestamp.into())
+}
+
+pub(super) fn c_change_history_item_content(
+    db: &LoreDatabase,
+    timestamp: i64,
+    content: *const libc::c_char,
+) -> Result<(), LoreCoreError> {
+    let content: HistoryItemContent = char_pointer_to_string(content)?.into();
+    db.change_history_item_content(timestamp.into(), &content)
+}
+
+pub(super) fn c_change_history_item_properties(
+    db: &LoreDatabase,
+    timestamp: i64,
+    properties: *const libc::c_char,
+) -> Result<(), LoreCoreError> {
+    let properties: HistoryItemProperties = (&char_pointer_to_string(properties)?).into();
+    db.change_history_item_properties(timestamp.into(), &properties)
+}
+
+pub(super) fn c_change_relationship_role(
+    db: &LoreDatabase,
+    relationship: *const CEntityRelationship,
+    new_role: *const libc::c_char,
+) -> Result<(), LoreCoreError> {
+    let relationship = relationship_from_pointer(relationship)?;
+    let new_role: Role = char_pointer_to_string(new_role)?.into();
+    db.change_relationship_role(relationship, &new_role)
+}
+
+pub(super) fn c_delete_relationship(
+    db: &LoreDatabase,
+    relationship: *const CEntityRelationship,
+) -> Result<(), LoreCoreError> {
+    db.delete_relationship(relationship_from_pointer(relationship)?)
+}
+
+fn relationship_from_pointer(
+    relationship: *const CEntityRelationship,
+) -> Result<EntityRelationship, LoreCoreError> {
+    match unsafe { relationship.as_ref() } {
+        Some(relationship) => relationship.try_into(),
+        None => Err(LoreCoreError::InputError(
+            "Relationship pointer is null.".to_string(),
+        )),
+    }
+}
@@ -1,39 +1,35 @@
-use super::{auxil::char_pointer_to_string, c_types::*};
-use crate::{errors::LoreCoreError, sql::lore_database::LoreDatabase};
-use std::path::PathBuf;
+use super::c_types::*;
+use crate::{errors::LoreCoreError, sql::lore_database::LoreDatabase, types::*};
 
-pub(super) fn c_write_entity_column(
-    db_path: *const libc::c_char,
-    column: &CEntityColumn,
+pub(super) fn c_write_entity_columns(
+    db: &LoreDatabase,
+    columns: &[CEntityColumn],
 ) -> Result<(), LoreCoreError> {
-    let db_path = char_pointer_to_string(db_path)?;
-    let db_path = PathBuf::from(db_path);
-    let column = column.try_into()?;
-    let db = LoreDatabase::open(db_path)?;
-    db.write_entity_columns(vec![column])?;
-    Ok(())
+    let columns = columns
+        .iter()
+        .map(EntityColumn::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    db.write_entity_columns(columns)
 }
 
-pub(super) fn c_write_history_item(
-    db_path: *const libc::c_char,
-    item: &CHistoryItem,
+pub(super) fn c_write_history_items(
+    db: &LoreDatabase,
+    items: &[CHistoryItem],
 ) -> Result<(), LoreCoreError> {
-    let db_path = char_pointer_to_string(db_path)?;
-    let db_path = PathBuf::from(db_path);
-    let item = item.try_into()?;
-    let db = LoreDatabase::open(db_path)?;
-    db.write_history_items(vec![item])?;
-    Ok(())
+    let items = items
+        .iter()
+        .map(HistoryItem::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    db.write_history_items(items)
 }
 
-pub(super) fn c_write_relationship(
-    db_path: *const libc::c_char,
-    rel: &CEntityRelationship,
+pub(super) fn c_write_relationships(
+    db: &LoreDatabase,
+    rels: &[CEntityRelationship],
 ) -> Result<(), LoreCoreError> {
-    let db_path = char_pointer_to_string(db_path)?;
-    let db_path = PathBuf::from(db_path);
-    let relationship = rel.try_into()?;
-    let db = LoreDatabase::open(db_path)?;
-    db.write_relationships(vec![relationship])?;
-    Ok(())
+    let relationships = rels
+        .iter()
+        .map(EntityRelationship::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    db.write_relationships(relationships)
 }
@@ -0,0 +1,537 @@
+//! Import and export of single tables as CSV or TSV, for example to exchange entity lists with
+//! spreadsheets.
+//!
+//! Every table has a fixed set of fields, which are also the default column headers:
+//! - entity columns: `label`, `descriptor`, `description`
+//! - history items: `timestamp`, `year`, `day`, `content`, `properties`
+//! - relationships: `parent`, `child`, `role`, `ordinal`
+//!
+//! History item properties are written as JSON. On import, columns may appear in any order and
+//! unknown columns are ignored. Only the fields forming the primary key (and the content of history
+//! items) are required; a missing timestamp is replaced by `try_current_timestamp`, which fails on
+//! targets without a system clock.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use serde_json::Value;
+
+use crate::{
+    errors::LoreCoreError,
+    sql::search_params::{
+        EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams,
+    },
+    store::LoreStore,
+    timestamp::try_current_timestamp,
+    types::*,
+};
+
+const ENTITY_COLUMN_FIELDS: [&str; 3] = ["label", "descriptor", "description"];
+const ENTITY_COLUMN_REQUIRED: [&str; 2] = ["label", "descriptor"];
+const HISTORY_ITEM_FIELDS: [&str; 5] = ["timestamp", "year", "day", "content", "properties"];
+const HISTORY_ITEM_REQUIRED: [&str; 2] = ["year", "content"];
+const RELATIONSHIP_FIELDS: [&str; 4] = ["parent", "child", "role", "ordinal"];
+const RELATIONSHIP_REQUIRED: [&str; 2] = ["parent", "child"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delimiter {
+    Comma,
+    Tab,
+}
+
+impl Delimiter {
+    fn to_byte(self) -> u8 {
+        match self {
+            Delimiter::Comma => b',',
+            Delimiter::Tab => b'\t',
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DelimitedParams {
+    pub(crate) delimiter: Delimiter,
+    pub(crate) has_headers: Option<bool>,
+    pub(crate) column_names: Vec<(String, String)>,
+}
+
+impl DelimitedParams {
+    /// `has_headers` states whether the first row holds column headers. If it is `None`, the first
+    /// row is taken as headers if it names all required fields.
+    /// `column_names` maps field names to the headers used instead of them. Without headers, the
+    /// columns are expected in the order of the fields.
+    pub fn new(
+        delimiter: Delimiter,
+        has_headers: Option<bool>,
+        column_names: Vec<(String, String)>,
+    ) -> Self {
+        Self {
+            delimiter,
+            has_headers,
+            column_names,
+        }
+    }
+
+    pub fn csv() -> Self {
+        Self::new(Delimiter::Comma, None, Vec::new())
+    }
+
+    pub fn tsv() -> Self {
+        Self::new(Delimiter::Tab, None, Vec::new())
+    }
+
+    fn column_name<'a>(&'a self, field: &'a str) -> &'a str {
+        self.column_names
+            .iter()
+            .find(|(f, _)| f == field)
+            .map(|(_, name)| name.as_str())
+            .unwrap_or(field)
+    }
+}
+
+/// A row that could not be imported, because its primary key is already taken.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowConflict {
+    /// The 1-based number of the row, counting the header row.
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// The number of rows holding a record.
+    pub rows: usize,
+    pub conflicts: Vec<RowConflict>,
+}
+
+struct Row {
+    number: usize,
+    values: HashMap<&'static str, String>,
+}
+
+impl Row {
+    fn get(&self, field: &str) -> &str {
+        self.values.get(field).map(|v| v.as_str()).unwrap_or("")
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> LoreCoreError {
+        LoreCoreError::InputError(format!("Row {}: {}", self.number, message))
+    }
+}
+
+pub fn export_entity_columns(
+    store: &impl LoreStore,
+    params: &DelimitedParams,
+) -> Result<String, LoreCoreError> {
+    let rows = store
+        .read_entity_columns(EntityColumnSearchParams::empty())?
+        .into_iter()
+        .map(|col| vec![col.label.0, col.descriptor.0, col.description.0])
+        .collect();
+    write_rows(params, &ENTITY_COLUMN_FIELDS, rows)
+}
+
+pub fn export_history_items(
+    store: &impl LoreStore,
+    params: &DelimitedParams,
+) -> Result<String, LoreCoreError> {
+    let mut rows = Vec::new();
+    for item in store.read_history_items(HistoryItemSearchParams::empty())? {
+        let properties = if item.properties.0.is_empty() {
+            String::new()
+        } else {
+            let sorted: BTreeMap<_, _> = item.properties.0.iter().collect();
+            serde_json::to_string(&sorted).map_err(|e| {
+                LoreCoreError::InputError(format!(
+                    "Failed to serialize properties of history item {}: {}",
+                    item.timestamp, e
+                ))
+            })?
+        };
+        rows.push(vec![
+            item.timestamp.to_string(),
+            item.year.to_string(),
+            item.day.to_string(),
+            item.content.0,
+            properties,
+        ]);
+    }
+    write_rows(params, &HISTORY_ITEM_FIELDS, rows)
+}
+
+pub fn export_relationships(
+    store: &impl LoreStore,
+    params: &DelimitedParams,
+) -> Result<String, LoreCoreError> {
+    let rows = store
+        .read_relationships(RelationshipSearchParams::empty())?
+        .into_iter()
+        .map(|rel| {
+            let ordinal = rel
+                .ordinal
+                .to_optional_int()
+                .map(|ordinal| ordinal.to_string())
+                .unwrap_or_default();
+            vec![rel.parent.0, rel.child.0, rel.role.0, ordinal]
+        })
+        .collect();
+    write_rows(params, &RELATIONSHIP_FIELDS, rows)
+}
+
+/// Writes the entity columns in `text` into `store`, unless `dry_run` is set.
+/// If any row conflicts with an existing column or an earlier row, nothing is written and an error
+/// is returned; in a dry run, the conflicts are listed in the report instead.
+pub fn import_entity_columns(
+    store: &impl LoreStore,
+    text: &str,
+    params: &DelimitedParams,
+    dry_run: bool,
+) -> Result<ImportReport, LoreCoreError> {
+    let mut cols = Vec::new();
+    for row in read_rows(text, params, &ENTITY_COLUMN_FIELDS, &ENTITY_COLUMN_REQUIRED)? {
+        let col = EntityColumn {
+            label: row.get("label").into(),
+            descriptor: row.get("descriptor").into(),
+            description: row.get("description").into(),
+        };
+        cols.push((row.number, col));
+    }
+    let existing = store
+        .read_entity_columns(EntityColumnSearchParams::empty())?
+        .into_iter()
+        .map(|col| (col.label, col.descriptor))
+        .collect();
+    import_records(
+        cols,
+        existing,
+        |col| (col.label.clone(), col.descriptor.clone()),
+        |col| {
+            format!(
+                "Entity \"{}\" with descriptor \"{}\"",
+                col.label, col.descriptor
+            )
+        },
+        dry_run,
+        |cols| store.write_entity_columns(cols),
+        store,
+    )
+}
+
+/// Writes the history items in `text` into `store`, unless `dry_run` is set.
+/// Conflicts are handled like in `import_entity_columns`.
+pub fn import_history_items(
+    store: &impl LoreStore,
+    text: &str,
+    params: &DelimitedParams,
+    dry_run: bool,
+) -> Result<ImportReport, LoreCoreError> {
+    let mut items = Vec::new();
+    for row in read_rows(text, params, &HISTORY_ITEM_FIELDS, &HISTORY_ITEM_REQUIRED)? {
+        let timestamp = match row.get("timestamp").trim() {
+            "" => try_current_timestamp()?,
+            timestamp => timestamp.parse::<i64>().map(Timestamp::from).map_err(|_| {
+                row.error(format!("Unable to parse \"{}\" as timestamp", timestamp))
+            })?,
+        };
+        let year = Year::try_from(row.get("year").trim()).map_err(|e| row.error(e))?;
+        let day = match row.get("day").trim() {
+            "" => Day::NONE,
+            day => Day::try_from(day).map_err(|e| row.error(e))?,
+        };
+        let properties = match row.get("properties").trim() {
+            "" => HistoryItemProperties::none(),
+            properties => serde_json::from_str::<HashMap<String, Value>>(properties)
+                .map(HistoryItemProperties::from)
+                .map_err(|e| row.error(format!("Unable to parse properties: {}", e)))?,
+        };
+        let item = HistoryItem {
+            timestamp,
+            year,
+            day,
+            content: row.get("content").into(),
+            properties,
+        };
+        items.push((row.number, item));
+    }
+    let existing = store
+        .read_history_items(HistoryItemSearchParams::empty())?
+        .into_iter()
+        .map(|item| item.timestamp)
+        .collect();
+    import_records(
+        items,
+        existing,
+        |item| item.timestamp,
+        |item| format!("History item with timestamp {}", item.timestamp),
+        dry_run,
+        |items| store.write_history_items(items),
+        store,
+    )
+}
+
+/// Writes the relationships in `text` into `store`, unless `dry_run` is set.
+/// Conflicts are handled like in `import_entity_columns`.
+pub fn import_relationships(
+    store: &impl LoreStore,
+    text: &str,
+    params: &DelimitedParams,
+    dry_run: bool,
+) -> Result<ImportReport, LoreCoreError> {
+    let mut rels = Vec::new();
+    for row in read_rows(text, params, &RELATIONSHIP_FIELDS, &RELATIONSHIP_REQUIRED)? {
+        let ordinal = match row.get("ordinal").trim() {
+            "" => Ordinal::NONE,
+            ordinal => ordinal
+                .parse::<u32>()
+                .map(Ordinal::from)
+                .map_err(|_| row.error(format!("Unable to parse \"{}\" as ordinal", ordinal)))?,
+        };
+        let rel = EntityRelationship {
+            parent: row.get("parent").into(),
+            child: row.get("child").into(),
+            role: row.get("role").into(),
+            ordinal,
+        };
+        rels.push((row.number, rel));
+    }
+    let existing = store
+        .read_relationships(RelationshipSearchParams::empty())?
+        .into_iter()
+        .map(|rel| (rel.parent, rel.child, rel.role))
+        .collect();
+    import_records(
+        rels,
+        existing,
+        |rel| (rel.parent.clone(), rel.child.clone(), rel.role.clone()),
+        |rel| {
+            format!(
+                "Relationship between \"{}\" and \"{}\" with role \"{}\"",
+                rel.parent, rel.child, rel.role
+            )
+        },
+        dry_run,
+        |rels| store.write_relationships(rels),
+        store,
+    )
+}
+
+fn import_records<T, K: Ord>(
+    records: Vec<(usize, T)>,
+    existing: BTreeSet<K>,
+    key: impl Fn(&T) -> K,
+    describe: impl Fn(&T) -> String,
+    dry_run: bool,
+    write: impl FnOnce(Vec<T>) -> Result<(), LoreCoreError>,
+    store: &impl LoreStore,
+) -> Result<ImportReport, LoreCoreError> {
+    let mut report = ImportReport {
+        rows: records.len(),
+        conflicts: Vec::new(),
+    };
+    let mut seen: BTreeMap<K, usize> = BTreeMap::new();
+    for (row, record) in records.iter() {
+        let record_key = key(record);
+        if existing.contains(&record_key) {
+            report.conflicts.push(RowConflict {
+                row: *row,
+                message: format!("{} already exists.", describe(record)),
+            });
+        } else if let Some(first) = seen.get(&record_key) {
+            report.conflicts.push(RowConflict {
+                row: *row,
+                message: format!("{} is already defined in row {}.", describe(record), first),
+            });
+        } else {
+            seen.insert(record_key, *row);
+        }
+    }
+    if dry_run {
+        return Ok(report);
+    }
+    if let Some(conflict) = report.conflicts.first() {
+        return Err(LoreCoreError::InputError(format!(
+            "{} rows conflict with existing records, nothing was imported. Row {}: {}",
+            report.conflicts.len(),
+            conflict.row,
+            conflict.message
+        )));
+    }
+
+    store.in_transaction(|| write(records.into_iter().map(|(_, record)| record).collect()))?;
+    Ok(report)
+}
+
+fn read_rows(
+    text: &str,
+    params: &DelimitedParams,
+    fields: &[&'static str],
+    required: &[&str],
+) -> Result<Vec<Row>, LoreCoreError> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(params.delimiter.to_byte())
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut records = Vec::new();
+    for record in reader.records() {
+        records.push(record.map_err(|e| {
+            LoreCoreError::InputError("Failed to parse table: ".to_string() + &e.to_string())
+        })?);
+    }
+    let Some(first) = records.first() else {
+        return Ok(Vec::new());
+    };
+
+    let header_index = |field: &str| {
+        let name = params.column_name(field);
+        first
+            .iter()
+            .position(|cell| cell.trim().eq_ignore_ascii_case(name))
+    };
+    let has_headers = params
+        .has_headers
+        .unwrap_or_else(|| required.iter().all(|field| header_index(field).is_some()));
+    let mut positions = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        if has_headers {
+            match header_index(field) {
+                Some(position) => positions.push((*field, position)),
+                None if required.contains(field) => {
+                    return Err(LoreCoreError::InputError(format!(
+                        "The table has no column \"{}\".",
+                        params.column_name(field)
+                    )));
+                }
+                None => {}
+            }
+        } else {
+            positions.push((*field, i));
+        }
+    }
+
+    let skipped = if has_headers { 1 } else { 0 };
+    let rows = records
+        .iter()
+        .enumerate()
+        .skip(skipped)
+        .filter(|(_, record)| !is_blank(record))
+        .map(|(i, record)| Row {
+            number: i + 1,
+            values: positions
+                .iter()
+                .filter_map(|(field, position)| {
+                    record
+                        .get(*position)
+                        .map(|value| (*field, value.to_string()))
+                })
+                .collect(),
+        })
+        .collect();
+    Ok(rows)
+}
+
+fn is_blank(record: &StringRecord) -> bool {
+    record.iter().all(|cell| cell.trim().is_empty())
+}
+
+fn write_rows(
+    params: &DelimitedParams,
+    fields: &[&str],
+    rows: Vec<Vec<String>>,
+) -> Result<String, LoreCoreError> {
+    let writing_error = |e: csv::Error| {
+        LoreCoreError::InputError("Failed to write table: ".to_string() + &e.to_string())
+    };
+    let mut writer = WriterBuilder::new()
+        .delimiter(params.delimiter.to_byte())
+        .from_writer(Vec::new());
+    writer
+        .write_record(fields.iter().map(|field| params.column_name(field)))
+        .map_err(writing_error)?;
+    for row in rows {
+        writer.write_record(&row).map_err(writing_error)?;
+    }
+    let bytes = writer.into_inner().map_err(|e| {
+        LoreCoreError::InputError("Failed to write table: ".to_string() + &e.to_string())
+    })?;
+    String::from_utf8(bytes).map_err(|e| {
+        LoreCoreError::InputError("Failed to write table: ".to_string() + &e.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headers_are_detected_by_the_required_fields() {
+        let params = DelimitedParams::csv();
+        let rows = read_rows(
+            "Descriptor,Label,Notes\nAge,Hero,x\n",
+            &params,
+            &ENTITY_COLUMN_FIELDS,
+            &ENTITY_COLUMN_REQUIRED,
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].number, 2);
+        assert_eq!(rows[0].get("label"), "Hero");
+        assert_eq!(rows[0].get("descriptor"), "Age");
+        assert_eq!(rows[0].get("description"), "");
+
+        let rows = read_rows(
+            "Hero,Age,Young\n\n,,\nVillain,Age,Old\n",
+            &params,
+            &ENTITY_COLUMN_FIELDS,
+            &ENTITY_COLUMN_REQUIRED,
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].number, 1);
+        assert_eq!(rows[1].number, 3);
+        assert_eq!(rows[1].get("description"), "Old");
+    }
+
+    #[test]
+    fn columns_can_be_renamed() {
+        let params = DelimitedParams::new(
+            Delimiter::Tab,
+            Some(true),
+            vec![("label".to_string(), "Name".to_string())],
+        );
+        let rows = read_rows(
+            "Name\tdescriptor\nHero\tAge\n",
+            &params,
+            &ENTITY_COLUMN_FIELDS,
+            &ENTITY_COLUMN_REQUIRED,
+        )
+        .unwrap();
+        assert_eq!(rows[0].get("label"), "Hero");
+
+        let missing = read_rows(
+            "label\tdescriptor\nHero\tAge\n",
+            &params,
+            &ENTITY_COLUMN_FIELDS,
+            &ENTITY_COLUMN_REQUIRED,
+        );
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn multi_line_cells_are_quoted() {
+        let text = write_rows(
+            &DelimitedParams::csv(),
+            &ENTITY_COLUMN_FIELDS,
+            vec![vec![
+                "Hero".into(),
+                "Bio".into(),
+                "Line 1\nLine \"2\"".into(),
+            ]],
+        )
+        .unwrap();
+        assert_eq!(
+            text,
+            "label,descriptor,description\nHero,Bio,\"Line 1\nLine \"\"2\"\"\"\n"
+        );
+    }
+}
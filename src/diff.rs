@@ -0,0 +1,308 @@
+//! Comparison of the contents of two lore stores.
+//!
+//! Records are matched by their primary key: entity columns by label and descriptor, history
+//! items by timestamp and relationships by parent, child and role. A record whose key exists in
+//! both stores, but whose other fields differ, is reported as modified.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use serde::Serialize;
+
+use crate::{
+    errors::LoreCoreError,
+    json::{JsonEntityColumn, JsonHistoryItem, JsonRelationship, import_json},
+    memory::MemoryDatabase,
+    sql::search_params::{
+        EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams,
+    },
+    store::LoreStore,
+    types::*,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change<T> {
+    Added(T),
+    Removed(T),
+    Modified { old: T, new: T },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoreDiff {
+    pub entity_columns: Vec<Change<EntityColumn>>,
+    pub history_items: Vec<Change<HistoryItem>>,
+    pub relationships: Vec<Change<EntityRelationship>>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "change", rename_all = "lowercase")]
+enum JsonChange<T> {
+    Added { new: T },
+    Removed { old: T },
+    Modified { old: T, new: T },
+}
+
+#[derive(Serialize)]
+struct JsonDiff {
+    entity_columns: Vec<JsonChange<JsonEntityColumn>>,
+    history_items: Vec<JsonChange<JsonHistoryItem>>,
+    relationships: Vec<JsonChange<JsonRelationship>>,
+}
+
+impl<T> Change<T> {
+    /// The record before the change, `None` if it was added.
+    pub fn old_record(&self) -> Option<&T> {
+        match self {
+            Change::Added(_) => None,
+            Change::Removed(old) | Change::Modified { old, .. } => Some(old),
+        }
+    }
+
+    /// The record after the change, `None` if it was removed.
+    pub fn new_record(&self) -> Option<&T> {
+        match self {
+            Change::Removed(_) => None,
+            Change::Added(new) | Change::Modified { new, .. } => Some(new),
+        }
+    }
+}
+
+impl<T: Clone> Change<T> {
+    fn to_json<J: From<T>>(&self) -> JsonChange<J> {
+        match self {
+            Change::Added(new) => JsonChange::Added {
+                new: new.clone().into(),
+            },
+            Change::Removed(old) => JsonChange::Removed {
+                old: old.clone().into(),
+            },
+            Change::Modified { old, new } => JsonChange::Modified {
+                old: old.clone().into(),
+                new: new.clone().into(),
+            },
+        }
+    }
+}
+
+impl LoreDiff {
+    pub fn is_empty(&self) -> bool {
+        self.entity_columns.is_empty()
+            && self.history_items.is_empty()
+            && self.relationships.is_empty()
+    }
+
+    /// Renders the changes as a pretty-printed JSON document. Every change has a `change` field
+    /// stating whether it was `added`, `removed` or `modified`, and the `old` and `new` record in
+    /// the format of `json::export_json`.
+    pub fn to_json(&self) -> Result<String, LoreCoreError> {
+        let diff = JsonDiff {
+            entity_columns: self.entity_columns.iter().map(Change::to_json).collect(),
+            history_items: self.history_items.iter().map(Change::to_json).collect(),
+            relationships: self.relationships.iter().map(Change::to_json).collect(),
+        };
+        serde_json::to_string_pretty(&diff).map_err(|e| {
+            LoreCoreError::InputError(
+                "Failed to serialize diff to JSON: ".to_string() + &e.to_string(),
+            )
+        })
+    }
+}
+
+/// One line per change, prefixed by `+`, `-` or `~` for added, removed and modified records.
+impl Display for LoreDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No differences.");
+        }
+        for change in &self.entity_columns {
+            match change {
+                Change::Added(col) => writeln!(f, "+ {}", describe_column(col))?,
+                Change::Removed(col) => writeln!(f, "- {}", describe_column(col))?,
+                Change::Modified { old, new } => writeln!(
+                    f,
+                    "~ entity \"{}\", descriptor \"{}\": {:?} -> {:?}",
+                    old.label,
+                    old.descriptor,
+                    old.description.to_str(),
+                    new.description.to_str()
+                )?,
+            }
+        }
+        for change in &self.history_items {
+            match change {
+                Change::Added(item) => writeln!(f, "+ {}", describe_item(item))?,
+                Change::Removed(item) => writeln!(f, "- {}", describe_item(item))?,
+                Change::Modified { old, new } => {
+                    let mut fields = Vec::new();
+                    if old.year != new.year {
+                        fields.push(format!("year {} -> {}", old.year, new.year));
+                    }
+                    if old.day != new.day {
+                        fields.push(format!(
+                            "day {:?} -> {:?}",
+                            old.day.to_optional_int(),
+                            new.day.to_optional_int()
+                        ));
+                    }
+                    if old.content != new.content {
+                        fields.push(format!(
+                            "content {:?} -> {:?}",
+                            old.content.to_str(),
+                            new.content.to_str()
+                        ));
+                    }
+                    if old.properties != new.properties {
+                        fields.push(format!(
+                            "properties {} -> {}",
+                            sorted_properties(&old.properties),
+                            sorted_properties(&new.properties)
+                        ));
+                    }
+                    writeln!(f, "~ history item {}: {}", old.timestamp, fields.join(", "))?
+                }
+            }
+        }
+        for change in &self.relationships {
+            match change {
+                Change::Added(rel) => writeln!(f, "+ {}", describe_relationship(rel))?,
+                Change::Removed(rel) => writeln!(f, "- {}", describe_relationship(rel))?,
+                Change::Modified { old, new } => writeln!(
+                    f,
+                    "~ {}: ordinal {:?} -> {:?}",
+                    describe_relationship(old),
+                    old.ordinal.to_optional_int(),
+                    new.ordinal.to_optional_int()
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn describe_column(col: &EntityColumn) -> String {
+    format!(
+        "entity \"{}\", descriptor \"{}\": {:?}",
+        col.label,
+        col.descriptor,
+        col.description.to_str()
+    )
+}
+
+fn describe_item(item: &HistoryItem) -> String {
+    let mut description = format!("history item {} in year {}", item.timestamp, item.year);
+    if let Some(day) = item.day.to_optional_int() {
+        description += &format!(", day {}", day);
+    }
+    description + &format!(": {:?}", item.content.to_str())
+}
+
+fn describe_relationship(rel: &EntityRelationship) -> String {
+    let mut description = format!("relationship \"{}\" -> \"{}\"", rel.parent, rel.child);
+    if let Some(role) = rel.role.to_optional_string() {
+        description += &format!(" ({})", role);
+    }
+    description
+}
+
+/// The properties as JSON with sorted keys, so that the output does not depend on hash order.
+fn sorted_properties(properties: &HistoryItemProperties) -> String {
+    let sorted: BTreeMap<_, _> = properties.to_map().iter().collect();
+    serde_json::to_string(&sorted).unwrap_or_default()
+}
+
+pub(crate) fn column_key(col: &EntityColumn) -> (Label, Descriptor) {
+    (col.label.clone(), col.descriptor.clone())
+}
+
+pub(crate) fn item_key(item: &HistoryItem) -> Timestamp {
+    item.timestamp
+}
+
+pub(crate) fn relationship_key(rel: &EntityRelationship) -> (Parent, Child, Role) {
+    (rel.parent.clone(), rel.child.clone(), rel.role.clone())
+}
+
+fn diff_records<T, K>(old: Vec<T>, new: Vec<T>, key: impl Fn(&T) -> K) -> Vec<Change<T>>
+where
+    T: PartialEq,
+    K: Ord,
+{
+    let mut old: BTreeMap<K, T> = old
+        .into_iter()
+        .map(|record| (key(&record), record))
+        .collect();
+    let mut changes: BTreeMap<K, Change<T>> = BTreeMap::new();
+    for record in new {
+        let record_key = key(&record);
+        match old.remove(&record_key) {
+            None => {
+                changes.insert(record_key, Change::Added(record));
+            }
+            Some(previous) if previous != record => {
+                changes.insert(
+                    record_key,
+                    Change::Modified {
+                        old: previous,
+                        new: record,
+                    },
+                );
+            }
+            Some(_) => {}
+        }
+    }
+    for (record_key, record) in old {
+        changes.insert(record_key, Change::Removed(record));
+    }
+    changes.into_values().collect()
+}
+
+/// Lists the changes that turn the contents of `old` into those of `new`.
+pub fn diff_stores(old: &impl LoreStore, new: &impl LoreStore) -> Result<LoreDiff, LoreCoreError> {
+    Ok(LoreDiff {
+        entity_columns: diff_records(
+            old.read_entity_columns(EntityColumnSearchParams::empty())?,
+            new.read_entity_columns(EntityColumnSearchParams::empty())?,
+            column_key,
+        ),
+        history_items: diff_records(
+            old.read_history_items(HistoryItemSearchParams::empty())?,
+            new.read_history_items(HistoryItemSearchParams::empty())?,
+            item_key,
+        ),
+        relationships: diff_records(
+            old.read_relationships(RelationshipSearchParams::empty())?,
+            new.read_relationships(RelationshipSearchParams::empty())?,
+            relationship_key,
+        ),
+    })
+}
+
+/// Lists the changes that turn the contents of a document written by `json::export_json` into
+/// those of `new`.
+pub fn diff_json(old_json: &str, new: &impl LoreStore) -> Result<LoreDiff, LoreCoreError> {
+    let old = MemoryDatabase::new();
+    import_json(&old, old_json)?;
+    diff_stores(&old, new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_are_matched_by_key() {
+        let old = vec![(1, "a"), (2, "b"), (3, "c")];
+        let new = vec![(4, "d"), (2, "B"), (3, "c")];
+        let changes = diff_records(old, new, |record| record.0);
+        assert_eq!(
+            changes,
+            vec![
+                Change::Removed((1, "a")),
+                Change::Modified {
+                    old: (2, "b"),
+                    new: (2, "B")
+                },
+                Change::Added((4, "d")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,436 @@
+//! A directory of TOML files holding the complete contents of a lore store, meant to be edited by
+//! hand and kept under version control.
+//!
+//! The directory contains
+//! - `lore.toml`, stating the format version,
+//! - `entities/<label>.toml` for every label, mapping descriptors to descriptions,
+//! - `history/<year>.toml` for every year, listing the history items of that year,
+//! - `relationships.toml`, listing all relationships.
+//!
+//! Characters of a label that are not safe in file names are written as `%XX`, one escape per
+//! UTF-8 byte. This includes uppercase letters, so that labels differing only in case do not share
+//! a file on case-insensitive file systems. The empty label is written as `%`. Import only accepts
+//! file names escaped exactly this way, so every label has a single file. Exporting the same
+//! lore always produces the same files, so the diff of a regenerated directory is empty if nothing
+//! has changed.
+//!
+//! TOML has no null value, so history items with a property that is or contains JSON `null` cannot
+//! be exported.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{
+    errors::LoreCoreError,
+    sql::search_params::{
+        EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams,
+    },
+    store::LoreStore,
+    types::*,
+};
+
+/// The version of the directory format written by `export_directory`.
+/// `import_directory` refuses directories of any other version.
+pub const DIRECTORY_FORMAT_VERSION: u32 = 1;
+
+const INDEX_FILE: &str = "lore.toml";
+const ENTITIES_DIR: &str = "entities";
+const HISTORY_DIR: &str = "history";
+const RELATIONSHIPS_FILE: &str = "relationships.toml";
+const EXTENSION: &str = "toml";
+const EMPTY_LABEL_FILE_STEM: &str = "%";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexFile {
+    version: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryFile {
+    #[serde(default, rename = "item")]
+    items: Vec<TomlHistoryItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TomlHistoryItem {
+    timestamp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    day: Option<u32>,
+    content: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    properties: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RelationshipsFile {
+    #[serde(default, rename = "relationship")]
+    relationships: Vec<TomlRelationship>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TomlRelationship {
+    parent: String,
+    child: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ordinal: Option<u32>,
+}
+
+/// Writes all lore of `store` into `dir`, creating it if necessary.
+/// Entity and history files in `dir` that no longer correspond to a label or year are removed.
+pub fn export_directory(store: &impl LoreStore, dir: &Path) -> Result<(), LoreCoreError> {
+    let mut entities: BTreeMap<Label, BTreeMap<String, String>> = BTreeMap::new();
+    for col in store.read_entity_columns(EntityColumnSearchParams::empty())? {
+        entities
+            .entry(col.label)
+            .or_default()
+            .insert(col.descriptor.0, col.description.0);
+    }
+
+    let mut years: BTreeMap<Year, HistoryFile> = BTreeMap::new();
+    for item in store.read_history_items(HistoryItemSearchParams::empty())? {
+        if let Some((key, _)) = item
+            .properties
+            .0
+            .iter()
+            .find(|(_, value)| contains_null(value))
+        {
+            return Err(LoreCoreError::InputError(format!(
+                "Property \"{}\" of the history item with timestamp {} contains null, which TOML \
+                 cannot represent.",
+                key, item.timestamp
+            )));
+        }
+        years
+            .entry(item.year)
+            .or_default()
+            .items
+            .push(TomlHistoryItem {
+                timestamp: item.timestamp.to_int(),
+                day: item.day.to_optional_int(),
+                content: item.content.0,
+                properties: item.properties.0.into_iter().collect(),
+            });
+    }
+
+    let relationships = store
+        .read_relationships(RelationshipSearchParams::empty())?
+        .into_iter()
+        .map(|rel| TomlRelationship {
+            role: rel.role.to_optional_string(),
+            parent: rel.parent.0,
+            child: rel.child.0,
+            ordinal: rel.ordinal.to_optional_int(),
+        })
+        .collect();
+
+    let entities_dir = dir.join(ENTITIES_DIR);
+    let history_dir = dir.join(HISTORY_DIR);
+    create_dir(&entities_dir)?;
+    create_dir(&history_dir)?;
+
+    write_toml(
+        &dir.join(INDEX_FILE),
+        &IndexFile {
+            version: DIRECTORY_FORMAT_VERSION,
+        },
+    )?;
+
+    let mut entity_files = Vec::new();
+    for (label, descriptors) in entities {
+        let path = entities_dir.join(label_to_file_name(&label));
+        write_toml(&path, &descriptors)?;
+        entity_files.push(path);
+    }
+    remove_stale_files(&entities_dir, &entity_files)?;
+
+    let mut history_files = Vec::new();
+    for (year, items) in years {
+        let path = history_dir.join(format!("{}.{}", year.to_int(), EXTENSION));
+        write_toml(&path, &items)?;
+        history_files.push(path);
+    }
+    remove_stale_files(&history_dir, &history_files)?;
+
+    write_toml(
+        &dir.join(RELATIONSHIPS_FILE),
+        &RelationshipsFile { relationships },
+    )
+}
+
+/// Writes the lore stored in `dir` into `store`, in a single transaction.
+pub fn import_directory(store: &impl LoreStore, dir: &Path) -> Result<(), LoreCoreError> {
+    let index: IndexFile = read_toml(&dir.join(INDEX_FILE))?;
+    if index.version != DIRECTORY_FORMAT_VERSION {
+        return Err(LoreCoreError::InputError(format!(
+            "Lore directory {} has version {}, but only version {} is supported.",
+            dir.display(),
+            index.version,
+            DIRECTORY_FORMAT_VERSION
+        )));
+    }
+
+    let mut cols = Vec::new();
+    for path in toml_files(&dir.join(ENTITIES_DIR))? {
+        let label = file_name_to_label(&path)?;
+        let descriptors: BTreeMap<String, String> = read_toml(&path)?;
+        for (descriptor, description) in descriptors {
+            cols.push(EntityColumn {
+                label: label.clone(),
+                descriptor: descriptor.into(),
+                description: description.into(),
+            });
+        }
+    }
+
+    let mut items = Vec::new();
+    for path in toml_files(&dir.join(HISTORY_DIR))? {
+        let year = file_name_to_year(&path)?;
+        let file: HistoryFile = read_toml(&path)?;
+        for item in file.items {
+            items.push(HistoryItem {
+                timestamp: item.timestamp.into(),
+                year,
+                day: item.day.into(),
+                content: item.content.into(),
+                properties: HistoryItemProperties(item.properties.into_iter().collect()),
+            });
+        }
+    }
+
+    let rels_path = dir.join(RELATIONSHIPS_FILE);
+    let rels = if rels_path.exists() {
+        let file: RelationshipsFile = read_toml(&rels_path)?;
+        file.relationships
+            .into_iter()
+            .map(|rel| EntityRelationship {
+                parent: rel.parent.into(),
+                child: rel.child.into(),
+                role: rel.role.into(),
+                ordinal: rel.ordinal.into(),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    store.in_transaction(|| {
+        store.write_entity_columns(cols)?;
+        store.write_history_items(items)?;
+        store.write_relationships(rels)
+    })
+}
+
+fn contains_null(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(values) => values.iter().any(contains_null),
+        Value::Object(map) => map.values().any(contains_null),
+        _ => false,
+    }
+}
+
+fn is_safe_file_name_char(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, ' ' | '_' | '-' | '\'')
+}
+
+fn label_to_file_name(label: &Label) -> String {
+    if label.to_str().is_empty() {
+        return EMPTY_LABEL_FILE_STEM.to_string() + "." + EXTENSION;
+    }
+    let mut name = String::new();
+    for c in label.to_str().chars() {
+        if is_safe_file_name_char(c) {
+            name.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                name += &format!("%{:02X}", byte);
+            }
+        }
+    }
+    name + "." + EXTENSION
+}
+
+fn file_name_to_label(path: &Path) -> Result<Label, LoreCoreError> {
+    let stem = file_stem(path)?;
+    if stem == EMPTY_LABEL_FILE_STEM {
+        return Ok("".into());
+    }
+    let invalid = || {
+        LoreCoreError::FileError(format!(
+            "File name {} is not a validly escaped label.",
+            path.display()
+        ))
+    };
+    let mut bytes = Vec::new();
+    let mut rest = stem.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2).ok_or_else(invalid)?;
+            let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+            bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    let label = String::from_utf8(bytes)
+        .map(Label::from)
+        .map_err(|_| invalid())?;
+    let expected = label_to_file_name(&label);
+    if path.file_name().and_then(|name| name.to_str()) != Some(expected.as_str()) {
+        return Err(LoreCoreError::FileError(format!(
+            "File name {} is not escaped the way export writes it, expected {}.",
+            path.display(),
+            expected
+        )));
+    }
+    Ok(label)
+}
+
+fn file_name_to_year(path: &Path) -> Result<Year, LoreCoreError> {
+    file_stem(path)?
+        .parse::<i32>()
+        .map(Year::from)
+        .map_err(|_| {
+            LoreCoreError::FileError(format!(
+                "File name {} does not state a year.",
+                path.display()
+            ))
+        })
+}
+
+fn file_stem(path: &Path) -> Result<&str, LoreCoreError> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| {
+            LoreCoreError::FileError(format!("File name {} is not valid UTF-8.", path.display()))
+        })
+}
+
+fn create_dir(dir: &Path) -> Result<(), LoreCoreError> {
+    fs::create_dir_all(dir).map_err(|e| {
+        LoreCoreError::FileError(format!(
+            "Failed to create directory {}: {}",
+            dir.display(),
+            e
+        ))
+    })
+}
+
+/// Returns the TOML files in `dir`, sorted by path. A missing directory contains no files.
+fn toml_files(dir: &Path) -> Result<Vec<PathBuf>, LoreCoreError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let read_error = |e: std::io::Error| {
+        LoreCoreError::FileError(format!("Failed to read directory {}: {}", dir.display(), e))
+    };
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == EXTENSION) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn remove_stale_files(dir: &Path, current: &[PathBuf]) -> Result<(), LoreCoreError> {
+    for path in toml_files(dir)? {
+        if !current.contains(&path) {
+            fs::remove_file(&path).map_err(|e| {
+                LoreCoreError::FileError(format!("Failed to remove {}: {}", path.display(), e))
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn write_toml<T: Serialize>(path: &Path, value: &T) -> Result<(), LoreCoreError> {
+    let text = toml::to_string(value).map_err(|e| {
+        LoreCoreError::InputError(format!(
+            "Failed to serialize {} to TOML: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    fs::write(path, text)
+        .map_err(|e| LoreCoreError::FileError(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+fn read_toml<T: DeserializeOwned>(path: &Path) -> Result<T, LoreCoreError> {
+    let text = fs::read_to_string(path).map_err(|e| {
+        LoreCoreError::FileError(format!("Failed to read {}: {}", path.display(), e))
+    })?;
+    toml::from_str(&text).map_err(|e| {
+        LoreCoreError::InputError(format!("Failed to parse {}: {}", path.display(), e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_survive_file_name_escaping() {
+        for label in [
+            "Hero",
+            "hero's dog",
+            "a/b\\c",
+            "100%",
+            "Ünïcödé",
+            "..",
+            "x.toml",
+            "",
+        ] {
+            let label: Label = label.into();
+            let name = label_to_file_name(&label);
+            assert!(!name.contains('/') && !name.contains('\\'));
+            assert!(!name.starts_with('.'));
+            assert_eq!(file_name_to_label(Path::new(&name)).unwrap(), label);
+        }
+    }
+
+    #[test]
+    fn file_names_differ_in_more_than_case() {
+        let upper = label_to_file_name(&"Hero".into());
+        let lower = label_to_file_name(&"hero".into());
+        assert_ne!(upper.to_lowercase(), lower.to_lowercase());
+        assert_eq!(upper, "%48ero.toml");
+    }
+
+    #[test]
+    fn file_names_must_be_escaped_like_export() {
+        assert!(file_name_to_label(Path::new("Hero.toml")).is_err());
+        assert!(file_name_to_label(Path::new("%48ero.toml")).is_ok());
+        assert!(file_name_to_label(Path::new("%68ero.toml")).is_err());
+        assert!(file_name_to_label(Path::new("%c3%9c.toml")).is_err());
+    }
+
+    #[test]
+    fn broken_escapes_are_rejected() {
+        assert!(file_name_to_label(Path::new("a%4.toml")).is_err());
+        assert!(file_name_to_label(Path::new("a%ZZ.toml")).is_err());
+        assert!(file_name_to_label(Path::new("%FF.toml")).is_err());
+    }
+
+    #[test]
+    fn years_are_read_from_file_names() {
+        assert_eq!(
+            file_name_to_year(Path::new("history/-12.toml")).unwrap(),
+            (-12).into()
+        );
+        assert!(file_name_to_year(Path::new("history/notes.toml")).is_err());
+    }
+}
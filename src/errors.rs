@@ -12,6 +12,14 @@ impl Display for LoreCoreError {
     }
 }
 
+#[cfg(feature = "sqlite")]
+impl From<diesel::result::Error> for LoreCoreError {
+    fn from(value: diesel::result::Error) -> Self {
+        LoreCoreError::SqlError("Database transaction failed: ".to_string() + &value.to_string())
+    }
+}
+
+#[cfg(feature = "sqlite")]
 pub(super) fn sql_loading_error<E>(
     loadee: &str,
     params: Vec<(&str, &dyn Debug)>,
@@ -28,6 +28,31 @@ pub fn extract_days(items: &[HistoryItem]) -> Vec<Day> {
     days
 }
 
+pub fn extract_parents(rels: &[EntityRelationship]) -> Vec<Parent> {
+    let mut parents: Vec<_> = rels.iter().map(|rel| rel.parent.clone()).collect();
+    parents.sort();
+    parents.dedup();
+    parents
+}
+
+pub fn extract_children(rels: &[EntityRelationship]) -> Vec<Child> {
+    let mut children: Vec<_> = rels.iter().map(|rel| rel.child.clone()).collect();
+    children.sort();
+    children.dedup();
+    children
+}
+
+pub fn extract_roles(rels: &[EntityRelationship]) -> Vec<Role> {
+    let mut roles: Vec<_> = rels
+        .iter()
+        .filter(|rel| !rel.role.0.is_empty())
+        .map(|rel| rel.role.clone())
+        .collect();
+    roles.sort();
+    roles.dedup();
+    roles
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,4 +169,82 @@ mod tests {
         let days = extract_days(&items);
         assert!(days == vec![Day::NONE, 1.into(), 2.into()]);
     }
+
+    #[test]
+    fn test_extract_parents() {
+        let rels = vec![
+            EntityRelationship {
+                parent: "b".into(),
+                child: "c".into(),
+                role: Role::NONE,
+                ordinal: Ordinal::NONE,
+            },
+            EntityRelationship {
+                parent: "a".into(),
+                child: "b".into(),
+                role: Role::NONE,
+                ordinal: Ordinal::NONE,
+            },
+            EntityRelationship {
+                parent: "a".into(),
+                child: "c".into(),
+                role: Role::NONE,
+                ordinal: Ordinal::NONE,
+            },
+        ];
+        let parents = extract_parents(&rels);
+        assert!(parents == vec!["a".into(), "b".into()]);
+    }
+
+    #[test]
+    fn test_extract_children() {
+        let rels = vec![
+            EntityRelationship {
+                parent: "b".into(),
+                child: "c".into(),
+                role: Role::NONE,
+                ordinal: Ordinal::NONE,
+            },
+            EntityRelationship {
+                parent: "a".into(),
+                child: "b".into(),
+                role: Role::NONE,
+                ordinal: Ordinal::NONE,
+            },
+            EntityRelationship {
+                parent: "a".into(),
+                child: "c".into(),
+                role: Role::NONE,
+                ordinal: Ordinal::NONE,
+            },
+        ];
+        let children = extract_children(&rels);
+        assert!(children == vec!["b".into(), "c".into()]);
+    }
+
+    #[test]
+    fn test_extract_roles() {
+        let rels = vec![
+            EntityRelationship {
+                parent: "b".into(),
+                child: "c".into(),
+                role: "r1".into(),
+                ordinal: Ordinal::NONE,
+            },
+            EntityRelationship {
+                parent: "a".into(),
+                child: "b".into(),
+                role: "r2".into(),
+                ordinal: Ordinal::NONE,
+            },
+            EntityRelationship {
+                parent: "a".into(),
+                child: "c".into(),
+                role: "r1".into(),
+                ordinal: Ordinal::NONE,
+            },
+        ];
+        let roles = extract_roles(&rels);
+        assert!(roles == vec!["r1".into(), "r2".into()]);
+    }
 }
@@ -0,0 +1,352 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::types::*;
+
+/// Determines which relationship roles are interpreted as family ties.
+/// A relationship with one of the `parent_roles` states that its parent is a (biological or
+/// adoptive) parent of its child. A relationship with one of the `spouse_roles` states that
+/// parent and child are married to each other.
+#[derive(Clone, Debug)]
+pub struct GenealogyRoles {
+    pub(crate) parent_roles: Vec<Role>,
+    pub(crate) spouse_roles: Vec<Role>,
+}
+
+impl GenealogyRoles {
+    pub fn new(parent_roles: Vec<Role>, spouse_roles: Vec<Role>) -> Self {
+        Self {
+            parent_roles,
+            spouse_roles,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Kinship {
+    pub common_ancestor: Label,
+    pub generations_to_first: usize,
+    pub generations_to_second: usize,
+}
+
+impl Kinship {
+    /// The degree of kinship as counted in civil law, i.e. the number of births separating the
+    /// two persons.
+    pub fn degree(&self) -> usize {
+        self.generations_to_first + self.generations_to_second
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FamilyTreeNode {
+    pub label: Label,
+    pub generation: usize,
+    pub spouses: Vec<Label>,
+    pub children: Vec<FamilyTreeNode>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FamilyTree {
+    parents: BTreeMap<Label, BTreeSet<Label>>,
+    children: BTreeMap<Label, BTreeSet<Label>>,
+    spouses: BTreeMap<Label, BTreeSet<Label>>,
+}
+
+fn get_sorted(map: &BTreeMap<Label, BTreeSet<Label>>, label: &Label) -> Vec<Label> {
+    map.get(label)
+        .map(|set| set.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+impl FamilyTree {
+    pub fn new(rels: &[EntityRelationship], roles: &GenealogyRoles) -> Self {
+        let mut tree = Self::default();
+        for rel in rels {
+            let parent = Label::from(rel.parent.to_str());
+            let child = Label::from(rel.child.to_str());
+            if roles.parent_roles.contains(&rel.role) {
+                tree.parents
+                    .entry(child.clone())
+                    .or_default()
+                    .insert(parent.clone());
+                tree.children.entry(parent).or_default().insert(child);
+            } else if roles.spouse_roles.contains(&rel.role) {
+                tree.spouses
+                    .entry(child.clone())
+                    .or_default()
+                    .insert(parent.clone());
+                tree.spouses.entry(parent).or_default().insert(child);
+            }
+        }
+        tree
+    }
+
+    pub fn persons(&self) -> Vec<Label> {
+        let mut persons = BTreeSet::new();
+        for map in [&self.parents, &self.children, &self.spouses] {
+            for (label, others) in map {
+                persons.insert(label.clone());
+                persons.extend(others.iter().cloned());
+            }
+        }
+        persons.into_iter().collect()
+    }
+
+    pub fn parents(&self, label: &Label) -> Vec<Label> {
+        get_sorted(&self.parents, label)
+    }
+
+    pub fn children(&self, label: &Label) -> Vec<Label> {
+        get_sorted(&self.children, label)
+    }
+
+    pub fn spouses(&self, label: &Label) -> Vec<Label> {
+        get_sorted(&self.spouses, label)
+    }
+
+    /// Everyone sharing at least one parent with `label`.
+    pub fn siblings(&self, label: &Label) -> Vec<Label> {
+        let mut siblings = BTreeSet::new();
+        for parent in self.parents(label) {
+            siblings.extend(self.children(&parent));
+        }
+        siblings.remove(label);
+        siblings.into_iter().collect()
+    }
+
+    /// The first cousins of `label`, i.e. the children of the siblings of its parents.
+    pub fn cousins(&self, label: &Label) -> Vec<Label> {
+        let mut cousins = BTreeSet::new();
+        for parent in self.parents(label) {
+            for aunt_or_uncle in self.siblings(&parent) {
+                cousins.extend(self.children(&aunt_or_uncle));
+            }
+        }
+        cousins.remove(label);
+        for sibling in self.siblings(label) {
+            cousins.remove(&sibling);
+        }
+        cousins.into_iter().collect()
+    }
+
+    /// Assigns every person a generation, counted from 0 for persons without known parents.
+    /// A person with several known ancestral lines belongs to the latest of these generations.
+    pub fn generations(&self) -> BTreeMap<Label, usize> {
+        let mut generations = BTreeMap::new();
+        for person in self.persons() {
+            self.generation(&person, &mut generations, &mut BTreeSet::new());
+        }
+        generations
+    }
+
+    fn generation(
+        &self,
+        label: &Label,
+        generations: &mut BTreeMap<Label, usize>,
+        visiting: &mut BTreeSet<Label>,
+    ) -> usize {
+        if let Some(generation) = generations.get(label) {
+            return *generation;
+        }
+        if !visiting.insert(label.clone()) {
+            // The relationships contain a cycle, which is broken up here.
+            return 0;
+        }
+        let generation = self
+            .parents(label)
+            .iter()
+            .map(|parent| self.generation(parent, generations, visiting) + 1)
+            .max()
+            .unwrap_or(0);
+        visiting.remove(label);
+        generations.insert(label.clone(), generation);
+        generation
+    }
+
+    fn ancestors_with_distance(&self, label: &Label) -> BTreeMap<Label, usize> {
+        let mut distances = BTreeMap::new();
+        distances.insert(label.clone(), 0);
+        let mut queue = VecDeque::new();
+        queue.push_back(label.clone());
+        while let Some(current) = queue.pop_front() {
+            let distance = distances[&current];
+            for parent in self.parents(&current) {
+                if !distances.contains_key(&parent) {
+                    distances.insert(parent.clone(), distance + 1);
+                    queue.push_back(parent);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Finds the closest common ancestor of the two persons, or `None` if they are not related
+    /// by blood. A person counts as their own ancestor, such that the kinship between a parent and
+    /// its child has degree 1.
+    pub fn kinship(&self, first: &Label, second: &Label) -> Option<Kinship> {
+        let first_ancestors = self.ancestors_with_distance(first);
+        let second_ancestors = self.ancestors_with_distance(second);
+        first_ancestors
+            .iter()
+            .filter_map(|(ancestor, first_distance)| {
+                second_ancestors
+                    .get(ancestor)
+                    .map(|second_distance| Kinship {
+                        common_ancestor: ancestor.clone(),
+                        generations_to_first: *first_distance,
+                        generations_to_second: *second_distance,
+                    })
+            })
+            .min_by_key(|kinship| kinship.degree())
+    }
+
+    /// Lays out the descendants of `root` as a tree. Persons reachable via several lines of
+    /// descent only appear below the first of them.
+    pub fn layout(&self, root: &Label) -> FamilyTreeNode {
+        let generations = self.generations();
+        let mut visited = BTreeSet::new();
+        self.layout_node(root, &generations, &mut visited)
+    }
+
+    fn layout_node(
+        &self,
+        label: &Label,
+        generations: &BTreeMap<Label, usize>,
+        visited: &mut BTreeSet<Label>,
+    ) -> FamilyTreeNode {
+        visited.insert(label.clone());
+        let mut children = Vec::new();
+        for child in self.children(label) {
+            if !visited.contains(&child) {
+                children.push(self.layout_node(&child, generations, visited));
+            }
+        }
+        FamilyTreeNode {
+            label: label.clone(),
+            generation: generations.get(label).copied().unwrap_or(0),
+            spouses: self.spouses(label),
+            children,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rel(parent: &str, child: &str, role: &str) -> EntityRelationship {
+        EntityRelationship {
+            parent: parent.into(),
+            child: child.into(),
+            role: role.into(),
+            ordinal: Ordinal::NONE,
+        }
+    }
+
+    //        grandpa = grandma
+    //        /              \
+    //     father = mother   aunt
+    //      /    \             |
+    //    me    sister       cousin
+    fn example_tree() -> FamilyTree {
+        let rels = vec![
+            rel("grandpa", "grandma", "spouse"),
+            rel("grandpa", "father", "father"),
+            rel("grandma", "father", "mother"),
+            rel("grandpa", "aunt", "father"),
+            rel("grandma", "aunt", "mother"),
+            rel("father", "mother", "spouse"),
+            rel("father", "me", "father"),
+            rel("mother", "me", "mother"),
+            rel("father", "sister", "father"),
+            rel("mother", "sister", "mother"),
+            rel("aunt", "cousin", "mother"),
+            rel("me", "sister", "friend"),
+        ];
+        let roles = GenealogyRoles::new(
+            vec!["father".into(), "mother".into()],
+            vec!["spouse".into()],
+        );
+        FamilyTree::new(&rels, &roles)
+    }
+
+    fn labels(labels: &[&str]) -> Vec<Label> {
+        labels.iter().map(|l| (*l).into()).collect()
+    }
+
+    #[test]
+    fn parents_children_and_spouses() {
+        let tree = example_tree();
+        assert_eq!(tree.parents(&"me".into()), labels(&["father", "mother"]));
+        assert_eq!(tree.children(&"aunt".into()), labels(&["cousin"]));
+        assert_eq!(tree.spouses(&"mother".into()), labels(&["father"]));
+        assert!(tree.spouses(&"me".into()).is_empty());
+    }
+
+    #[test]
+    fn siblings_share_a_parent() {
+        let tree = example_tree();
+        assert_eq!(tree.siblings(&"me".into()), labels(&["sister"]));
+        assert_eq!(tree.siblings(&"father".into()), labels(&["aunt"]));
+        assert!(tree.siblings(&"cousin".into()).is_empty());
+    }
+
+    #[test]
+    fn cousins_are_children_of_aunts_and_uncles() {
+        let tree = example_tree();
+        assert_eq!(tree.cousins(&"me".into()), labels(&["cousin"]));
+        assert_eq!(tree.cousins(&"cousin".into()), labels(&["me", "sister"]));
+        assert!(tree.cousins(&"father".into()).is_empty());
+    }
+
+    #[test]
+    fn generations_count_from_the_oldest_ancestors() {
+        let generations = example_tree().generations();
+        assert_eq!(generations[&"grandpa".into()], 0);
+        assert_eq!(generations[&"mother".into()], 0);
+        assert_eq!(generations[&"aunt".into()], 1);
+        assert_eq!(generations[&"me".into()], 2);
+        assert_eq!(generations[&"cousin".into()], 2);
+    }
+
+    #[test]
+    fn generations_survive_cycles() {
+        let rels = vec![rel("a", "b", "parent"), rel("b", "a", "parent")];
+        let roles = GenealogyRoles::new(vec!["parent".into()], Vec::new());
+        let generations = FamilyTree::new(&rels, &roles).generations();
+        assert_eq!(generations.len(), 2);
+    }
+
+    #[test]
+    fn degree_of_kinship() {
+        let tree = example_tree();
+        let kinship = tree.kinship(&"me".into(), &"cousin".into()).unwrap();
+        assert_eq!(kinship.degree(), 4);
+        assert!(["grandpa".into(), "grandma".into()].contains(&kinship.common_ancestor));
+
+        let kinship = tree.kinship(&"me".into(), &"father".into()).unwrap();
+        assert_eq!(kinship.common_ancestor, "father".into());
+        assert_eq!(kinship.degree(), 1);
+
+        let kinship = tree.kinship(&"me".into(), &"sister".into()).unwrap();
+        assert_eq!(kinship.degree(), 2);
+
+        assert!(tree.kinship(&"me".into(), &"mother".into()).is_some());
+        assert!(tree.kinship(&"father".into(), &"mother".into()).is_none());
+    }
+
+    #[test]
+    fn layout_of_descendants() {
+        let tree = example_tree();
+        let root = tree.layout(&"grandma".into());
+        assert_eq!(root.label, "grandma".into());
+        assert_eq!(root.generation, 0);
+        assert_eq!(root.spouses, labels(&["grandpa"]));
+        let children: Vec<_> = root.children.iter().map(|c| c.label.clone()).collect();
+        assert_eq!(children, labels(&["aunt", "father"]));
+        let father = &root.children[1];
+        assert_eq!(father.generation, 1);
+        let grandchildren: Vec<_> = father.children.iter().map(|c| c.label.clone()).collect();
+        assert_eq!(grandchildren, labels(&["me", "sister"]));
+        assert!(father.children[0].children.is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+use super::RelationshipGraph;
+
+fn quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+impl RelationshipGraph {
+    pub fn to_dot(&self) -> String {
+        let mut dot = "digraph lore {\n".to_string();
+        for node in &self.nodes {
+            dot += "    ";
+            dot += &quote(node.label.to_str());
+            if !node.attributes.is_empty() {
+                let attributes: Vec<_> = node
+                    .attributes
+                    .iter()
+                    .map(|(descriptor, description)| {
+                        quote(descriptor.to_str()) + "=" + &quote(description.to_str())
+                    })
+                    .collect();
+                dot += &format!(" [{}]", attributes.join(", "));
+            }
+            dot += ";\n";
+        }
+        for edge in &self.edges {
+            dot += &format!(
+                "    {} -> {}",
+                quote(edge.parent.to_str()),
+                quote(edge.child.to_str())
+            );
+            if let Some(role) = edge.role.to_optional_string() {
+                dot += &format!(" [label={}]", quote(&role));
+            }
+            dot += ";\n";
+        }
+        dot += "}\n";
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::tests::example_graph;
+
+    #[test]
+    fn dot_output_of_example_graph() {
+        let expected = r#"digraph lore {
+    "cellar";
+    "city" ["name"="Big \"City\""];
+    "kingdom";
+    "tavern";
+    "city" -> "tavern";
+    "kingdom" -> "city" [label="capital"];
+    "tavern" -> "cellar";
+}
+"#;
+        assert_eq!(example_graph().to_dot(), expected);
+    }
+
+    #[test]
+    fn backslashes_and_newlines_are_escaped() {
+        assert_eq!(super::quote("a\\b\nc"), "\"a\\\\b\\nc\"");
+    }
+}
@@ -0,0 +1,93 @@
+use super::RelationshipGraph;
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+impl RelationshipGraph {
+    pub fn to_graphml(&self) -> String {
+        let mut xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n".to_string();
+        xml += "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n";
+        xml += "  <key id=\"role\" for=\"edge\" attr.name=\"role\" attr.type=\"string\"/>\n";
+        for (i, descriptor) in self.node_descriptors.iter().enumerate() {
+            xml += &format!(
+                "  <key id=\"d{}\" for=\"node\" attr.name=\"{}\" attr.type=\"string\"/>\n",
+                i,
+                escape(descriptor.to_str())
+            );
+        }
+        xml += "  <graph id=\"lore\" edgedefault=\"directed\">\n";
+        for node in &self.nodes {
+            let label = escape(node.label.to_str());
+            if node.attributes.is_empty() {
+                xml += &format!("    <node id=\"{}\"/>\n", label);
+                continue;
+            }
+            xml += &format!("    <node id=\"{}\">\n", label);
+            for (descriptor, description) in &node.attributes {
+                let key = self
+                    .node_descriptors
+                    .iter()
+                    .position(|d| d == descriptor)
+                    .unwrap_or_default();
+                xml += &format!(
+                    "      <data key=\"d{}\">{}</data>\n",
+                    key,
+                    escape(description.to_str())
+                );
+            }
+            xml += "    </node>\n";
+        }
+        for edge in &self.edges {
+            let parent = escape(edge.parent.to_str());
+            let child = escape(edge.child.to_str());
+            match edge.role.to_optional_string() {
+                Some(role) => {
+                    xml += &format!("    <edge source=\"{}\" target=\"{}\">\n", parent, child);
+                    xml += &format!("      <data key=\"role\">{}</data>\n", escape(&role));
+                    xml += "    </edge>\n";
+                }
+                None => {
+                    xml += &format!("    <edge source=\"{}\" target=\"{}\"/>\n", parent, child);
+                }
+            }
+        }
+        xml += "  </graph>\n";
+        xml += "</graphml>\n";
+        xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::tests::example_graph;
+
+    #[test]
+    fn graphml_output_of_example_graph() {
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="role" for="edge" attr.name="role" attr.type="string"/>
+  <key id="d0" for="node" attr.name="name" attr.type="string"/>
+  <graph id="lore" edgedefault="directed">
+    <node id="cellar"/>
+    <node id="city">
+      <data key="d0">Big &quot;City&quot;</data>
+    </node>
+    <node id="kingdom"/>
+    <node id="tavern"/>
+    <edge source="city" target="tavern"/>
+    <edge source="kingdom" target="city">
+      <data key="role">capital</data>
+    </edge>
+    <edge source="tavern" target="cellar"/>
+  </graph>
+</graphml>
+"#;
+        assert_eq!(example_graph().to_graphml(), expected);
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::{errors::LoreCoreError, types::*};
+
+pub mod dot;
+pub mod graphml;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphNode {
+    pub label: Label,
+    pub attributes: Vec<(Descriptor, Description)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelationshipGraph {
+    pub node_descriptors: Vec<Descriptor>,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<EntityRelationship>,
+}
+
+#[derive(Debug)]
+pub struct GraphExportParams {
+    pub(crate) center: Option<(Label, usize)>,
+    pub(crate) node_descriptors: Vec<Descriptor>,
+}
+
+impl GraphExportParams {
+    pub fn new(center: Option<(Label, usize)>, node_descriptors: Vec<Descriptor>) -> Self {
+        Self {
+            center,
+            node_descriptors,
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            center: None,
+            node_descriptors: Vec::new(),
+        }
+    }
+}
+
+impl RelationshipGraph {
+    pub fn new(
+        rels: &[EntityRelationship],
+        cols: &[EntityColumn],
+        node_descriptors: &[Descriptor],
+    ) -> Self {
+        let mut labels = BTreeSet::new();
+        for rel in rels {
+            labels.insert(Label::from(rel.parent.to_str()));
+            labels.insert(Label::from(rel.child.to_str()));
+        }
+        let mut edges = rels.to_vec();
+        edges.sort();
+        Self::from_labels(labels, edges, cols, node_descriptors)
+    }
+
+    /// Builds the graph described by `params`, failing if its center takes part in no
+    /// relationship.
+    pub fn build(
+        rels: &[EntityRelationship],
+        cols: &[EntityColumn],
+        params: &GraphExportParams,
+    ) -> Result<Self, LoreCoreError> {
+        let graph = RelationshipGraph::new(rels, cols, &params.node_descriptors);
+        match &params.center {
+            Some((label, depth)) => {
+                if !graph.nodes.iter().any(|node| &node.label == label) {
+                    return Err(LoreCoreError::InputError(format!(
+                        "Label \"{}\" does not take part in any relationship.",
+                        label
+                    )));
+                }
+                Ok(graph.subgraph(label, *depth))
+            }
+            None => Ok(graph),
+        }
+    }
+
+    /// Restricts the graph to the nodes that are at most `depth` edges away from `center`,
+    /// regardless of the direction of the edges.
+    pub fn subgraph(&self, center: &Label, depth: usize) -> Self {
+        let mut neighbours: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for edge in &self.edges {
+            let parent = edge.parent.to_str();
+            let child = edge.child.to_str();
+            neighbours.entry(parent).or_default().push(child);
+            neighbours.entry(child).or_default().push(parent);
+        }
+
+        let mut reached = BTreeSet::new();
+        reached.insert(center.to_str());
+        let mut queue = VecDeque::new();
+        queue.push_back((center.to_str(), 0));
+        while let Some((label, distance)) = queue.pop_front() {
+            if distance >= depth {
+                continue;
+            }
+            for neighbour in neighbours.get(label).into_iter().flatten() {
+                if reached.insert(neighbour) {
+                    queue.push_back((neighbour, distance + 1));
+                }
+            }
+        }
+
+        let nodes = self
+            .nodes
+            .iter()
+            .filter(|node| reached.contains(node.label.to_str()))
+            .cloned()
+            .collect();
+        let edges = self
+            .edges
+            .iter()
+            .filter(|edge| {
+                reached.contains(edge.parent.to_str()) && reached.contains(edge.child.to_str())
+            })
+            .cloned()
+            .collect();
+        Self {
+            node_descriptors: self.node_descriptors.clone(),
+            nodes,
+            edges,
+        }
+    }
+
+    fn from_labels(
+        labels: BTreeSet<Label>,
+        edges: Vec<EntityRelationship>,
+        cols: &[EntityColumn],
+        node_descriptors: &[Descriptor],
+    ) -> Self {
+        let nodes = labels
+            .into_iter()
+            .map(|label| {
+                let attributes = node_descriptors
+                    .iter()
+                    .filter_map(|descriptor| {
+                        cols.iter()
+                            .find(|col| col.label == label && &col.descriptor == descriptor)
+                            .map(|col| (descriptor.clone(), col.description.clone()))
+                    })
+                    .collect();
+                GraphNode { label, attributes }
+            })
+            .collect();
+        Self {
+            node_descriptors: node_descriptors.to_vec(),
+            nodes,
+            edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(super) fn example_graph() -> RelationshipGraph {
+        let rels = vec![
+            EntityRelationship {
+                parent: "kingdom".into(),
+                child: "city".into(),
+                role: "capital".into(),
+                ordinal: Ordinal::NONE,
+            },
+            EntityRelationship {
+                parent: "city".into(),
+                child: "tavern".into(),
+                role: Role::NONE,
+                ordinal: Ordinal::NONE,
+            },
+            EntityRelationship {
+                parent: "tavern".into(),
+                child: "cellar".into(),
+                role: Role::NONE,
+                ordinal: Ordinal::NONE,
+            },
+        ];
+        let cols = vec![
+            EntityColumn {
+                label: "city".into(),
+                descriptor: "name".into(),
+                description: "Big \"City\"".into(),
+            },
+            EntityColumn {
+                label: "city".into(),
+                descriptor: "_secret".into(),
+                description: "hidden".into(),
+            },
+        ];
+        RelationshipGraph::new(&rels, &cols, &["name".into()])
+    }
+
+    #[test]
+    fn graph_contains_all_labels_of_relationships() {
+        let graph = example_graph();
+        let labels: Vec<_> = graph.nodes.iter().map(|n| n.label.to_string()).collect();
+        assert_eq!(labels, vec!["cellar", "city", "kingdom", "tavern"]);
+        assert_eq!(graph.edges.len(), 3);
+    }
+
+    #[test]
+    fn only_selected_descriptors_become_attributes() {
+        let graph = example_graph();
+        let city = graph
+            .nodes
+            .iter()
+            .find(|n| n.label == "city".into())
+            .unwrap();
+        assert_eq!(
+            city.attributes,
+            vec![("name".into(), "Big \"City\"".into())]
+        );
+    }
+
+    #[test]
+    fn subgraph_is_limited_by_depth() {
+        let graph = example_graph();
+        let subgraph = graph.subgraph(&"city".into(), 1);
+        let labels: Vec<_> = subgraph.nodes.iter().map(|n| n.label.to_string()).collect();
+        assert_eq!(labels, vec!["city", "kingdom", "tavern"]);
+        assert_eq!(subgraph.edges.len(), 2);
+    }
+
+    #[test]
+    fn subgraph_of_depth_zero_contains_only_center() {
+        let graph = example_graph();
+        let subgraph = graph.subgraph(&"tavern".into(), 0);
+        assert_eq!(subgraph.nodes.len(), 1);
+        assert!(subgraph.edges.is_empty());
+    }
+}
@@ -0,0 +1,275 @@
+//! A versioned JSON document holding the complete contents of a lore store.
+//! Records are written in the order in which the store returns them, and the keys of history item
+//! properties are sorted, so that exporting the same lore always yields the same text.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    errors::LoreCoreError,
+    sql::search_params::{
+        EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams,
+    },
+    store::LoreStore,
+    types::*,
+};
+
+/// The version of the document format written by `export_json`.
+/// `import_json` refuses documents of any other version.
+pub const JSON_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonDocument {
+    version: u32,
+    entity_columns: Vec<JsonEntityColumn>,
+    history_items: Vec<JsonHistoryItem>,
+    relationships: Vec<JsonRelationship>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct JsonEntityColumn {
+    label: String,
+    descriptor: String,
+    description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct JsonHistoryItem {
+    timestamp: i64,
+    year: i32,
+    day: Option<u32>,
+    content: String,
+    properties: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct JsonRelationship {
+    parent: String,
+    child: String,
+    role: Option<String>,
+    ordinal: Option<u32>,
+}
+
+impl From<EntityColumn> for JsonEntityColumn {
+    fn from(col: EntityColumn) -> Self {
+        Self {
+            label: col.label.0,
+            descriptor: col.descriptor.0,
+            description: col.description.0,
+        }
+    }
+}
+
+impl From<JsonEntityColumn> for EntityColumn {
+    fn from(col: JsonEntityColumn) -> Self {
+        Self {
+            label: col.label.into(),
+            descriptor: col.descriptor.into(),
+            description: col.description.into(),
+        }
+    }
+}
+
+impl From<HistoryItem> for JsonHistoryItem {
+    fn from(item: HistoryItem) -> Self {
+        Self {
+            timestamp: item.timestamp.to_int(),
+            year: item.year.to_int(),
+            day: item.day.to_optional_int(),
+            content: item.content.0,
+            properties: item.properties.0.into_iter().collect(),
+        }
+    }
+}
+
+impl From<JsonHistoryItem> for HistoryItem {
+    fn from(item: JsonHistoryItem) -> Self {
+        Self {
+            timestamp: item.timestamp.into(),
+            year: item.year.into(),
+            day: item.day.into(),
+            content: item.content.into(),
+            properties: HistoryItemProperties(item.properties.into_iter().collect()),
+        }
+    }
+}
+
+impl From<EntityRelationship> for JsonRelationship {
+    fn from(rel: EntityRelationship) -> Self {
+        Self {
+            role: rel.role.to_optional_string(),
+            parent: rel.parent.0,
+            child: rel.child.0,
+            ordinal: rel.ordinal.to_optional_int(),
+        }
+    }
+}
+
+impl From<JsonRelationship> for EntityRelationship {
+    fn from(rel: JsonRelationship) -> Self {
+        Self {
+            parent: rel.parent.into(),
+            child: rel.child.into(),
+            role: rel.role.into(),
+            ordinal: rel.ordinal.into(),
+        }
+    }
+}
+
+/// Serializes all entity columns, history items and relationships of `store` into a pretty-printed
+/// JSON document.
+pub fn export_json(store: &impl LoreStore) -> Result<String, LoreCoreError> {
+    let document = JsonDocument {
+        version: JSON_FORMAT_VERSION,
+        entity_columns: store
+            .read_entity_columns(EntityColumnSearchParams::empty())?
+            .into_iter()
+            .map(JsonEntityColumn::from)
+            .collect(),
+        history_items: store
+            .read_history_items(HistoryItemSearchParams::empty())?
+            .into_iter()
+            .map(JsonHistoryItem::from)
+            .collect(),
+        relationships: store
+            .read_relationships(RelationshipSearchParams::empty())?
+            .into_iter()
+            .map(JsonRelationship::from)
+            .collect(),
+    };
+    serde_json::to_string_pretty(&document).map_err(|e| {
+        LoreCoreError::InputError("Failed to serialize lore to JSON: ".to_string() + &e.to_string())
+    })
+}
+
+/// Writes the records of a document produced by `export_json` into `store`.
+/// Either all records are written or, if one of them conflicts with the existing contents of the
+/// store, none of them.
+pub fn import_json(store: &impl LoreStore, json: &str) -> Result<(), LoreCoreError> {
+    let document: JsonDocument = serde_json::from_str(json).map_err(|e| {
+        LoreCoreError::InputError("Failed to parse lore JSON: ".to_string() + &e.to_string())
+    })?;
+    if document.version != JSON_FORMAT_VERSION {
+        return Err(LoreCoreError::InputError(format!(
+            "Lore JSON has version {}, but only version {} is supported.",
+            document.version, JSON_FORMAT_VERSION
+        )));
+    }
+
+    store.in_transaction(|| write_document(store, document))
+}
+
+fn write_document(store: &impl LoreStore, document: JsonDocument) -> Result<(), LoreCoreError> {
+    store.write_entity_columns(
+        document
+            .entity_columns
+            .into_iter()
+            .map(EntityColumn::from)
+            .collect(),
+    )?;
+    store.write_history_items(
+        document
+            .history_items
+            .into_iter()
+            .map(HistoryItem::from)
+            .collect(),
+    )?;
+    store.write_relationships(
+        document
+            .relationships
+            .into_iter()
+            .map(EntityRelationship::from)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryDatabase;
+
+    fn example_store() -> MemoryDatabase {
+        let store = MemoryDatabase::new();
+        store
+            .write_entity_columns(vec![EntityColumn {
+                label: "Hero".into(),
+                descriptor: "Age".into(),
+                description: "Multi\nline \"quoted\"".into(),
+            }])
+            .unwrap();
+        store
+            .write_history_items(vec![HistoryItem {
+                timestamp: 12.into(),
+                year: (-3).into(),
+                day: Day::NONE,
+                content: "\\entityref{Hero} was born.".into(),
+                properties: "{\"is_secret\":true,\"additional_concerns\":[\"a\"]}".into(),
+            }])
+            .unwrap();
+        store
+            .write_relationships(vec![
+                EntityRelationship {
+                    parent: "Hero".into(),
+                    child: "Dog".into(),
+                    role: "owner".into(),
+                    ordinal: 2.into(),
+                },
+                EntityRelationship {
+                    parent: "Hero".into(),
+                    child: "Cat".into(),
+                    role: Role::NONE,
+                    ordinal: Ordinal::NONE,
+                },
+            ])
+            .unwrap();
+        store
+    }
+
+    #[test]
+    fn export_and_import_round_trip() {
+        let original = example_store();
+        let json = export_json(&original).unwrap();
+        let copy = MemoryDatabase::new();
+        import_json(&copy, &json).unwrap();
+        assert_eq!(export_json(&copy).unwrap(), json);
+        assert_eq!(
+            copy.read_history_items(HistoryItemSearchParams::empty())
+                .unwrap(),
+            original
+                .read_history_items(HistoryItemSearchParams::empty())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn property_keys_are_sorted() {
+        let json = export_json(&example_store()).unwrap();
+        let concerns = json.find("additional_concerns").unwrap();
+        let secret = json.find("is_secret").unwrap();
+        assert!(concerns < secret);
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let json = export_json(&example_store())
+            .unwrap()
+            .replace("\"version\": 1", "\"version\": 2");
+        assert!(import_json(&MemoryDatabase::new(), &json).is_err());
+    }
+
+    #[test]
+    fn conflicting_import_writes_nothing() {
+        let store = example_store();
+        let mut json = export_json(&store).unwrap();
+        let other = MemoryDatabase::new();
+        import_json(&other, &json).unwrap();
+        other.delete_entity("Hero".into()).unwrap();
+        json = json.replace("\"Hero\"", "\"Heroine\"");
+        let before = export_json(&other).unwrap();
+        // The relationships from Heroine are new, but the history item timestamp clashes.
+        assert!(import_json(&other, &json).is_err());
+        assert_eq!(export_json(&other).unwrap(), before);
+    }
+}
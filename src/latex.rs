@@ -0,0 +1,236 @@
+//! Rendering of entities to LaTeX, meant to be `\input` by a LoreTex document.
+//!
+//! Every entity becomes a heading with a `\label`, followed by one heading per descriptor, a list of
+//! its relationships and a list of the history items concerning it. Descriptions and history item
+//! contents are LaTeX already and are written as they are, except that `\entityref{label}` is
+//! resolved into a `\hyperref` if the label is part of the output, and into its plain text
+//! otherwise.
+
+use std::collections::BTreeSet;
+
+use crate::{
+    errors::LoreCoreError,
+    sql::search_params::EntityColumnSearchParams,
+    store::LoreStore,
+    types::{history::replace_entityrefs, *},
+};
+
+/// The sectioning command used for the entities. Their descriptors, relationships and history are
+/// one level below.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LatexHeading {
+    Chapter,
+    Section,
+    Subsection,
+}
+
+impl LatexHeading {
+    fn command(self) -> &'static str {
+        match self {
+            LatexHeading::Chapter => "chapter",
+            LatexHeading::Section => "section",
+            LatexHeading::Subsection => "subsection",
+        }
+    }
+
+    fn sub_command(self) -> &'static str {
+        match self {
+            LatexHeading::Chapter => "section",
+            LatexHeading::Section => "subsection",
+            LatexHeading::Subsection => "subsubsection",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LatexExportParams {
+    pub(crate) search_params: EntityColumnSearchParams,
+    pub(crate) heading: LatexHeading,
+}
+
+impl LatexExportParams {
+    pub fn new(search_params: EntityColumnSearchParams, heading: LatexHeading) -> Self {
+        Self {
+            search_params,
+            heading,
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            search_params: EntityColumnSearchParams::empty(),
+            heading: LatexHeading::Section,
+        }
+    }
+}
+
+/// Escapes the characters that have a special meaning in LaTeX.
+pub fn escape_latex(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped += "\\textbackslash{}",
+            '^' => escaped += "\\textasciicircum{}",
+            '~' => escaped += "\\textasciitilde{}",
+            '{' | '}' | '$' | '&' | '#' | '_' | '%' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The key under which the heading of an entity is `\label`ed.
+pub fn latex_key(label: &Label) -> String {
+    "lore:".to_string() + &label.to_alphanumeric_key()
+}
+
+pub fn export_latex(
+    store: &impl LoreStore,
+    params: LatexExportParams,
+) -> Result<String, LoreCoreError> {
+    let entities = store.read_entities(params.search_params)?;
+    Ok(render_latex(&entities, params.heading))
+}
+
+pub fn render_latex(entities: &[Entity], heading: LatexHeading) -> String {
+    let labels: BTreeSet<&Label> = entities.iter().map(|entity| &entity.label).collect();
+    let reference = |label: &Label| {
+        if labels.contains(label) {
+            format!(
+                "\\hyperref[{}]{{{}}}",
+                latex_key(label),
+                escape_latex(label.to_str())
+            )
+        } else {
+            escape_latex(label.to_str())
+        }
+    };
+    let resolve = |text: &str| replace_entityrefs(text, reference);
+
+    let mut latex = "% Generated by lorecore, changes will be overwritten.\n".to_string();
+    for entity in entities {
+        latex += &format!(
+            "\n\\{}{{{}}}\\label{{{}}}\n",
+            heading.command(),
+            escape_latex(entity.label.to_str()),
+            latex_key(&entity.label)
+        );
+        for (descriptor, description) in &entity.descriptors {
+            latex += &format!(
+                "\n\\{}{{{}}}\n",
+                heading.sub_command(),
+                escape_latex(descriptor.to_str())
+            );
+            latex += &resolve(description.to_str());
+            latex += "\n";
+        }
+
+        if !entity.parents.is_empty() || !entity.children.is_empty() {
+            latex += &format!("\n\\{}{{Relationships}}\n", heading.sub_command());
+            latex += "\\begin{itemize}\n";
+            for rel in &entity.parents {
+                latex += &format!(
+                    "    \\item Child of {}",
+                    reference(&rel.parent.clone().into())
+                );
+                latex += &role_suffix(&rel.role);
+            }
+            for rel in &entity.children {
+                latex += &format!(
+                    "    \\item Parent of {}",
+                    reference(&rel.child.clone().into())
+                );
+                latex += &role_suffix(&rel.role);
+            }
+            latex += "\\end{itemize}\n";
+        }
+
+        if !entity.history.is_empty() {
+            latex += &format!("\n\\{}{{History}}\n", heading.sub_command());
+            latex += "\\begin{description}\n";
+            for item in &entity.history {
+                let date = match item.day.to_optional_int() {
+                    Some(day) => format!("{}, day {}", item.year, day),
+                    None => item.year.to_string(),
+                };
+                latex += &format!("    \\item[{}] {}\n", date, resolve(item.content.to_str()));
+            }
+            latex += "\\end{description}\n";
+        }
+    }
+    latex
+}
+
+fn role_suffix(role: &Role) -> String {
+    match role.to_optional_string() {
+        Some(role) => format!(" ({})\n", escape_latex(&role)),
+        None => "\n".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn special_characters_are_escaped() {
+        assert_eq!(
+            escape_latex("_secret & 100% {x} #1 $ ~ ^ \\"),
+            "\\_secret \\& 100\\% \\{x\\} \\#1 \\$ \\textasciitilde{} \\textasciicircum{} \\textbackslash{}"
+        );
+    }
+
+    #[test]
+    fn keys_contain_only_safe_characters() {
+        assert_eq!(latex_key(&"Hero".into()), "lore:Hero");
+        assert_eq!(latex_key(&"hero's dog".into()), "lore:hero-27s-20dog");
+        assert_ne!(latex_key(&"a b".into()), latex_key(&"a-20b".into()));
+    }
+
+    #[test]
+    fn entity_is_rendered_with_resolved_references() {
+        let rel = EntityRelationship {
+            parent: "Hero".into(),
+            child: "Dog".into(),
+            role: "owner".into(),
+            ordinal: Ordinal::NONE,
+        };
+        let hero = Entity {
+            label: "Hero".into(),
+            descriptors: vec![(
+                "Biography".into(),
+                "Friend of \\entityref{Dog} and \\entityref{Hero}.".into(),
+            )],
+            parents: Vec::new(),
+            children: vec![rel],
+            history: vec![HistoryItem {
+                timestamp: 1.into(),
+                year: 12.into(),
+                day: 3.into(),
+                content: "\\entityref{Hero} is born.".into(),
+                properties: HistoryItemProperties::none(),
+            }],
+        };
+        let expected = r"% Generated by lorecore, changes will be overwritten.
+
+\chapter{Hero}\label{lore:Hero}
+
+\section{Biography}
+Friend of Dog and \hyperref[lore:Hero]{Hero}.
+
+\section{Relationships}
+\begin{itemize}
+    \item Parent of Dog (owner)
+\end{itemize}
+
+\section{History}
+\begin{description}
+    \item[12, day 3] \hyperref[lore:Hero]{Hero} is born.
+\end{description}
+";
+        assert_eq!(render_latex(&[hero], LatexHeading::Chapter), expected);
+    }
+}
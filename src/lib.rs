@@ -1,8 +1,21 @@
 #![warn(clippy::unwrap_used)]
 
+#[cfg(feature = "sqlite")]
 pub mod c_api;
+pub mod delimited;
+pub mod diff;
+pub mod directory;
 pub mod errors;
 pub mod extractions;
+pub mod genealogy;
+pub mod graph;
+pub mod json;
+pub mod latex;
+pub mod loretex;
+pub mod markdown;
+pub mod memory;
+pub mod merge;
 pub mod sql;
+pub mod store;
 pub mod timestamp;
 pub mod types;
@@ -0,0 +1,412 @@
+//! Import of lore from LoreTex source files.
+//!
+//! The following macros are understood:
+//! - `\newentity{<label>}` starts an entity; the macros below apply to the latest one.
+//! - `\setdescriptor{<descriptor>}{<description>}` sets a descriptor of the entity.
+//! - `\addhistory[<day>]{<year>}{<content>}` adds a history item concerning the entity. The day
+//!   is optional.
+//! - `\addparent[<role>]{<parent>}` adds a relationship with the entity as child. The role is
+//!   optional.
+//!
+//! Comments, `\documentclass`, `\usepackage`, `\begin` and `\end` are skipped. Everything else
+//! outside of these macros, as well as macros that cannot be interpreted, is listed in the report
+//! instead of being imported.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{
+    errors::LoreCoreError,
+    store::LoreStore,
+    timestamp::try_current_timestamp,
+    types::{history::entityref, *},
+};
+
+const SKIPPED_COMMANDS: [&str; 4] = ["documentclass", "usepackage", "begin", "end"];
+
+/// A part of the source that was not imported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnparsedConstruct {
+    /// The 1-based line on which the construct starts.
+    pub line: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoreTexImport {
+    pub cols: Vec<EntityColumn>,
+    pub items: Vec<HistoryItem>,
+    pub rels: Vec<EntityRelationship>,
+    pub unparsed: Vec<UnparsedConstruct>,
+}
+
+struct Scanner<'a> {
+    source: &'a str,
+    pos: usize,
+    /// The line of `counted_pos`, so that newlines are only counted once.
+    line: usize,
+    counted_pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn new(source: &'a str) -> Self {
+        Scanner {
+            source,
+            pos: 0,
+            line: 1,
+            counted_pos: 0,
+        }
+    }
+
+    /// Returns the line of `pos`, which must not lie before a position passed earlier.
+    fn line(&mut self, pos: usize) -> usize {
+        self.line += self.source[self.counted_pos..pos].matches('\n').count();
+        self.counted_pos = pos;
+        self.line
+    }
+
+    fn skip_line(&mut self) {
+        match self.rest().find('\n') {
+            Some(end) => self.pos += end + 1,
+            None => self.pos = self.source.len(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Reads a group enclosed by `open` and `close`, respecting nesting and escaped characters.
+    /// Returns the content without the delimiters, or `None` if there is no complete group.
+    fn read_group(&mut self, open: char, close: char) -> Option<&'a str> {
+        let rest = self.rest();
+        if !rest.starts_with(open) {
+            return None;
+        }
+        let mut depth = 0;
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c == '\\' {
+                chars.next();
+            } else if c == open {
+                depth += 1;
+            } else if c == close {
+                depth -= 1;
+                if depth == 0 {
+                    self.pos += i + 1;
+                    return Some(&rest[1..i]);
+                }
+            }
+        }
+        None
+    }
+
+    /// Reads the name following a backslash: a run of letters, or a single other character.
+    fn read_command_name(&mut self) -> &'a str {
+        let rest = self.rest();
+        let letters = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let len = if letters > 0 {
+            letters
+        } else {
+            rest.chars().next().map(|c| c.len_utf8()).unwrap_or(0)
+        };
+        self.pos += len;
+        &rest[..len]
+    }
+}
+
+struct Command<'a> {
+    line: usize,
+    text: &'a str,
+    optional: Option<&'a str>,
+    args: Vec<&'a str>,
+}
+
+/// Parses the macros in `source` into records for the `write_*` functions of a store.
+/// History items receive timestamps from `try_current_timestamp`. On targets without a system
+/// clock, `\addhistory` is therefore reported as not understood.
+pub fn parse_loretex(source: &str) -> LoreTexImport {
+    let mut import = LoreTexImport::default();
+    let mut current: Option<Label> = None;
+    let mut scanner = Scanner::new(source);
+
+    while let Some(c) = scanner.peek() {
+        let start = scanner.pos;
+        if c == '%' {
+            scanner.skip_line();
+        } else if c.is_whitespace() {
+            scanner.pos += c.len_utf8();
+        } else if c == '\\' {
+            scanner.pos += 1;
+            let name = scanner.read_command_name();
+            let arity = match name {
+                "newentity" => Some(1),
+                "setdescriptor" | "addhistory" => Some(2),
+                "addparent" => Some(1),
+                _ => None,
+            };
+            let Some(arity) = arity else {
+                while scanner.read_group('[', ']').is_some()
+                    || scanner.read_group('{', '}').is_some()
+                {}
+                if !SKIPPED_COMMANDS.contains(&name) {
+                    import.unparsed.push(UnparsedConstruct {
+                        line: scanner.line(start),
+                        text: source[start..scanner.pos].to_string(),
+                        reason: "Unknown macro".to_string(),
+                    });
+                }
+                continue;
+            };
+
+            scanner.skip_whitespace();
+            let optional = scanner.read_group('[', ']');
+            let mut args = Vec::new();
+            for _ in 0..arity {
+                scanner.skip_whitespace();
+                match scanner.read_group('{', '}') {
+                    Some(arg) => args.push(arg),
+                    None => break,
+                }
+            }
+            let command = Command {
+                line: scanner.line(start),
+                text: source[start..scanner.pos].trim_end(),
+                optional,
+                args,
+            };
+            if command.args.len() < arity {
+                import
+                    .unparsed
+                    .push(unparsed(&command, format!("Expected {} arguments", arity)));
+                continue;
+            }
+            if let Err(reason) = apply_command(name, &command, &mut current, &mut import) {
+                import.unparsed.push(unparsed(&command, reason));
+            }
+        } else {
+            let rest = scanner.rest();
+            let len = rest.find(['\\', '%']).unwrap_or(rest.len());
+            scanner.pos += len;
+            let text = rest[..len].trim_end();
+            import.unparsed.push(UnparsedConstruct {
+                line: scanner.line(start),
+                text: text.to_string(),
+                reason: "Text outside of a macro".to_string(),
+            });
+        }
+    }
+    import
+}
+
+fn unparsed(command: &Command, reason: String) -> UnparsedConstruct {
+    UnparsedConstruct {
+        line: command.line,
+        text: command.text.to_string(),
+        reason,
+    }
+}
+
+fn message(error: LoreCoreError) -> String {
+    match error {
+        LoreCoreError::FileError(message)
+        | LoreCoreError::InputError(message)
+        | LoreCoreError::SqlError(message) => message,
+    }
+}
+
+fn apply_command(
+    name: &str,
+    command: &Command,
+    current: &mut Option<Label>,
+    import: &mut LoreTexImport,
+) -> Result<(), String> {
+    if name == "newentity" {
+        let label = command.args[0].trim();
+        if label.is_empty() {
+            return Err("The label is empty".to_string());
+        }
+        *current = Some(label.into());
+        return Ok(());
+    }
+    let Some(label) = current.clone() else {
+        return Err("No entity has been started with \\newentity".to_string());
+    };
+    match name {
+        "setdescriptor" => {
+            let descriptor: Descriptor = command.args[0].trim().into();
+            if import
+                .cols
+                .iter()
+                .any(|col| col.label == label && col.descriptor == descriptor)
+            {
+                return Err(format!(
+                    "Entity \"{}\" already has a descriptor \"{}\"",
+                    label, descriptor
+                ));
+            }
+            import.cols.push(EntityColumn {
+                label,
+                descriptor,
+                description: command.args[1].trim().into(),
+            });
+        }
+        "addhistory" => {
+            let year = Year::try_from(command.args[0].trim()).map_err(message)?;
+            let day = match command.optional.map(str::trim) {
+                None | Some("") => Day::NONE,
+                Some(day) => Day::try_from(day).map_err(message)?,
+            };
+            let content = command.args[1].trim();
+            let mut properties = HashMap::new();
+            if !content.contains(&entityref(&label)) {
+                properties.insert(
+                    "additional_concerns".to_string(),
+                    Value::Array(vec![Value::String(label.to_string())]),
+                );
+            }
+            import.items.push(HistoryItem {
+                timestamp: try_current_timestamp().map_err(message)?,
+                year,
+                day,
+                content: content.into(),
+                properties: properties.into(),
+            });
+        }
+        "addparent" => {
+            let role = match command.optional.map(str::trim) {
+                None | Some("") => Role::NONE,
+                Some(role) => role.into(),
+            };
+            let parent: Parent = command.args[0].trim().into();
+            if import.rels.iter().any(|rel| {
+                rel.parent == parent && rel.child.to_str() == label.to_str() && rel.role == role
+            }) {
+                return Err(format!(
+                    "Entity \"{}\" already has a parent \"{}\" with role \"{}\"",
+                    label, parent, role
+                ));
+            }
+            import.rels.push(EntityRelationship {
+                parent,
+                child: label.into(),
+                role,
+                ordinal: Ordinal::NONE,
+            });
+        }
+        _ => return Err("Unknown macro".to_string()),
+    }
+    Ok(())
+}
+
+/// Parses `source` and writes everything that could be understood into `store`.
+/// Returns the constructs that were not imported.
+pub fn import_loretex(
+    store: &impl LoreStore,
+    source: &str,
+) -> Result<Vec<UnparsedConstruct>, LoreCoreError> {
+    let import = parse_loretex(source);
+    store.in_transaction(|| {
+        store.write_entity_columns(import.cols)?;
+        store.write_history_items(import.items)?;
+        store.write_relationships(import.rels)
+    })?;
+    Ok(import.unparsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_respect_nesting_and_escapes() {
+        let mut scanner = Scanner::new("{a {b} \\} c}rest");
+        assert_eq!(scanner.read_group('{', '}'), Some("a {b} \\} c"));
+        assert_eq!(scanner.rest(), "rest");
+
+        let mut scanner = Scanner::new("{unterminated");
+        assert_eq!(scanner.read_group('{', '}'), None);
+        assert_eq!(scanner.pos, 0);
+    }
+
+    #[test]
+    fn macros_are_turned_into_records() {
+        let source = r"% A comment \newentity{ignored}
+\documentclass{article}
+\newentity{Hero}
+\setdescriptor{Biography}{Born in \textbf{a village}.}
+\addhistory[3]{-12}{\entityref{Hero} is born.}
+\addhistory{20}{The war begins.}
+\addparent[mentor]{Old Mage}
+";
+        let import = parse_loretex(source);
+        assert!(import.unparsed.is_empty(), "{:?}", import.unparsed);
+        assert_eq!(
+            import.cols,
+            vec![EntityColumn {
+                label: "Hero".into(),
+                descriptor: "Biography".into(),
+                description: "Born in \\textbf{a village}.".into(),
+            }]
+        );
+        assert_eq!(import.items.len(), 2);
+        assert_eq!(import.items[0].year, (-12).into());
+        assert_eq!(import.items[0].day, 3.into());
+        assert!(import.items[0].properties.additional_concerns().is_empty());
+        assert_eq!(
+            import.items[1].properties.additional_concerns(),
+            vec!["Hero".to_string()]
+        );
+        assert_eq!(
+            import.rels,
+            vec![EntityRelationship {
+                parent: "Old Mage".into(),
+                child: "Hero".into(),
+                role: "mentor".into(),
+                ordinal: Ordinal::NONE,
+            }]
+        );
+    }
+
+    #[test]
+    fn constructs_that_are_not_understood_are_reported() {
+        let source = r"\setdescriptor{Age}{12}
+\newentity{Hero}
+Some loose text.
+\customspell{Fireball}[3]
+\addhistory{soon}{Something.}
+\setdescriptor{Age}
+";
+        let import = parse_loretex(source);
+        let reported: Vec<(usize, &str)> = import
+            .unparsed
+            .iter()
+            .map(|u| (u.line, u.text.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (1, "\\setdescriptor{Age}{12}"),
+                (3, "Some loose text."),
+                (4, "\\customspell{Fireball}[3]"),
+                (5, "\\addhistory{soon}{Something.}"),
+                (6, "\\setdescriptor{Age}"),
+            ]
+        );
+        assert!(import.cols.is_empty());
+        assert!(import.items.is_empty());
+    }
+}
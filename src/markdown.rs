@@ -0,0 +1,243 @@
+//! Export of the public part of the lore as Markdown pages, for example for a player-facing wiki.
+//!
+//! The pages are `index.md` listing all entities, `history.md` listing all history items in
+//! chronological order and `entities/<label>.md` for every entity. `\entityref{label}` becomes a
+//! relative link to the page of the label.
+//!
+//! Protected labels, descriptors and roles (those starting with an underscore) and secret history
+//! items are left out. References to protected labels are replaced by `(unknown)`, so that their
+//! names appear on no page.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    errors::LoreCoreError,
+    sql::search_params::{EntityColumnSearchParams, HistoryItemSearchParams},
+    store::LoreStore,
+    types::{history::replace_entityrefs, *},
+};
+
+const ENTITIES_DIR: &str = "entities";
+const UNKNOWN_LABEL: &str = "(unknown)";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarkdownPage {
+    /// The path of the page, relative to the root of the site.
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Escapes the characters that Markdown would interpret as formatting.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '!'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// The file name of the page of an entity, which can be used in links without further encoding.
+pub fn markdown_file_name(label: &Label) -> String {
+    label.to_alphanumeric_key() + ".md"
+}
+
+pub fn export_markdown(store: &impl LoreStore, dir: &Path) -> Result<(), LoreCoreError> {
+    let entities = store.read_entities(EntityColumnSearchParams::empty())?;
+    let history = store.read_history_items(HistoryItemSearchParams::empty())?;
+    let pages = render_markdown(&entities, &history);
+
+    let entities_dir = dir.join(ENTITIES_DIR);
+    fs::create_dir_all(&entities_dir).map_err(|e| {
+        LoreCoreError::FileError(format!(
+            "Failed to create directory {}: {}",
+            entities_dir.display(),
+            e
+        ))
+    })?;
+    for page in &pages {
+        let path = dir.join(&page.path);
+        fs::write(&path, &page.content).map_err(|e| {
+            LoreCoreError::FileError(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+    }
+    remove_stale_pages(dir, &entities_dir, &pages)
+}
+
+/// Removes the pages of entities that no longer exist or have become protected, so that they are
+/// not published by accident.
+fn remove_stale_pages(
+    dir: &Path,
+    entities_dir: &Path,
+    pages: &[MarkdownPage],
+) -> Result<(), LoreCoreError> {
+    let current: BTreeSet<PathBuf> = pages.iter().map(|page| dir.join(&page.path)).collect();
+    let read_error = |e: std::io::Error| {
+        LoreCoreError::FileError(format!(
+            "Failed to read directory {}: {}",
+            entities_dir.display(),
+            e
+        ))
+    };
+    for entry in fs::read_dir(entities_dir).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+        let is_page = path.extension().is_some_and(|ext| ext == "md");
+        if is_page && !current.contains(&path) {
+            fs::remove_file(&path).map_err(|e| {
+                LoreCoreError::FileError(format!("Failed to remove {}: {}", path.display(), e))
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders the index page, the history page and one page per public entity.
+pub fn render_markdown(entities: &[Entity], history: &[HistoryItem]) -> Vec<MarkdownPage> {
+    let public: Vec<&Entity> = entities
+        .iter()
+        .filter(|entity| !entity.label.is_protected())
+        .collect();
+    let labels: BTreeSet<&Label> = public.iter().map(|entity| &entity.label).collect();
+    let link = |label: &Label, prefix: &str| {
+        if label.is_protected() {
+            UNKNOWN_LABEL.to_string()
+        } else if labels.contains(label) {
+            format!(
+                "[{}]({}{})",
+                escape_markdown(label.to_str()),
+                prefix,
+                markdown_file_name(label)
+            )
+        } else {
+            escape_markdown(label.to_str())
+        }
+    };
+
+    let mut index = "# Index\n\n".to_string();
+    index += "[History](history.md)\n\n";
+    for entity in &public {
+        index += &format!("- {}\n", link(&entity.label, "entities/"));
+    }
+
+    let mut history_page = "# History\n".to_string();
+    let mut current_year = None;
+    for item in history.iter().filter(|item| !item.properties.is_secret()) {
+        if current_year != Some(item.year) {
+            history_page += &format!("\n## Year {}\n\n", item.year);
+            current_year = Some(item.year);
+        }
+        history_page += &timeline_entry(item, |label| link(label, "entities/"));
+    }
+
+    let mut pages = vec![
+        MarkdownPage {
+            path: "index.md".into(),
+            content: index,
+        },
+        MarkdownPage {
+            path: "history.md".into(),
+            content: history_page,
+        },
+    ];
+    for entity in public {
+        pages.push(MarkdownPage {
+            path: Path::new(ENTITIES_DIR).join(markdown_file_name(&entity.label)),
+            content: entity_page(entity, |label| link(label, "")),
+        });
+    }
+    pages
+}
+
+fn entity_page(entity: &Entity, link: impl Fn(&Label) -> String) -> String {
+    let mut page = format!("# {}\n", escape_markdown(entity.label.to_str()));
+    for (descriptor, description) in &entity.descriptors {
+        if descriptor.is_protected() {
+            continue;
+        }
+        page += &format!("\n## {}\n\n", escape_markdown(descriptor.to_str()));
+        page += &replace_entityrefs(description.to_str(), &link);
+        page += "\n";
+    }
+
+    let is_public = |rel: &&EntityRelationship| {
+        !rel.role.is_protected()
+            && !Label::from(rel.parent.clone()).is_protected()
+            && !Label::from(rel.child.clone()).is_protected()
+    };
+    let parents: Vec<_> = entity.parents.iter().filter(is_public).collect();
+    let children: Vec<_> = entity.children.iter().filter(is_public).collect();
+    if !parents.is_empty() || !children.is_empty() {
+        page += "\n## Relationships\n\n";
+        for rel in parents {
+            page += &format!("- Child of {}", link(&rel.parent.clone().into()));
+            page += &role_suffix(&rel.role);
+        }
+        for rel in children {
+            page += &format!("- Parent of {}", link(&rel.child.clone().into()));
+            page += &role_suffix(&rel.role);
+        }
+    }
+
+    let history: Vec<_> = entity
+        .history
+        .iter()
+        .filter(|item| !item.properties.is_secret())
+        .collect();
+    if !history.is_empty() {
+        page += "\n## History\n\n";
+        for item in history {
+            page += &timeline_entry(item, &link);
+        }
+    }
+    page
+}
+
+fn timeline_entry(item: &HistoryItem, link: impl Fn(&Label) -> String) -> String {
+    let date = match item.day.to_optional_int() {
+        Some(day) => format!("{}, day {}", item.year, day),
+        None => item.year.to_string(),
+    };
+    format!(
+        "- **{}**: {}\n",
+        date,
+        replace_entityrefs(item.content.to_str(), link)
+    )
+}
+
+fn role_suffix(role: &Role) -> String {
+    match role.to_optional_string() {
+        Some(role) => format!(" ({})\n", escape_markdown(&role)),
+        None => "\n".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting_characters_are_escaped() {
+        assert_eq!(
+            escape_markdown("*a* [b] _c_ #d"),
+            "\\*a\\* \\[b\\] \\_c\\_ \\#d"
+        );
+    }
+
+    #[test]
+    fn file_names_need_no_encoding_in_links() {
+        assert_eq!(markdown_file_name(&"Hero".into()), "Hero.md");
+        assert_eq!(
+            markdown_file_name(&"hero's dog".into()),
+            "hero-27s-20dog.md"
+        );
+    }
+}
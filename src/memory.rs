@@ -0,0 +1,356 @@
+//! A lore database that lives entirely in memory.
+//! It implements `LoreStore` like the SQLite backend, but needs neither a file system nor
+//! native libraries, so it can be used in the browser and in fast unit tests.
+
+use std::sync::{Mutex, MutexGuard};
+
+use crate::{
+    errors::LoreCoreError,
+    sql::search_params::{
+        EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams,
+    },
+    store::LoreStore,
+    types::*,
+};
+
+#[derive(Clone, Debug, Default)]
+struct MemoryState {
+    cols: Vec<EntityColumn>,
+    items: Vec<HistoryItem>,
+    rels: Vec<EntityRelationship>,
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryDatabase {
+    state: Mutex<MemoryState>,
+    /// The states at the beginning of the open transactions, innermost last.
+    snapshots: Mutex<Vec<MemoryState>>,
+}
+
+impl MemoryDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock_state(&self) -> Result<MutexGuard<'_, MemoryState>, LoreCoreError> {
+        self.state.lock().map_err(|_| {
+            LoreCoreError::InputError(
+                "The database is unusable, because a previous operation panicked.".to_string(),
+            )
+        })
+    }
+
+    fn lock_snapshots(&self) -> Result<MutexGuard<'_, Vec<MemoryState>>, LoreCoreError> {
+        self.snapshots.lock().map_err(|_| {
+            LoreCoreError::InputError(
+                "The database is unusable, because a previous operation panicked.".to_string(),
+            )
+        })
+    }
+}
+
+impl LoreStore for MemoryDatabase {
+    /// Transactions can be nested.
+    fn begin_transaction(&self) -> Result<(), LoreCoreError> {
+        let state = self.lock_state()?.clone();
+        self.lock_snapshots()?.push(state);
+        Ok(())
+    }
+
+    fn commit_transaction(&self) -> Result<(), LoreCoreError> {
+        match self.lock_snapshots()?.pop() {
+            Some(_) => Ok(()),
+            None => Err(no_open_transaction()),
+        }
+    }
+
+    fn rollback_transaction(&self) -> Result<(), LoreCoreError> {
+        match self.lock_snapshots()?.pop() {
+            Some(snapshot) => {
+                *self.lock_state()? = snapshot;
+                Ok(())
+            }
+            None => Err(no_open_transaction()),
+        }
+    }
+
+    fn write_entity_columns(&self, cols: Vec<EntityColumn>) -> Result<(), LoreCoreError> {
+        let mut state = self.lock_state()?;
+        for (i, col) in cols.iter().enumerate() {
+            if state
+                .cols
+                .iter()
+                .chain(&cols[..i])
+                .any(|c| c.label == col.label && c.descriptor == col.descriptor)
+            {
+                return Err(column_exists(&col.label, &col.descriptor));
+            }
+        }
+        state.cols.extend(cols);
+        Ok(())
+    }
+
+    fn relabel_entity(&self, old_label: &Label, new_label: &Label) -> Result<(), LoreCoreError> {
+        let mut state = self.lock_state()?;
+        if old_label != new_label {
+            let moved = state.cols.iter().filter(|col| &col.label == old_label);
+            for col in moved {
+                if state
+                    .cols
+                    .iter()
+                    .any(|c| &c.label == new_label && c.descriptor == col.descriptor)
+                {
+                    return Err(column_exists(new_label, &col.descriptor));
+                }
+            }
+        }
+        for col in state.cols.iter_mut() {
+            if &col.label == old_label {
+                col.label = new_label.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_entity(&self, label: Label) -> Result<(), LoreCoreError> {
+        self.lock_state()?.cols.retain(|col| col.label != label);
+        Ok(())
+    }
+
+    fn change_entity_descriptor(
+        &self,
+        (label, old_descriptor): (&Label, Descriptor),
+        new_descriptor: &Descriptor,
+    ) -> Result<(), LoreCoreError> {
+        let mut state = self.lock_state()?;
+        let has_column = |descriptor: &Descriptor| {
+            state
+                .cols
+                .iter()
+                .any(|col| &col.label == label && &col.descriptor == descriptor)
+        };
+        if &old_descriptor != new_descriptor
+            && has_column(&old_descriptor)
+            && has_column(new_descriptor)
+        {
+            return Err(column_exists(label, new_descriptor));
+        }
+        for col in state.cols.iter_mut() {
+            if &col.label == label && col.descriptor == old_descriptor {
+                col.descriptor = new_descriptor.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_entity_column(
+        &self,
+        (label, descriptor): (Label, Descriptor),
+    ) -> Result<(), LoreCoreError> {
+        self.lock_state()?
+            .cols
+            .retain(|col| col.label != label || col.descriptor != descriptor);
+        Ok(())
+    }
+
+    fn change_entity_description(
+        &self,
+        (label, descriptor): (&Label, &Descriptor),
+        new_description: &Description,
+    ) -> Result<(), LoreCoreError> {
+        for col in self.lock_state()?.cols.iter_mut() {
+            if &col.label == label && &col.descriptor == descriptor {
+                col.description = new_description.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn read_entity_columns(
+        &self,
+        search_params: EntityColumnSearchParams,
+    ) -> Result<Vec<EntityColumn>, LoreCoreError> {
+        let mut cols: Vec<_> = self
+            .lock_state()?
+            .cols
+            .iter()
+            .filter(|col| search_params.matches(col))
+            .cloned()
+            .collect();
+        cols.sort();
+        Ok(cols)
+    }
+
+    fn write_history_items(&self, items: Vec<HistoryItem>) -> Result<(), LoreCoreError> {
+        let mut state = self.lock_state()?;
+        for (i, item) in items.iter().enumerate() {
+            if state
+                .items
+                .iter()
+                .chain(&items[..i])
+                .any(|other| other.timestamp == item.timestamp)
+            {
+                return Err(LoreCoreError::InputError(format!(
+                    "There already is a history item with timestamp {}.",
+                    item.timestamp
+                )));
+            }
+        }
+        state.items.extend(items);
+        Ok(())
+    }
+
+    fn redate_history_item(
+        &self,
+        timestamp: Timestamp,
+        year: Year,
+        day: Day,
+    ) -> Result<(), LoreCoreError> {
+        for item in self.lock_state()?.items.iter_mut() {
+            if item.timestamp == timestamp {
+                item.year = year;
+                item.day = day;
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_history_item(&self, timestamp: Timestamp) -> Result<(), LoreCoreError> {
+        self.lock_state()?
+            .items
+            .retain(|item| item.timestamp != timestamp);
+        Ok(())
+    }
+
+    fn change_history_item_content(
+        &self,
+        timestamp: Timestamp,
+        content: &HistoryItemContent,
+    ) -> Result<(), LoreCoreError> {
+        for item in self.lock_state()?.items.iter_mut() {
+            if item.timestamp == timestamp {
+                item.content = content.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn change_history_item_properties(
+        &self,
+        timestamp: Timestamp,
+        properties: &HistoryItemProperties,
+    ) -> Result<(), LoreCoreError> {
+        for item in self.lock_state()?.items.iter_mut() {
+            if item.timestamp == timestamp {
+                item.properties = properties.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn read_history_items(
+        &self,
+        search_params: HistoryItemSearchParams,
+    ) -> Result<Vec<HistoryItem>, LoreCoreError> {
+        let mut items: Vec<_> = self
+            .lock_state()?
+            .items
+            .iter()
+            .filter(|item| search_params.matches(item))
+            .cloned()
+            .collect();
+        items.sort();
+        Ok(items)
+    }
+
+    fn write_relationships(&self, rels: Vec<EntityRelationship>) -> Result<(), LoreCoreError> {
+        let mut state = self.lock_state()?;
+        for (i, rel) in rels.iter().enumerate() {
+            rel.ordinal.check_range()?;
+            if state
+                .rels
+                .iter()
+                .chain(&rels[..i])
+                .any(|r| is_same_relationship(r, rel))
+            {
+                return Err(relationship_exists(rel));
+            }
+        }
+        state.rels.extend(rels);
+        Ok(())
+    }
+
+    fn change_relationship_role(
+        &self,
+        old_relationship: EntityRelationship,
+        new_role: &Role,
+    ) -> Result<(), LoreCoreError> {
+        let mut state = self.lock_state()?;
+        let new_relationship = EntityRelationship {
+            role: new_role.clone(),
+            ..old_relationship.clone()
+        };
+        let exists =
+            |rel: &EntityRelationship| state.rels.iter().any(|r| is_same_relationship(r, rel));
+        if &old_relationship.role != new_role
+            && exists(&old_relationship)
+            && exists(&new_relationship)
+        {
+            return Err(relationship_exists(&new_relationship));
+        }
+        for rel in state.rels.iter_mut() {
+            if is_same_relationship(rel, &old_relationship) {
+                rel.role = new_role.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_relationship(&self, relationship: EntityRelationship) -> Result<(), LoreCoreError> {
+        self.lock_state()?
+            .rels
+            .retain(|rel| !is_same_relationship(rel, &relationship));
+        Ok(())
+    }
+
+    fn read_relationships(
+        &self,
+        search_params: RelationshipSearchParams,
+    ) -> Result<Vec<EntityRelationship>, LoreCoreError> {
+        let mut rels: Vec<_> = self
+            .lock_state()?
+            .rels
+            .iter()
+            .filter(|rel| search_params.matches(rel))
+            .cloned()
+            .collect();
+        rels.sort();
+        Ok(rels)
+    }
+}
+
+/// Relationships are identified by parent, child and role, like the primary key of the SQL table.
+fn is_same_relationship(a: &EntityRelationship, b: &EntityRelationship) -> bool {
+    a.parent == b.parent && a.child == b.child && a.role == b.role
+}
+
+fn column_exists(label: &Label, descriptor: &Descriptor) -> LoreCoreError {
+    LoreCoreError::InputError(format!(
+        "Entity \"{}\" already has a descriptor \"{}\".",
+        label,
+        descriptor.to_str()
+    ))
+}
+
+fn relationship_exists(rel: &EntityRelationship) -> LoreCoreError {
+    LoreCoreError::InputError(format!(
+        "The relationship between \"{}\" and \"{}\" with role \"{}\" already exists.",
+        rel.parent.to_str(),
+        rel.child.to_str(),
+        rel.role.to_str()
+    ))
+}
+
+fn no_open_transaction() -> LoreCoreError {
+    LoreCoreError::InputError("There is no open transaction.".to_string())
+}
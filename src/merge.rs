@@ -0,0 +1,217 @@
+//! Three-way merge of parallel edits made in two copies of the same lore.
+//!
+//! Both copies are compared against their common ancestor with `diff::diff_stores`. A change made
+//! on only one side is applied, as are identical changes made on both sides. A record that was
+//! changed differently on both sides is a conflict: it is left as it is in the store that is
+//! merged into and reported for manual resolution.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    diff::{Change, LoreDiff, column_key, diff_stores, item_key, relationship_key},
+    errors::LoreCoreError,
+    store::LoreStore,
+    types::*,
+};
+
+/// A record that was changed differently in both copies. `None` means that the record does not
+/// exist in that version, because it was never added or because it was removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeConflict<T> {
+    pub base: Option<T>,
+    pub ours: Option<T>,
+    pub theirs: Option<T>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    /// The changes from `theirs` that were applied to `ours`.
+    pub applied: LoreDiff,
+    pub entity_column_conflicts: Vec<MergeConflict<EntityColumn>>,
+    pub history_item_conflicts: Vec<MergeConflict<HistoryItem>>,
+    pub relationship_conflicts: Vec<MergeConflict<EntityRelationship>>,
+}
+
+impl MergeReport {
+    pub fn has_conflicts(&self) -> bool {
+        !self.entity_column_conflicts.is_empty()
+            || !self.history_item_conflicts.is_empty()
+            || !self.relationship_conflicts.is_empty()
+    }
+}
+
+/// Merges the changes that turned `base` into `theirs` into `ours`, which has been edited in
+/// parallel. `base` and `theirs` are only read.
+/// The changes are applied in one transaction, so that `ours` is left untouched if writing fails.
+pub fn merge_stores(
+    base: &impl LoreStore,
+    ours: &impl LoreStore,
+    theirs: &impl LoreStore,
+) -> Result<MergeReport, LoreCoreError> {
+    let our_changes = diff_stores(base, ours)?;
+    let their_changes = diff_stores(base, theirs)?;
+
+    let mut report = MergeReport::default();
+    (
+        report.applied.entity_columns,
+        report.entity_column_conflicts,
+    ) = merge_changes(
+        our_changes.entity_columns,
+        their_changes.entity_columns,
+        column_key,
+    );
+    (report.applied.history_items, report.history_item_conflicts) = merge_changes(
+        our_changes.history_items,
+        their_changes.history_items,
+        item_key,
+    );
+    (report.applied.relationships, report.relationship_conflicts) = merge_changes(
+        our_changes.relationships,
+        their_changes.relationships,
+        relationship_key,
+    );
+
+    ours.in_transaction(|| apply_diff(ours, &report.applied))?;
+    Ok(report)
+}
+
+/// Splits their changes into those that need to be applied to ours and conflicts.
+fn merge_changes<T, K>(
+    ours: Vec<Change<T>>,
+    theirs: Vec<Change<T>>,
+    key: impl Fn(&T) -> K,
+) -> (Vec<Change<T>>, Vec<MergeConflict<T>>)
+where
+    T: Clone + PartialEq,
+    K: Ord,
+{
+    let change_key = |change: &Change<T>| {
+        let record = change.old_record().or(change.new_record());
+        record
+            .map(&key)
+            .expect("A change has an old or a new record")
+    };
+    let mut ours: BTreeMap<K, Change<T>> = ours
+        .into_iter()
+        .map(|change| (change_key(&change), change))
+        .collect();
+
+    let mut applied = Vec::new();
+    let mut conflicts = Vec::new();
+    for their_change in theirs {
+        match ours.remove(&change_key(&their_change)) {
+            None => applied.push(their_change),
+            Some(our_change) if our_change.new_record() == their_change.new_record() => {}
+            Some(our_change) => conflicts.push(MergeConflict {
+                base: their_change.old_record().cloned(),
+                ours: our_change.new_record().cloned(),
+                theirs: their_change.new_record().cloned(),
+            }),
+        }
+    }
+    (applied, conflicts)
+}
+
+fn apply_diff(store: &impl LoreStore, diff: &LoreDiff) -> Result<(), LoreCoreError> {
+    let mut added_cols = Vec::new();
+    for change in &diff.entity_columns {
+        match change {
+            Change::Added(col) => added_cols.push(col.clone()),
+            Change::Removed(col) => {
+                store.delete_entity_column((col.label.clone(), col.descriptor.clone()))?
+            }
+            Change::Modified { new, .. } => {
+                store.change_entity_description((&new.label, &new.descriptor), &new.description)?
+            }
+        }
+    }
+    store.write_entity_columns(added_cols)?;
+
+    let mut added_items = Vec::new();
+    for change in &diff.history_items {
+        match change {
+            Change::Added(item) => added_items.push(item.clone()),
+            Change::Removed(item) => store.delete_history_item(item.timestamp)?,
+            Change::Modified { old, new } => {
+                if old.year != new.year || old.day != new.day {
+                    store.redate_history_item(new.timestamp, new.year, new.day)?;
+                }
+                if old.content != new.content {
+                    store.change_history_item_content(new.timestamp, &new.content)?;
+                }
+                if old.properties != new.properties {
+                    store.change_history_item_properties(new.timestamp, &new.properties)?;
+                }
+            }
+        }
+    }
+    store.write_history_items(added_items)?;
+
+    let mut added_rels = Vec::new();
+    for change in &diff.relationships {
+        match change {
+            Change::Added(rel) => added_rels.push(rel.clone()),
+            Change::Removed(rel) => store.delete_relationship(rel.clone())?,
+            // Only the ordinal can differ, and there is no way to change it in place.
+            Change::Modified { old, new } => {
+                store.delete_relationship(old.clone())?;
+                added_rels.push(new.clone());
+            }
+        }
+    }
+    store.write_relationships(added_rels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_on_one_side_are_applied_and_different_ones_conflict() {
+        let ours = vec![
+            Change::Modified {
+                old: (1, "a"),
+                new: (1, "ours"),
+            },
+            Change::Removed((2, "b")),
+            Change::Added((3, "same")),
+        ];
+        let theirs = vec![
+            Change::Modified {
+                old: (1, "a"),
+                new: (1, "theirs"),
+            },
+            Change::Added((3, "same")),
+            Change::Removed((4, "d")),
+        ];
+        let (applied, conflicts) = merge_changes(ours, theirs, |record| record.0);
+        assert_eq!(applied, vec![Change::Removed((4, "d"))]);
+        assert_eq!(
+            conflicts,
+            vec![MergeConflict {
+                base: Some((1, "a")),
+                ours: Some((1, "ours")),
+                theirs: Some((1, "theirs")),
+            }]
+        );
+    }
+
+    #[test]
+    fn removal_conflicts_with_modification() {
+        let ours = vec![Change::Removed((1, "a"))];
+        let theirs = vec![Change::Modified {
+            old: (1, "a"),
+            new: (1, "theirs"),
+        }];
+        let (applied, conflicts) = merge_changes(ours, theirs, |record| record.0);
+        assert!(applied.is_empty());
+        assert_eq!(
+            conflicts,
+            vec![MergeConflict {
+                base: Some((1, "a")),
+                ours: None,
+                theirs: Some((1, "theirs")),
+            }]
+        );
+    }
+}
@@ -0,0 +1,385 @@
+//! The append-only log of all modifications of a `LoreDatabase`.
+//!
+//! Every call of a writing, updating or deleting method that changes at least one row appends one
+//! entry, in the same transaction as the change itself. The entry holds all affected rows of the
+//! table as they were before and as they are after the call, so that multi-row operations like
+//! relabeling an entity are recorded as one change.
+//!
+//! The entries also form the journal for `LoreDatabase::undo` and `LoreDatabase::redo`, which
+//! only change the state of entries instead of appending new ones.
+
+use ::diesel::prelude::*;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    errors::{LoreCoreError, sql_loading_error},
+    json::{JsonEntityColumn, JsonHistoryItem, JsonRelationship},
+    timestamp::current_timestamp,
+    types::*,
+};
+
+use super::{lore_database::LoreDatabase, schema::change_log, sql_types::*};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChangeOperation {
+    WriteEntityColumns,
+    RelabelEntity,
+    DeleteEntity,
+    ChangeEntityDescriptor,
+    DeleteEntityColumn,
+    ChangeEntityDescription,
+    WriteHistoryItems,
+    RedateHistoryItem,
+    DeleteHistoryItem,
+    ChangeHistoryItemContent,
+    ChangeHistoryItemProperties,
+    WriteRelationships,
+    ChangeRelationshipRole,
+    DeleteRelationship,
+    ReorderChildren,
+    Reparent,
+}
+
+const OPERATIONS: [ChangeOperation; 16] = [
+    ChangeOperation::WriteEntityColumns,
+    ChangeOperation::RelabelEntity,
+    ChangeOperation::DeleteEntity,
+    ChangeOperation::ChangeEntityDescriptor,
+    ChangeOperation::DeleteEntityColumn,
+    ChangeOperation::ChangeEntityDescription,
+    ChangeOperation::WriteHistoryItems,
+    ChangeOperation::RedateHistoryItem,
+    ChangeOperation::DeleteHistoryItem,
+    ChangeOperation::ChangeHistoryItemContent,
+    ChangeOperation::ChangeHistoryItemProperties,
+    ChangeOperation::WriteRelationships,
+    ChangeOperation::ChangeRelationshipRole,
+    ChangeOperation::DeleteRelationship,
+    ChangeOperation::ReorderChildren,
+    ChangeOperation::Reparent,
+];
+
+impl ChangeOperation {
+    /// The name of the `LoreDatabase` method, as stored in the database.
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ChangeOperation::WriteEntityColumns => "write_entity_columns",
+            ChangeOperation::RelabelEntity => "relabel_entity",
+            ChangeOperation::DeleteEntity => "delete_entity",
+            ChangeOperation::ChangeEntityDescriptor => "change_entity_descriptor",
+            ChangeOperation::DeleteEntityColumn => "delete_entity_column",
+            ChangeOperation::ChangeEntityDescription => "change_entity_description",
+            ChangeOperation::WriteHistoryItems => "write_history_items",
+            ChangeOperation::RedateHistoryItem => "redate_history_item",
+            ChangeOperation::DeleteHistoryItem => "delete_history_item",
+            ChangeOperation::ChangeHistoryItemContent => "change_history_item_content",
+            ChangeOperation::ChangeHistoryItemProperties => "change_history_item_properties",
+            ChangeOperation::WriteRelationships => "write_relationships",
+            ChangeOperation::ChangeRelationshipRole => "change_relationship_role",
+            ChangeOperation::DeleteRelationship => "delete_relationship",
+            ChangeOperation::ReorderChildren => "reorder_children",
+            ChangeOperation::Reparent => "reparent",
+        }
+    }
+}
+
+impl TryFrom<&str> for ChangeOperation {
+    type Error = LoreCoreError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        OPERATIONS
+            .into_iter()
+            .find(|operation| operation.to_str() == value)
+            .ok_or_else(|| {
+                LoreCoreError::InputError(format!("Unknown change log operation \"{}\".", value))
+            })
+    }
+}
+
+/// The state of a change with respect to undo and redo.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JournalState {
+    Applied,
+    /// The change has been undone and can be redone.
+    Undone,
+    /// The change has been undone and can no longer be redone, because other changes were made
+    /// afterwards.
+    Discarded,
+}
+
+impl JournalState {
+    pub(super) fn to_int(self) -> i32 {
+        match self {
+            JournalState::Applied => 0,
+            JournalState::Undone => 1,
+            JournalState::Discarded => 2,
+        }
+    }
+}
+
+impl TryFrom<i32> for JournalState {
+    type Error = LoreCoreError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(JournalState::Applied),
+            1 => Ok(JournalState::Undone),
+            2 => Ok(JournalState::Discarded),
+            _ => Err(LoreCoreError::SqlError(format!(
+                "Unknown change log state {}.",
+                value
+            ))),
+        }
+    }
+}
+
+/// The rows of one table that were affected by a change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChangedRecords {
+    EntityColumns(Vec<EntityColumn>),
+    HistoryItems(Vec<HistoryItem>),
+    Relationships(Vec<EntityRelationship>),
+}
+
+impl ChangedRecords {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            ChangedRecords::EntityColumns(cols) => cols.is_empty(),
+            ChangedRecords::HistoryItems(items) => items.is_empty(),
+            ChangedRecords::Relationships(rels) => rels.is_empty(),
+        }
+    }
+
+    fn to_json(&self) -> Result<String, LoreCoreError> {
+        match self {
+            ChangedRecords::EntityColumns(cols) => records_to_json::<_, JsonEntityColumn>(cols),
+            ChangedRecords::HistoryItems(items) => records_to_json::<_, JsonHistoryItem>(items),
+            ChangedRecords::Relationships(rels) => records_to_json::<_, JsonRelationship>(rels),
+        }
+    }
+
+    /// Parses the values stored for an entry, whose table is determined by its operation.
+    fn from_json(operation: ChangeOperation, json: &str) -> Result<Self, LoreCoreError> {
+        use ChangeOperation::*;
+        Ok(match operation {
+            WriteEntityColumns
+            | RelabelEntity
+            | DeleteEntity
+            | ChangeEntityDescriptor
+            | DeleteEntityColumn
+            | ChangeEntityDescription => {
+                ChangedRecords::EntityColumns(records_from_json::<JsonEntityColumn, _>(json)?)
+            }
+            WriteHistoryItems
+            | RedateHistoryItem
+            | DeleteHistoryItem
+            | ChangeHistoryItemContent
+            | ChangeHistoryItemProperties => {
+                ChangedRecords::HistoryItems(records_from_json::<JsonHistoryItem, _>(json)?)
+            }
+            WriteRelationships
+            | ChangeRelationshipRole
+            | DeleteRelationship
+            | ReorderChildren
+            | Reparent => {
+                ChangedRecords::Relationships(records_from_json::<JsonRelationship, _>(json)?)
+            }
+        })
+    }
+}
+
+fn records_to_json<T, J>(records: &[T]) -> Result<String, LoreCoreError>
+where
+    T: Clone,
+    J: From<T> + Serialize,
+{
+    let records: Vec<J> = records.iter().cloned().map(J::from).collect();
+    serde_json::to_string(&records).map_err(|e| {
+        LoreCoreError::SqlError(
+            "Failed to serialize records for the change log: ".to_string() + &e.to_string(),
+        )
+    })
+}
+
+fn records_from_json<J, T>(json: &str) -> Result<Vec<T>, LoreCoreError>
+where
+    J: DeserializeOwned,
+    T: From<J>,
+{
+    let records: Vec<J> = serde_json::from_str(json).map_err(|e| {
+        LoreCoreError::SqlError(
+            "Failed to parse records from the change log: ".to_string() + &e.to_string(),
+        )
+    })?;
+    Ok(records.into_iter().map(T::from).collect())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangeLogEntry {
+    /// Ascending in the order in which the changes were made.
+    pub id: i64,
+    pub timestamp: Timestamp,
+    pub author: Option<String>,
+    pub operation: ChangeOperation,
+    pub old: ChangedRecords,
+    pub new: ChangedRecords,
+    pub state: JournalState,
+}
+
+impl SqlChangeLogEntry {
+    pub(super) fn to_change_log_entry(&self) -> Result<ChangeLogEntry, LoreCoreError> {
+        let operation = ChangeOperation::try_from(self.operation.as_str())?;
+        Ok(ChangeLogEntry {
+            id: self.id,
+            timestamp: self.timestamp.into(),
+            author: self.author.clone(),
+            operation,
+            old: ChangedRecords::from_json(operation, &self.old_values)?,
+            new: ChangedRecords::from_json(operation, &self.new_values)?,
+            state: JournalState::try_from(self.state)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ChangeLogSearchParams {
+    pub(crate) author: Option<String>,
+    pub(crate) operation: Option<ChangeOperation>,
+    pub(crate) since: Option<Timestamp>,
+    pub(crate) until: Option<Timestamp>,
+}
+
+impl ChangeLogSearchParams {
+    /// `since` and `until` are inclusive bounds of the timestamp of the entries.
+    pub fn new(
+        author: Option<String>,
+        operation: Option<ChangeOperation>,
+        since: Option<Timestamp>,
+        until: Option<Timestamp>,
+    ) -> Self {
+        Self {
+            author,
+            operation,
+            since,
+            until,
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            author: None,
+            operation: None,
+            since: None,
+            until: None,
+        }
+    }
+}
+
+/// Appends an entry to the change log, unless the operation did not affect any row.
+/// Changes that have been undone can no longer be redone afterwards.
+pub(super) fn log_change(
+    connection: &mut SqliteConnection,
+    author: Option<String>,
+    operation: ChangeOperation,
+    old: ChangedRecords,
+    new: ChangedRecords,
+) -> Result<(), LoreCoreError> {
+    if old.is_empty() && new.is_empty() {
+        return Ok(());
+    }
+    diesel::update(change_log::table.filter(change_log::state.eq(JournalState::Undone.to_int())))
+        .set(change_log::state.eq(JournalState::Discarded.to_int()))
+        .execute(connection)
+        .map_err(|e| {
+            LoreCoreError::SqlError(
+                "Discarding undone changes in database failed: ".to_string() + &e.to_string(),
+            )
+        })?;
+    let entry = NewSqlChangeLogEntry {
+        timestamp: current_timestamp().to_int(),
+        author,
+        operation: operation.to_str().to_string(),
+        old_values: old.to_json()?,
+        new_values: new.to_json()?,
+    };
+    diesel::insert_into(change_log::table)
+        .values(&entry)
+        .execute(connection)
+        .map_err(|e| {
+            LoreCoreError::SqlError(
+                "Writing change log entry to database failed: ".to_string() + &e.to_string(),
+            )
+        })?;
+    Ok(())
+}
+
+impl LoreDatabase {
+    /// Returns the matching change log entries, oldest first.
+    pub fn read_change_log(
+        &self,
+        search_params: ChangeLogSearchParams,
+    ) -> Result<Vec<ChangeLogEntry>, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        let mut query = change_log::table.into_boxed();
+        let author = search_params.author;
+        if let Some(author) = &author {
+            query = query.filter(change_log::author.eq(author.clone()));
+        }
+        let operation = search_params.operation;
+        if let Some(operation) = operation {
+            query = query.filter(change_log::operation.eq(operation.to_str()));
+        }
+        let since = search_params.since;
+        if let Some(since) = since {
+            query = query.filter(change_log::timestamp.ge(since.to_int()));
+        }
+        let until = search_params.until;
+        if let Some(until) = until {
+            query = query.filter(change_log::timestamp.le(until.to_int()));
+        }
+        query
+            .order(change_log::id.asc())
+            .load::<SqlChangeLogEntry>(&mut *connection)
+            .map_err(|e| {
+                sql_loading_error(
+                    "change log",
+                    vec![("author", &author), ("operation", &operation)],
+                    e,
+                )
+            })?
+            .iter()
+            .map(SqlChangeLogEntry::to_change_log_entry)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operations_round_trip_through_their_names() {
+        for operation in OPERATIONS {
+            assert_eq!(
+                ChangeOperation::try_from(operation.to_str()).unwrap(),
+                operation
+            );
+        }
+        assert!(ChangeOperation::try_from("drop_table").is_err());
+    }
+
+    #[test]
+    fn records_round_trip_through_json() {
+        let records = ChangedRecords::Relationships(vec![EntityRelationship {
+            parent: "Hero".into(),
+            child: "Dog".into(),
+            role: Role::NONE,
+            ordinal: 2.into(),
+        }]);
+        let json = records.to_json().unwrap();
+        assert_eq!(
+            ChangedRecords::from_json(ChangeOperation::DeleteRelationship, &json).unwrap(),
+            records
+        );
+    }
+}
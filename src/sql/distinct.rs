@@ -0,0 +1,91 @@
+use ::diesel::prelude::*;
+use diesel::{QueryDsl, RunQueryDsl};
+
+use crate::errors::{LoreCoreError, sql_loading_error};
+use crate::types::*;
+
+use super::lore_database::LoreDatabase;
+use super::schema::{entities, history_items, relationships};
+
+/// These are the database counterparts of the functions in `extractions` and `sql::relationship`.
+/// They return the same sorted, deduplicated values without loading all rows.
+impl LoreDatabase {
+    pub fn read_distinct_labels(&self) -> Result<Vec<Label>, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        let labels = entities::table
+            .select(entities::label)
+            .distinct()
+            .order(entities::label)
+            .load::<String>(&mut *connection)
+            .map_err(|e| sql_loading_error("distinct labels", vec![], e))?;
+        Ok(labels.into_iter().map(Label::from).collect())
+    }
+
+    pub fn read_distinct_descriptors(&self) -> Result<Vec<Descriptor>, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        let descriptors = entities::table
+            .select(entities::descriptor)
+            .distinct()
+            .order(entities::descriptor)
+            .load::<String>(&mut *connection)
+            .map_err(|e| sql_loading_error("distinct descriptors", vec![], e))?;
+        Ok(descriptors.into_iter().map(Descriptor::from).collect())
+    }
+
+    pub fn read_distinct_years(&self) -> Result<Vec<Year>, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        let years = history_items::table
+            .select(history_items::year)
+            .distinct()
+            .order(history_items::year)
+            .load::<i32>(&mut *connection)
+            .map_err(|e| sql_loading_error("distinct years", vec![], e))?;
+        Ok(years.into_iter().map(Year::from).collect())
+    }
+
+    pub fn read_distinct_days(&self) -> Result<Vec<Day>, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        let days = history_items::table
+            .select(history_items::day)
+            .distinct()
+            .order(history_items::day)
+            .load::<i32>(&mut *connection)
+            .map_err(|e| sql_loading_error("distinct days", vec![], e))?;
+        Ok(days.into_iter().map(Day::from).collect())
+    }
+
+    pub fn read_distinct_parents(&self) -> Result<Vec<Parent>, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        let parents = relationships::table
+            .select(relationships::parent)
+            .distinct()
+            .order(relationships::parent)
+            .load::<String>(&mut *connection)
+            .map_err(|e| sql_loading_error("distinct parents", vec![], e))?;
+        Ok(parents.into_iter().map(Parent::from).collect())
+    }
+
+    pub fn read_distinct_children(&self) -> Result<Vec<Child>, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        let children = relationships::table
+            .select(relationships::child)
+            .distinct()
+            .order(relationships::child)
+            .load::<String>(&mut *connection)
+            .map_err(|e| sql_loading_error("distinct children", vec![], e))?;
+        Ok(children.into_iter().map(Child::from).collect())
+    }
+
+    /// Like `extract_roles`, this omits the empty role.
+    pub fn read_distinct_roles(&self) -> Result<Vec<Role>, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        let roles = relationships::table
+            .select(relationships::role)
+            .filter(relationships::role.ne(""))
+            .distinct()
+            .order(relationships::role)
+            .load::<String>(&mut *connection)
+            .map_err(|e| sql_loading_error("distinct roles", vec![], e))?;
+        Ok(roles.into_iter().map(Role::from).collect())
+    }
+}
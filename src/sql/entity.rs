@@ -7,23 +7,59 @@ use crate::{
     types::*,
 };
 
-use super::{lore_database::LoreDatabase, search_params::EntityColumnSearchParams, sql_types::*};
+use super::{
+    change_log::{ChangeOperation, ChangedRecords, log_change},
+    lore_database::LoreDatabase,
+    search_params::EntityColumnSearchParams,
+    sql_types::*,
+};
+
+/// Loads the columns of `label`, or only the one with `descriptor` if given.
+fn load_entity_columns(
+    connection: &mut SqliteConnection,
+    label: &Label,
+    descriptor: Option<&Descriptor>,
+) -> Result<Vec<EntityColumn>, LoreCoreError> {
+    let mut query = entities::table
+        .filter(entities::label.eq(label.to_str()))
+        .into_boxed();
+    if let Some(descriptor) = descriptor {
+        query = query.filter(entities::descriptor.eq(descriptor.to_str()));
+    }
+    let mut cols: Vec<_> = query
+        .load::<SqlEntityColumn>(connection)
+        .map_err(|e| sql_loading_error("entities", vec![("label", label)], e))?
+        .into_iter()
+        .map(|c| c.to_entity_column())
+        .collect();
+    cols.sort();
+    Ok(cols)
+}
 
 impl LoreDatabase {
     pub fn write_entity_columns(&self, cols: Vec<EntityColumn>) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        for col in cols.into_iter() {
-            let col = col.to_sql_entity_column();
-            diesel::insert_into(entities::table)
-                .values(&col)
-                .execute(&mut connection)
-                .map_err(|e| {
-                    LoreCoreError::SqlError(
-                        "Writing column to database failed: ".to_string() + &e.to_string(),
-                    )
-                })?;
-        }
-        Ok(())
+        let author = connection.author();
+        connection.transaction(|connection| {
+            for col in cols.iter() {
+                let col = col.to_sql_entity_column();
+                diesel::insert_into(entities::table)
+                    .values(&col)
+                    .execute(connection)
+                    .map_err(|e| {
+                        LoreCoreError::SqlError(
+                            "Writing column to database failed: ".to_string() + &e.to_string(),
+                        )
+                    })?;
+            }
+            log_change(
+                connection,
+                author,
+                ChangeOperation::WriteEntityColumns,
+                ChangedRecords::EntityColumns(Vec::new()),
+                ChangedRecords::EntityColumns(cols),
+            )
+        })
     }
 
     pub fn relabel_entity(
@@ -32,27 +68,54 @@ impl LoreDatabase {
         new_label: &Label,
     ) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        diesel::update(entities::table.filter(entities::label.eq(old_label.to_str())))
-            .set(entities::label.eq(new_label.to_str()))
-            .execute(&mut connection)
-            .map_err(|e| {
-                LoreCoreError::SqlError(
-                    "Relabeling entity in database failed: ".to_string() + &e.to_string(),
-                )
-            })?;
-        Ok(())
+        let author = connection.author();
+        connection.transaction(|connection| {
+            let old = load_entity_columns(connection, old_label, None)?;
+            diesel::update(entities::table.filter(entities::label.eq(old_label.to_str())))
+                .set(entities::label.eq(new_label.to_str()))
+                .execute(connection)
+                .map_err(|e| {
+                    LoreCoreError::SqlError(
+                        "Relabeling entity in database failed: ".to_string() + &e.to_string(),
+                    )
+                })?;
+            let new = old
+                .iter()
+                .map(|col| EntityColumn {
+                    label: new_label.clone(),
+                    ..col.clone()
+                })
+                .collect();
+            log_change(
+                connection,
+                author,
+                ChangeOperation::RelabelEntity,
+                ChangedRecords::EntityColumns(old),
+                ChangedRecords::EntityColumns(new),
+            )
+        })
     }
 
     pub fn delete_entity(&self, label: Label) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        diesel::delete(entities::table.filter(entities::label.eq(label.to_str())))
-            .execute(&mut connection)
-            .map_err(|e| {
-                LoreCoreError::SqlError(
-                    "Deleting entity from database failed: ".to_string() + &e.to_string(),
-                )
-            })?;
-        Ok(())
+        let author = connection.author();
+        connection.transaction(|connection| {
+            let old = load_entity_columns(connection, &label, None)?;
+            diesel::delete(entities::table.filter(entities::label.eq(label.to_str())))
+                .execute(connection)
+                .map_err(|e| {
+                    LoreCoreError::SqlError(
+                        "Deleting entity from database failed: ".to_string() + &e.to_string(),
+                    )
+                })?;
+            log_change(
+                connection,
+                author,
+                ChangeOperation::DeleteEntity,
+                ChangedRecords::EntityColumns(old),
+                ChangedRecords::EntityColumns(Vec::new()),
+            )
+        })
     }
 
     pub fn change_entity_descriptor(
@@ -61,19 +124,36 @@ impl LoreDatabase {
         new_descriptor: &Descriptor,
     ) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        diesel::update(
-            entities::table
-                .filter(entities::label.eq(label.to_str()))
-                .filter(entities::descriptor.eq(old_descriptor.to_str())),
-        )
-        .set(entities::descriptor.eq(new_descriptor.to_str()))
-        .execute(&mut connection)
-        .map_err(|e| {
-            LoreCoreError::SqlError(
-                "Changing entity descriptor in database failed: ".to_string() + &e.to_string(),
+        let author = connection.author();
+        connection.transaction(|connection| {
+            let old = load_entity_columns(connection, label, Some(&old_descriptor))?;
+            diesel::update(
+                entities::table
+                    .filter(entities::label.eq(label.to_str()))
+                    .filter(entities::descriptor.eq(old_descriptor.to_str())),
+            )
+            .set(entities::descriptor.eq(new_descriptor.to_str()))
+            .execute(connection)
+            .map_err(|e| {
+                LoreCoreError::SqlError(
+                    "Changing entity descriptor in database failed: ".to_string() + &e.to_string(),
+                )
+            })?;
+            let new = old
+                .iter()
+                .map(|col| EntityColumn {
+                    descriptor: new_descriptor.clone(),
+                    ..col.clone()
+                })
+                .collect();
+            log_change(
+                connection,
+                author,
+                ChangeOperation::ChangeEntityDescriptor,
+                ChangedRecords::EntityColumns(old),
+                ChangedRecords::EntityColumns(new),
             )
-        })?;
-        Ok(())
+        })
     }
 
     pub fn delete_entity_column(
@@ -81,18 +161,28 @@ impl LoreDatabase {
         (label, descriptor): (Label, Descriptor),
     ) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        diesel::delete(
-            entities::table
-                .filter(entities::label.eq(label.to_str()))
-                .filter(entities::descriptor.eq(descriptor.to_str())),
-        )
-        .execute(&mut connection)
-        .map_err(|e| {
-            LoreCoreError::SqlError(
-                "Deleting entity column from database failed: ".to_string() + &e.to_string(),
+        let author = connection.author();
+        connection.transaction(|connection| {
+            let old = load_entity_columns(connection, &label, Some(&descriptor))?;
+            diesel::delete(
+                entities::table
+                    .filter(entities::label.eq(label.to_str()))
+                    .filter(entities::descriptor.eq(descriptor.to_str())),
+            )
+            .execute(connection)
+            .map_err(|e| {
+                LoreCoreError::SqlError(
+                    "Deleting entity column from database failed: ".to_string() + &e.to_string(),
+                )
+            })?;
+            log_change(
+                connection,
+                author,
+                ChangeOperation::DeleteEntityColumn,
+                ChangedRecords::EntityColumns(old),
+                ChangedRecords::EntityColumns(Vec::new()),
             )
-        })?;
-        Ok(())
+        })
     }
 
     pub fn change_entity_description(
@@ -101,19 +191,36 @@ impl LoreDatabase {
         new_description: &Description,
     ) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        diesel::update(
-            entities::table
-                .filter(entities::label.eq(label.to_str()))
-                .filter(entities::descriptor.eq(descriptor.to_str())),
-        )
-        .set(entities::description.eq(new_description.to_str()))
-        .execute(&mut connection)
-        .map_err(|e| {
-            LoreCoreError::SqlError(
-                "Changing entity description in database failed: ".to_string() + &e.to_string(),
+        let author = connection.author();
+        connection.transaction(|connection| {
+            let old = load_entity_columns(connection, label, Some(descriptor))?;
+            diesel::update(
+                entities::table
+                    .filter(entities::label.eq(label.to_str()))
+                    .filter(entities::descriptor.eq(descriptor.to_str())),
+            )
+            .set(entities::description.eq(new_description.to_str()))
+            .execute(connection)
+            .map_err(|e| {
+                LoreCoreError::SqlError(
+                    "Changing entity description in database failed: ".to_string() + &e.to_string(),
+                )
+            })?;
+            let new = old
+                .iter()
+                .map(|col| EntityColumn {
+                    description: new_description.clone(),
+                    ..col.clone()
+                })
+                .collect();
+            log_change(
+                connection,
+                author,
+                ChangeOperation::ChangeEntityDescription,
+                ChangedRecords::EntityColumns(old),
+                ChangedRecords::EntityColumns(new),
             )
-        })?;
-        Ok(())
+        })
     }
 
     pub fn read_entity_columns(
@@ -139,7 +246,7 @@ impl LoreDatabase {
             }
         }
         let mut cols: Vec<_> = query
-            .load::<SqlEntityColumn>(&mut connection)
+            .load::<SqlEntityColumn>(&mut *connection)
             .map_err(|e| {
                 sql_loading_error(
                     "entities",
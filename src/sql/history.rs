@@ -2,29 +2,55 @@ use ::diesel::prelude::*;
 
 use crate::{
     errors::{LoreCoreError, sql_loading_error},
-    types::*,
+    types::{history::entityref, *},
 };
 
 use super::{
-    lore_database::LoreDatabase, schema::history_items, search_params::HistoryItemSearchParams,
+    change_log::{ChangeOperation, ChangedRecords, log_change},
+    lore_database::LoreDatabase,
+    schema::history_items,
+    search_params::HistoryItemSearchParams,
     sql_types::*,
 };
 
+fn load_history_item(
+    connection: &mut SqliteConnection,
+    timestamp: Timestamp,
+) -> Result<Vec<HistoryItem>, LoreCoreError> {
+    Ok(history_items::table
+        .filter(history_items::timestamp.eq(timestamp.to_int()))
+        .load::<SqlHistoryItem>(connection)
+        .map_err(|e| sql_loading_error("history items", vec![("timestamp", &timestamp)], e))?
+        .into_iter()
+        .map(|item| item.to_history_item())
+        .collect())
+}
+
 impl LoreDatabase {
-    pub fn write_history_items(&self, cols: Vec<HistoryItem>) -> Result<(), LoreCoreError> {
+    pub fn write_history_items(&self, items: Vec<HistoryItem>) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        for col in cols.into_iter() {
-            let col = col.to_sql_history_item();
-            diesel::insert_into(history_items::table)
-                .values(&col)
-                .execute(&mut connection)
-                .map_err(|e| {
-                    LoreCoreError::SqlError(
-                        "Writing history item to database failed: ".to_string() + &e.to_string(),
-                    )
-                })?;
-        }
-        Ok(())
+        let author = connection.author();
+        connection.transaction(|connection| {
+            for item in items.iter() {
+                let item = item.to_sql_history_item();
+                diesel::insert_into(history_items::table)
+                    .values(&item)
+                    .execute(connection)
+                    .map_err(|e| {
+                        LoreCoreError::SqlError(
+                            "Writing history item to database failed: ".to_string()
+                                + &e.to_string(),
+                        )
+                    })?;
+            }
+            log_change(
+                connection,
+                author,
+                ChangeOperation::WriteHistoryItems,
+                ChangedRecords::HistoryItems(Vec::new()),
+                ChangedRecords::HistoryItems(items),
+            )
+        })
     }
 
     pub fn redate_history_item(
@@ -34,34 +60,62 @@ impl LoreDatabase {
         day: Day,
     ) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        diesel::update(
-            history_items::table.filter(history_items::timestamp.eq(timestamp.to_int())),
-        )
-        .set((
-            history_items::year.eq(year.to_int()),
-            history_items::day.eq(day.to_int() as i32),
-        ))
-        .execute(&mut connection)
-        .map_err(|e| {
-            LoreCoreError::SqlError(
-                "Redating history item in database failed: ".to_string() + &e.to_string(),
+        let author = connection.author();
+        connection.transaction(|connection| {
+            let old = load_history_item(connection, timestamp)?;
+            diesel::update(
+                history_items::table.filter(history_items::timestamp.eq(timestamp.to_int())),
+            )
+            .set((
+                history_items::year.eq(year.to_int()),
+                history_items::day.eq(day.to_int() as i32),
+            ))
+            .execute(connection)
+            .map_err(|e| {
+                LoreCoreError::SqlError(
+                    "Redating history item in database failed: ".to_string() + &e.to_string(),
+                )
+            })?;
+            let new = old
+                .iter()
+                .map(|item| HistoryItem {
+                    year,
+                    day,
+                    ..item.clone()
+                })
+                .collect();
+            log_change(
+                connection,
+                author,
+                ChangeOperation::RedateHistoryItem,
+                ChangedRecords::HistoryItems(old),
+                ChangedRecords::HistoryItems(new),
             )
-        })?;
-        Ok(())
+        })
     }
 
     pub fn delete_history_item(&self, timestamp: Timestamp) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        diesel::delete(
-            history_items::table.filter(history_items::timestamp.eq(timestamp.to_int())),
-        )
-        .execute(&mut connection)
-        .map_err(|e| {
-            LoreCoreError::SqlError(
-                "Deleting history item from database failed: ".to_string() + &e.to_string(),
+        let author = connection.author();
+        connection.transaction(|connection| {
+            let old = load_history_item(connection, timestamp)?;
+            diesel::delete(
+                history_items::table.filter(history_items::timestamp.eq(timestamp.to_int())),
+            )
+            .execute(connection)
+            .map_err(|e| {
+                LoreCoreError::SqlError(
+                    "Deleting history item from database failed: ".to_string() + &e.to_string(),
+                )
+            })?;
+            log_change(
+                connection,
+                author,
+                ChangeOperation::DeleteHistoryItem,
+                ChangedRecords::HistoryItems(old),
+                ChangedRecords::HistoryItems(Vec::new()),
             )
-        })?;
-        Ok(())
+        })
     }
 
     pub fn change_history_item_content(
@@ -70,17 +124,35 @@ impl LoreDatabase {
         content: &HistoryItemContent,
     ) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        diesel::update(
-            history_items::table.filter(history_items::timestamp.eq(timestamp.to_int())),
-        )
-        .set(history_items::content.eq(content.to_str()))
-        .execute(&mut connection)
-        .map_err(|e| {
-            LoreCoreError::SqlError(
-                "Changing history item content in database failed: ".to_string() + &e.to_string(),
+        let author = connection.author();
+        connection.transaction(|connection| {
+            let old = load_history_item(connection, timestamp)?;
+            diesel::update(
+                history_items::table.filter(history_items::timestamp.eq(timestamp.to_int())),
             )
-        })?;
-        Ok(())
+            .set(history_items::content.eq(content.to_str()))
+            .execute(connection)
+            .map_err(|e| {
+                LoreCoreError::SqlError(
+                    "Changing history item content in database failed: ".to_string()
+                        + &e.to_string(),
+                )
+            })?;
+            let new = old
+                .iter()
+                .map(|item| HistoryItem {
+                    content: content.clone(),
+                    ..item.clone()
+                })
+                .collect();
+            log_change(
+                connection,
+                author,
+                ChangeOperation::ChangeHistoryItemContent,
+                ChangedRecords::HistoryItems(old),
+                ChangedRecords::HistoryItems(new),
+            )
+        })
     }
 
     pub fn change_history_item_properties(
@@ -89,18 +161,35 @@ impl LoreDatabase {
         properties: &HistoryItemProperties,
     ) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        diesel::update(
-            history_items::table.filter(history_items::timestamp.eq(timestamp.to_int())),
-        )
-        .set(history_items::properties.eq(properties.to_string()))
-        .execute(&mut connection)
-        .map_err(|e| {
-            LoreCoreError::SqlError(
-                "Changing history item properties in database failed: ".to_string()
-                    + &e.to_string(),
+        let author = connection.author();
+        connection.transaction(|connection| {
+            let old = load_history_item(connection, timestamp)?;
+            diesel::update(
+                history_items::table.filter(history_items::timestamp.eq(timestamp.to_int())),
+            )
+            .set(history_items::properties.eq(properties.to_string()))
+            .execute(connection)
+            .map_err(|e| {
+                LoreCoreError::SqlError(
+                    "Changing history item properties in database failed: ".to_string()
+                        + &e.to_string(),
+                )
+            })?;
+            let new = old
+                .iter()
+                .map(|item| HistoryItem {
+                    properties: properties.clone(),
+                    ..item.clone()
+                })
+                .collect();
+            log_change(
+                connection,
+                author,
+                ChangeOperation::ChangeHistoryItemProperties,
+                ChangedRecords::HistoryItems(old),
+                ChangedRecords::HistoryItems(new),
             )
-        })?;
-        Ok(())
+        })
     }
 
     pub fn read_history_items(
@@ -130,7 +219,7 @@ impl LoreDatabase {
             }
         }
         let mut items: Vec<_> = query
-            .load::<SqlHistoryItem>(&mut connection)
+            .load::<SqlHistoryItem>(&mut *connection)
             .map_err(|e| {
                 sql_loading_error("history items", vec![("year", &year), ("day", &day)], e)
             })?
@@ -140,4 +229,30 @@ impl LoreDatabase {
         items.sort();
         Ok(items)
     }
+
+    /// Narrows the items down in SQL before checking `HistoryItem::concerns`. `LIKE` treats `%`
+    /// and `_` in the label as wildcards and ignores ASCII case, which only lets more items pass.
+    pub fn read_history_items_concerning(
+        &self,
+        label: &Label,
+    ) -> Result<Vec<HistoryItem>, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        // Properties are stored as serialized JSON, so the label is looked for in its JSON form.
+        let json_label = serde_json::to_string(label.to_str()).unwrap_or_default();
+        let json_label = &json_label[1..json_label.len() - 1];
+        let mut items: Vec<_> = history_items::table
+            .filter(
+                history_items::content
+                    .like(format!("%{}%", entityref(label)))
+                    .or(history_items::properties.like(format!("%{}%", json_label))),
+            )
+            .load::<SqlHistoryItem>(&mut *connection)
+            .map_err(|e| sql_loading_error("history items", vec![("label", &label)], e))?
+            .into_iter()
+            .map(|item| item.to_history_item())
+            .filter(|item| item.concerns(label))
+            .collect();
+        items.sort();
+        Ok(items)
+    }
 }
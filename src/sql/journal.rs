@@ -0,0 +1,170 @@
+//! Undo and redo of the changes recorded in the change log.
+//!
+//! Undoing a change removes the rows it wrote and restores the rows it replaced, redoing it does the
+//! opposite. Neither appends to the change log, they only update the state of the entry. Since the
+//! journal lives in the database, changes can still be undone after reopening it.
+
+use ::diesel::prelude::*;
+
+use crate::errors::LoreCoreError;
+
+use super::{
+    change_log::{ChangeLogEntry, ChangedRecords, JournalState},
+    lore_database::LoreDatabase,
+    schema::{change_log, entities, history_items, relationships},
+    sql_types::*,
+};
+
+fn journal_error(e: diesel::result::Error) -> LoreCoreError {
+    LoreCoreError::SqlError(
+        "Replaying change log in database failed: ".to_string() + &e.to_string(),
+    )
+}
+
+/// Deletes the rows with the keys of `remove` and inserts the rows of `insert`.
+fn replace_records(
+    connection: &mut SqliteConnection,
+    remove: &ChangedRecords,
+    insert: &ChangedRecords,
+) -> Result<(), LoreCoreError> {
+    match (remove, insert) {
+        (ChangedRecords::EntityColumns(remove), ChangedRecords::EntityColumns(insert)) => {
+            for col in remove {
+                diesel::delete(
+                    entities::table
+                        .filter(entities::label.eq(col.label.to_str()))
+                        .filter(entities::descriptor.eq(col.descriptor.to_str())),
+                )
+                .execute(connection)
+                .map_err(journal_error)?;
+            }
+            for col in insert {
+                diesel::insert_into(entities::table)
+                    .values(&col.to_sql_entity_column())
+                    .execute(connection)
+                    .map_err(journal_error)?;
+            }
+        }
+        (ChangedRecords::HistoryItems(remove), ChangedRecords::HistoryItems(insert)) => {
+            for item in remove {
+                diesel::delete(
+                    history_items::table
+                        .filter(history_items::timestamp.eq(item.timestamp.to_int())),
+                )
+                .execute(connection)
+                .map_err(journal_error)?;
+            }
+            for item in insert {
+                diesel::insert_into(history_items::table)
+                    .values(&item.to_sql_history_item())
+                    .execute(connection)
+                    .map_err(journal_error)?;
+            }
+        }
+        (ChangedRecords::Relationships(remove), ChangedRecords::Relationships(insert)) => {
+            for rel in remove {
+                let rel = rel.to_sql_entity_relationship()?;
+                diesel::delete(
+                    relationships::table.filter(
+                        relationships::parent
+                            .eq(rel.parent)
+                            .and(relationships::child.eq(rel.child))
+                            .and(relationships::role.eq(rel.role)),
+                    ),
+                )
+                .execute(connection)
+                .map_err(journal_error)?;
+            }
+            for rel in insert {
+                diesel::insert_into(relationships::table)
+                    .values(&rel.to_sql_entity_relationship()?)
+                    .execute(connection)
+                    .map_err(journal_error)?;
+            }
+        }
+        _ => {
+            return Err(LoreCoreError::SqlError(
+                "A change log entry mixes records of different tables.".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Loads the most recent applied entry when undoing, the oldest undone entry when redoing.
+fn next_entry(
+    connection: &mut SqliteConnection,
+    state: JournalState,
+) -> Result<Option<ChangeLogEntry>, LoreCoreError> {
+    let query = change_log::table
+        .filter(change_log::state.eq(state.to_int()))
+        .into_boxed();
+    let query = match state {
+        JournalState::Applied => query.order(change_log::id.desc()),
+        _ => query.order(change_log::id.asc()),
+    };
+    let entry = query
+        .first::<SqlChangeLogEntry>(connection)
+        .optional()
+        .map_err(journal_error)?;
+    entry.map(|entry| entry.to_change_log_entry()).transpose()
+}
+
+fn set_state(
+    connection: &mut SqliteConnection,
+    entry: &ChangeLogEntry,
+    state: JournalState,
+) -> Result<(), LoreCoreError> {
+    diesel::update(change_log::table.filter(change_log::id.eq(entry.id)))
+        .set(change_log::state.eq(state.to_int()))
+        .execute(connection)
+        .map_err(journal_error)?;
+    Ok(())
+}
+
+impl LoreDatabase {
+    /// Undoes the last `count` changes that have not been undone yet, most recent first.
+    /// Returns the undone entries, which are fewer than `count` if the journal runs out.
+    /// Either all of them are undone, or none.
+    pub fn undo(&self, count: usize) -> Result<Vec<ChangeLogEntry>, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        connection.transaction(|connection| {
+            let mut undone = Vec::new();
+            while undone.len() < count {
+                let Some(entry) = next_entry(connection, JournalState::Applied)? else {
+                    break;
+                };
+                replace_records(connection, &entry.new, &entry.old)?;
+                set_state(connection, &entry, JournalState::Undone)?;
+                undone.push(ChangeLogEntry {
+                    state: JournalState::Undone,
+                    ..entry
+                });
+            }
+            Ok(undone)
+        })
+    }
+
+    /// Redoes the next `count` undone changes, in the order in which they were originally made.
+    /// Returns the redone entries, which are fewer than `count` if there is nothing left to redo.
+    /// Changes can no longer be redone once another change has been made.
+    /// Either all of them are redone, or none.
+    pub fn redo(&self, count: usize) -> Result<Vec<ChangeLogEntry>, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        connection.transaction(|connection| {
+            let mut redone = Vec::new();
+            while redone.len() < count {
+                let Some(entry) = next_entry(connection, JournalState::Undone)? else {
+                    break;
+                };
+                replace_records(connection, &entry.old, &entry.new)?;
+                set_state(connection, &entry, JournalState::Applied)?;
+                redone.push(ChangeLogEntry {
+                    state: JournalState::Applied,
+                    ..entry
+                });
+            }
+            Ok(redone)
+        })
+    }
+}
@@ -1,50 +1,160 @@
-use std::path::PathBuf;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
 
 use crate::errors::LoreCoreError;
+use diesel::connection::TransactionManager;
 use diesel::{Connection, SqliteConnection};
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 
 pub struct LoreDatabase {
     path: PathBuf,
+    state: Mutex<ConnectionState>,
+}
+
+struct ConnectionState {
+    connection: SqliteConnection,
+    transaction_depth: usize,
+    author: Option<String>,
+}
+
+/// Grants exclusive access to the connection of a `LoreDatabase`.
+pub(super) struct ConnectionGuard<'a>(MutexGuard<'a, ConnectionState>);
+
+impl ConnectionGuard<'_> {
+    /// The author that changes made through this connection are attributed to in the change log.
+    pub(super) fn author(&self) -> Option<String> {
+        self.0.author.clone()
+    }
+}
+
+impl Deref for ConnectionGuard<'_> {
+    type Target = SqliteConnection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0.connection
+    }
+}
+
+impl DerefMut for ConnectionGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0.connection
+    }
 }
 
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
 
+type SqliteTransactionManager = <SqliteConnection as Connection>::TransactionManager;
+
 impl LoreDatabase {
     pub fn open(path: PathBuf) -> Result<Self, LoreCoreError> {
-        let db = LoreDatabase { path };
-        db.db_connection()?
-            .run_pending_migrations(MIGRATIONS)
-            .map_err(|e| {
-                LoreCoreError::SqlError(
-                    "Failed to run SQL database migrations: ".to_string() + &e.to_string(),
-                )
-            })?;
-        Ok(db)
+        let mut connection = establish_connection(&path)?;
+        connection.run_pending_migrations(MIGRATIONS).map_err(|e| {
+            LoreCoreError::SqlError(
+                "Failed to run SQL database migrations: ".to_string() + &e.to_string(),
+            )
+        })?;
+        Ok(LoreDatabase {
+            path,
+            state: Mutex::new(ConnectionState {
+                connection,
+                transaction_depth: 0,
+                author: None,
+            }),
+        })
     }
 
     pub fn path_as_string(&self) -> String {
         self.path.to_string_lossy().to_string()
     }
 
-    pub(super) fn db_connection(&self) -> Result<SqliteConnection, LoreCoreError> {
-        let path = match self.path.to_str() {
-            Some(str) => str,
-            None => {
-                return Err(LoreCoreError::FileError(
-                    "Could not open database path.".to_string()
-                        + "This is likely because it contains characters that can not be UTF-8 encoded."
-                        + "The lossy path conversion reads:\n"
-                        + &self.path.to_string_lossy(),
-                ));
-            }
-        };
-        SqliteConnection::establish(path).map_err(|e| {
+    /// Sets the author that all following changes are attributed to in the change log.
+    pub fn set_author(&self, author: Option<String>) -> Result<(), LoreCoreError> {
+        self.lock_state()?.author = author;
+        Ok(())
+    }
+
+    /// Starts a transaction that spans all following calls on this database, until it is
+    /// committed or rolled back. Transactions can be nested.
+    pub fn begin_transaction(&self) -> Result<(), LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        SqliteTransactionManager::begin_transaction(&mut *connection).map_err(|e| {
+            LoreCoreError::SqlError("Failed to begin transaction: ".to_string() + &e.to_string())
+        })?;
+        connection.0.transaction_depth += 1;
+        Ok(())
+    }
+
+    pub fn commit_transaction(&self) -> Result<(), LoreCoreError> {
+        let mut connection = self.open_transaction_connection()?;
+        SqliteTransactionManager::commit_transaction(&mut *connection).map_err(|e| {
+            LoreCoreError::SqlError("Failed to commit transaction: ".to_string() + &e.to_string())
+        })?;
+        connection.0.transaction_depth -= 1;
+        Ok(())
+    }
+
+    pub fn rollback_transaction(&self) -> Result<(), LoreCoreError> {
+        let mut connection = self.open_transaction_connection()?;
+        SqliteTransactionManager::rollback_transaction(&mut *connection).map_err(|e| {
+            LoreCoreError::SqlError(
+                "Failed to roll back transaction: ".to_string() + &e.to_string(),
+            )
+        })?;
+        connection.0.transaction_depth -= 1;
+        Ok(())
+    }
+
+    fn lock_state(&self) -> Result<MutexGuard<'_, ConnectionState>, LoreCoreError> {
+        self.state.lock().map_err(|_| {
             LoreCoreError::SqlError(
-                "Failed to establish a connection to the database: ".to_string() + &e.to_string(),
+                "The database connection is unusable, because a previous operation panicked."
+                    .to_string(),
             )
         })
     }
+
+    fn open_transaction_connection(&self) -> Result<ConnectionGuard<'_>, LoreCoreError> {
+        let state = self.lock_state()?;
+        if state.transaction_depth == 0 {
+            return Err(LoreCoreError::InputError(
+                "There is no open transaction.".to_string(),
+            ));
+        }
+        Ok(ConnectionGuard(state))
+    }
+
+    /// All calls share the connection established by `open`. Outside of transactions, the
+    /// database file is checked to still exist, because the connection would silently keep
+    /// working on a deleted file.
+    pub(super) fn db_connection(&self) -> Result<ConnectionGuard<'_>, LoreCoreError> {
+        let state = self.lock_state()?;
+        if state.transaction_depth == 0 && !self.path.exists() {
+            return Err(LoreCoreError::FileError(
+                "The database file no longer exists: ".to_string() + &self.path_as_string(),
+            ));
+        }
+        Ok(ConnectionGuard(state))
+    }
+}
+
+fn establish_connection(path: &Path) -> Result<SqliteConnection, LoreCoreError> {
+    let path_str = match path.to_str() {
+        Some(str) => str,
+        None => {
+            return Err(LoreCoreError::FileError(
+                "Could not open database path.".to_string()
+                    + "This is likely because it contains characters that can not be UTF-8 encoded."
+                    + "The lossy path conversion reads:\n"
+                    + &path.to_string_lossy(),
+            ));
+        }
+    };
+    SqliteConnection::establish(path_str).map_err(|e| {
+        LoreCoreError::SqlError(
+            "Failed to establish a connection to the database: ".to_string() + &e.to_string(),
+        )
+    })
 }
 
 #[cfg(test)]
@@ -68,4 +178,29 @@ mod tests {
         let result = LoreDatabase::open(non_utf8_path);
         assert!(matches!(result, Err(LoreCoreError::FileError(_))));
     }
+
+    #[test]
+    fn connection_is_kept_between_calls() {
+        use diesel::RunQueryDsl;
+
+        let temp_path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+        // Temporary tables only exist for the connection that created them.
+        diesel::sql_query("CREATE TEMP TABLE scratch (id INTEGER)")
+            .execute(&mut *db.db_connection().unwrap())
+            .unwrap();
+        diesel::sql_query("INSERT INTO scratch VALUES (1)")
+            .execute(&mut *db.db_connection().unwrap())
+            .unwrap();
+        temp_path.close().unwrap();
+    }
+
+    #[test]
+    fn committing_without_transaction_fails() {
+        let temp_path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+        assert!(db.commit_transaction().is_err());
+        assert!(db.rollback_transaction().is_err());
+        temp_path.close().unwrap();
+    }
 }
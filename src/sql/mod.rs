@@ -1,7 +1,23 @@
+#[cfg(feature = "sqlite")]
+pub mod change_log;
+#[cfg(feature = "sqlite")]
+pub mod distinct;
+#[cfg(feature = "sqlite")]
 pub mod entity;
+#[cfg(feature = "sqlite")]
 pub mod history;
+#[cfg(feature = "sqlite")]
+pub mod journal;
+#[cfg(feature = "sqlite")]
 pub mod lore_database;
+#[cfg(feature = "sqlite")]
 pub mod relationship;
+#[cfg(feature = "sqlite")]
+pub mod reparent;
+#[cfg(feature = "sqlite")]
 pub(super) mod schema;
 pub mod search_params;
+#[cfg(feature = "sqlite")]
 mod sql_types;
+#[cfg(feature = "sqlite")]
+mod store;
@@ -6,23 +6,76 @@ use crate::types::*;
 
 use super::search_params::RelationshipSearchParams;
 use super::sql_types::*;
-use super::{lore_database::LoreDatabase, schema::relationships};
+use super::{
+    change_log::{ChangeOperation, ChangedRecords, log_change},
+    lore_database::LoreDatabase,
+    schema::relationships,
+};
+
+pub use crate::extractions::{extract_children, extract_parents, extract_roles};
+
+/// Loads the relationship with the same parent, child and role as `rel`, if it exists.
+fn load_relationship(
+    connection: &mut SqliteConnection,
+    rel: &SqlEntityRelationship,
+) -> Result<Vec<EntityRelationship>, LoreCoreError> {
+    Ok(relationships::table
+        .filter(
+            relationships::parent
+                .eq(&rel.parent)
+                .and(relationships::child.eq(&rel.child))
+                .and(relationships::role.eq(&rel.role)),
+        )
+        .load::<SqlEntityRelationship>(connection)
+        .map_err(|e| {
+            sql_loading_error(
+                "relationships",
+                vec![("parent", &rel.parent), ("child", &rel.child)],
+                e,
+            )
+        })?
+        .into_iter()
+        .map(|rel| rel.to_relationship())
+        .collect())
+}
+
+fn load_children(
+    connection: &mut SqliteConnection,
+    parent: &Parent,
+) -> Result<Vec<EntityRelationship>, LoreCoreError> {
+    let mut rels: Vec<_> = relationships::table
+        .filter(relationships::parent.eq(parent.to_str()))
+        .load::<SqlEntityRelationship>(connection)
+        .map_err(|e| sql_loading_error("relationships", vec![("parent", parent)], e))?;
+    rels.sort();
+    Ok(rels.into_iter().map(|rel| rel.to_relationship()).collect())
+}
 
 impl LoreDatabase {
     pub fn write_relationships(&self, rels: Vec<EntityRelationship>) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        for rel in rels.into_iter() {
-            let rel = rel.to_sql_entity_relationship();
-            diesel::insert_into(relationships::table)
-                .values(&rel)
-                .execute(&mut connection)
-                .map_err(|e| {
-                    LoreCoreError::SqlError(
-                        "Writing relationship to database failed: ".to_string() + &e.to_string(),
-                    )
-                })?;
-        }
-        Ok(())
+        let author = connection.author();
+        connection.transaction(|connection| {
+            for rel in rels.iter() {
+                let rel = rel.to_sql_entity_relationship()?;
+                diesel::insert_into(relationships::table)
+                    .values(&rel)
+                    .execute(connection)
+                    .map_err(|e| {
+                        LoreCoreError::SqlError(
+                            "Writing relationship to database failed: ".to_string()
+                                + &e.to_string(),
+                        )
+                    })?;
+            }
+            log_change(
+                connection,
+                author,
+                ChangeOperation::WriteRelationships,
+                ChangedRecords::Relationships(Vec::new()),
+                ChangedRecords::Relationships(rels),
+            )
+        })
     }
 
     pub fn change_relationship_role(
@@ -31,23 +84,40 @@ impl LoreDatabase {
         new_role: &Role,
     ) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        let old_relationship = old_relationship.to_sql_entity_relationship();
-        diesel::update(
-            relationships::table.filter(
-                relationships::parent
-                    .eq(old_relationship.parent)
-                    .and(relationships::child.eq(old_relationship.child))
-                    .and(relationships::role.eq(old_relationship.role)),
-            ),
-        )
-        .set(relationships::role.eq(new_role.to_string()))
-        .execute(&mut connection)
-        .map_err(|e| {
-            LoreCoreError::SqlError(
-                "Changing relationship role in database failed: ".to_string() + &e.to_string(),
+        let author = connection.author();
+        let old_relationship = old_relationship.to_sql_entity_relationship()?;
+        connection.transaction(|connection| {
+            let old = load_relationship(connection, &old_relationship)?;
+            diesel::update(
+                relationships::table.filter(
+                    relationships::parent
+                        .eq(&old_relationship.parent)
+                        .and(relationships::child.eq(&old_relationship.child))
+                        .and(relationships::role.eq(&old_relationship.role)),
+                ),
             )
-        })?;
-        Ok(())
+            .set(relationships::role.eq(new_role.to_string()))
+            .execute(connection)
+            .map_err(|e| {
+                LoreCoreError::SqlError(
+                    "Changing relationship role in database failed: ".to_string() + &e.to_string(),
+                )
+            })?;
+            let new = old
+                .iter()
+                .map(|rel| EntityRelationship {
+                    role: new_role.clone(),
+                    ..rel.clone()
+                })
+                .collect();
+            log_change(
+                connection,
+                author,
+                ChangeOperation::ChangeRelationshipRole,
+                ChangedRecords::Relationships(old),
+                ChangedRecords::Relationships(new),
+            )
+        })
     }
 
     pub fn delete_relationship(
@@ -55,22 +125,93 @@ impl LoreDatabase {
         relationship: EntityRelationship,
     ) -> Result<(), LoreCoreError> {
         let mut connection = self.db_connection()?;
-        let relationship = relationship.to_sql_entity_relationship();
-        diesel::delete(
-            relationships::table.filter(
-                relationships::parent
-                    .eq(relationship.parent)
-                    .and(relationships::child.eq(relationship.child))
-                    .and(relationships::role.eq(relationship.role)),
-            ),
-        )
-        .execute(&mut connection)
-        .map_err(|e| {
-            LoreCoreError::SqlError(
-                "Deleting relationship from database failed: ".to_string() + &e.to_string(),
+        let author = connection.author();
+        let relationship = relationship.to_sql_entity_relationship()?;
+        connection.transaction(|connection| {
+            let old = load_relationship(connection, &relationship)?;
+            diesel::delete(
+                relationships::table.filter(
+                    relationships::parent
+                        .eq(&relationship.parent)
+                        .and(relationships::child.eq(&relationship.child))
+                        .and(relationships::role.eq(&relationship.role)),
+                ),
             )
-        })?;
-        Ok(())
+            .execute(connection)
+            .map_err(|e| {
+                LoreCoreError::SqlError(
+                    "Deleting relationship from database failed: ".to_string() + &e.to_string(),
+                )
+            })?;
+            log_change(
+                connection,
+                author,
+                ChangeOperation::DeleteRelationship,
+                ChangedRecords::Relationships(old),
+                ChangedRecords::Relationships(Vec::new()),
+            )
+        })
+    }
+
+    /// Assigns the ordinals 1, 2, ... to the relationships between `parent` and `children`, in
+    /// the given order. All other relationships of `parent` lose their ordinal.
+    pub fn reorder_children(
+        &self,
+        parent: &Parent,
+        children: &[Child],
+    ) -> Result<(), LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        let author = connection.author();
+        connection.transaction(|connection| {
+            let before = load_children(connection, parent)?;
+            diesel::update(relationships::table.filter(relationships::parent.eq(parent.to_str())))
+                .set(relationships::ordinal.eq(sql_ordinal(Ordinal::NONE)?))
+                .execute(connection)
+                .map_err(|e| {
+                    LoreCoreError::SqlError(
+                        "Resetting relationship ordinals in database failed: ".to_string()
+                            + &e.to_string(),
+                    )
+                })?;
+            for (i, child) in children.iter().enumerate() {
+                let ordinal = Ordinal::from(i as u32 + 1);
+                let updated_rows = diesel::update(
+                    relationships::table.filter(
+                        relationships::parent
+                            .eq(parent.to_str())
+                            .and(relationships::child.eq(child.to_str())),
+                    ),
+                )
+                .set(relationships::ordinal.eq(sql_ordinal(ordinal)?))
+                .execute(connection)
+                .map_err(|e| {
+                    LoreCoreError::SqlError(
+                        "Changing relationship ordinal in database failed: ".to_string()
+                            + &e.to_string(),
+                    )
+                })?;
+                if updated_rows == 0 {
+                    return Err(LoreCoreError::InputError(format!(
+                        "\"{}\" is not a child of \"{}\".",
+                        child, parent
+                    )));
+                }
+            }
+            let after = load_children(connection, parent)?;
+            // Both lists are sorted by parent, child and role, which the update leaves untouched.
+            let (old, new) = before
+                .into_iter()
+                .zip(after)
+                .filter(|(old, new)| old != new)
+                .unzip();
+            log_change(
+                connection,
+                author,
+                ChangeOperation::ReorderChildren,
+                ChangedRecords::Relationships(old),
+                ChangedRecords::Relationships(new),
+            )
+        })
     }
 
     pub fn read_relationships(
@@ -96,7 +237,7 @@ impl LoreDatabase {
             }
         }
         let rels = query
-            .load::<SqlEntityRelationship>(&mut connection)
+            .load::<SqlEntityRelationship>(&mut *connection)
             .map_err(|e| {
                 sql_loading_error(
                     "relationships",
@@ -110,102 +251,3 @@ impl LoreDatabase {
         Ok(rels)
     }
 }
-
-pub fn extract_parents(rels: &[EntityRelationship]) -> Vec<Parent> {
-    let mut parents: Vec<_> = rels.iter().map(|rel| rel.parent.clone()).collect();
-    parents.sort();
-    parents.dedup();
-    parents
-}
-
-pub fn extract_children(rels: &[EntityRelationship]) -> Vec<Child> {
-    let mut children: Vec<_> = rels.iter().map(|rel| rel.child.clone()).collect();
-    children.sort();
-    children.dedup();
-    children
-}
-
-pub fn extract_roles(rels: &[EntityRelationship]) -> Vec<Role> {
-    let mut roles: Vec<_> = rels
-        .iter()
-        .filter(|rel| !rel.role.0.is_empty())
-        .map(|rel| rel.role.clone())
-        .collect();
-    roles.sort();
-    roles.dedup();
-    roles
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_extract_parents() {
-        let rels = vec![
-            EntityRelationship {
-                parent: "b".into(),
-                child: "c".into(),
-                role: Role::NONE,
-            },
-            EntityRelationship {
-                parent: "a".into(),
-                child: "b".into(),
-                role: Role::NONE,
-            },
-            EntityRelationship {
-                parent: "a".into(),
-                child: "c".into(),
-                role: Role::NONE,
-            },
-        ];
-        let parents = extract_parents(&rels);
-        assert!(parents == vec!["a".into(), "b".into()]);
-    }
-
-    #[test]
-    fn test_extract_children() {
-        let rels = vec![
-            EntityRelationship {
-                parent: "b".into(),
-                child: "c".into(),
-                role: Role::NONE,
-            },
-            EntityRelationship {
-                parent: "a".into(),
-                child: "b".into(),
-                role: Role::NONE,
-            },
-            EntityRelationship {
-                parent: "a".into(),
-                child: "c".into(),
-                role: Role::NONE,
-            },
-        ];
-        let children = extract_children(&rels);
-        assert!(children == vec!["b".into(), "c".into()]);
-    }
-
-    #[test]
-    fn test_extract_roles() {
-        let rels = vec![
-            EntityRelationship {
-                parent: "b".into(),
-                child: "c".into(),
-                role: "r1".into(),
-            },
-            EntityRelationship {
-                parent: "a".into(),
-                child: "b".into(),
-                role: "r2".into(),
-            },
-            EntityRelationship {
-                parent: "a".into(),
-                child: "c".into(),
-                role: "r1".into(),
-            },
-        ];
-        let roles = extract_roles(&rels);
-        assert!(roles == vec!["r1".into(), "r2".into()]);
-    }
-}
@@ -0,0 +1,315 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use ::diesel::prelude::*;
+
+use crate::{errors::LoreCoreError, types::*};
+
+use super::{
+    change_log::{ChangeOperation, ChangedRecords, log_change},
+    lore_database::LoreDatabase,
+    schema::relationships,
+    sql_types::*,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReparentConflict {
+    /// The new parent is the child itself or one of the descendants that would stay below it.
+    WouldCreateCycle(Label),
+    /// A relationship that would be written by the move already exists.
+    RelationshipExists(EntityRelationship),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReparentReport {
+    pub removed: Vec<EntityRelationship>,
+    pub added: Vec<EntityRelationship>,
+    pub conflicts: Vec<ReparentConflict>,
+}
+
+impl ReparentReport {
+    pub fn is_applied(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+fn descendants(rels: &[EntityRelationship], label: &str) -> BTreeSet<String> {
+    let mut descendants = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(label.to_string());
+    while let Some(current) = queue.pop_front() {
+        for rel in rels.iter().filter(|rel| rel.parent.to_str() == current) {
+            if descendants.insert(rel.child.to_string()) {
+                queue.push_back(rel.child.to_string());
+            }
+        }
+    }
+    descendants
+}
+
+fn plan_reparenting(
+    rels: &[EntityRelationship],
+    child: &Child,
+    role: &Role,
+    new_parent: &Parent,
+    carry_descendants: bool,
+) -> ReparentReport {
+    let mut report = ReparentReport {
+        removed: rels
+            .iter()
+            .filter(|rel| &rel.child == child)
+            .cloned()
+            .collect(),
+        ..Default::default()
+    };
+    let has_former_parents = !report.removed.is_empty();
+    let keeps_descendants = carry_descendants || !has_former_parents;
+
+    if new_parent.to_str() == child.to_str() {
+        report
+            .conflicts
+            .push(ReparentConflict::WouldCreateCycle(child.to_str().into()));
+    } else if keeps_descendants && descendants(rels, child.to_str()).contains(new_parent.to_str()) {
+        report.conflicts.push(ReparentConflict::WouldCreateCycle(
+            new_parent.to_str().into(),
+        ));
+    }
+
+    report.added.push(EntityRelationship {
+        parent: new_parent.clone(),
+        child: child.clone(),
+        role: role.clone(),
+        ordinal: Ordinal::NONE,
+    });
+
+    if !keeps_descendants {
+        let former_parents: Vec<_> = report
+            .removed
+            .iter()
+            .map(|rel| rel.parent.clone())
+            .collect();
+        for grandchild_rel in rels
+            .iter()
+            .filter(|rel| rel.parent.to_str() == child.to_str())
+        {
+            report.removed.push(grandchild_rel.clone());
+            for parent in &former_parents {
+                report.added.push(EntityRelationship {
+                    parent: parent.clone(),
+                    child: grandchild_rel.child.clone(),
+                    role: role.clone(),
+                    ordinal: Ordinal::NONE,
+                });
+            }
+        }
+    }
+
+    for added in &report.added {
+        let exists = rels.iter().any(|rel| {
+            rel.parent == added.parent && rel.child == added.child && !report.removed.contains(rel)
+        });
+        if exists {
+            report
+                .conflicts
+                .push(ReparentConflict::RelationshipExists(added.clone()));
+        }
+    }
+    report
+}
+
+impl LoreDatabase {
+    /// Moves `child` from its current parents under `role` to `new_parent`.
+    ///
+    /// If `carry_descendants` is set, everything below `child` (with respect to `role`) moves
+    /// along. Otherwise, the children of `child` are handed over to its former parents. If
+    /// `child` has no parents under `role`, there is no one to hand them over to, and they stay
+    /// with `child`.
+    /// Moved relationships lose their ordinal.
+    ///
+    /// Either all changes are applied, or none. In the latter case, the returned report lists
+    /// the conflicts that prevented the move.
+    pub fn reparent(
+        &self,
+        child: &Child,
+        role: &Role,
+        new_parent: &Parent,
+        carry_descendants: bool,
+    ) -> Result<ReparentReport, LoreCoreError> {
+        let mut connection = self.db_connection()?;
+        let author = connection.author();
+        connection.transaction(|connection| {
+            let rels: Vec<EntityRelationship> = relationships::table
+                .filter(relationships::role.eq(role.to_str()))
+                .load::<SqlEntityRelationship>(connection)
+                .map_err(|e| {
+                    LoreCoreError::SqlError(
+                        "Loading relationships for reparenting failed: ".to_string()
+                            + &e.to_string(),
+                    )
+                })?
+                .into_iter()
+                .map(|rel| rel.to_relationship())
+                .collect();
+
+            let report = plan_reparenting(&rels, child, role, new_parent, carry_descendants);
+            if !report.is_applied() {
+                return Ok(report);
+            }
+
+            for rel in &report.removed {
+                let rel = rel.to_sql_entity_relationship()?;
+                diesel::delete(
+                    relationships::table.filter(
+                        relationships::parent
+                            .eq(rel.parent)
+                            .and(relationships::child.eq(rel.child))
+                            .and(relationships::role.eq(rel.role)),
+                    ),
+                )
+                .execute(connection)
+                .map_err(|e| {
+                    LoreCoreError::SqlError(
+                        "Deleting relationship from database failed: ".to_string() + &e.to_string(),
+                    )
+                })?;
+            }
+            for rel in &report.added {
+                diesel::insert_into(relationships::table)
+                    .values(&rel.to_sql_entity_relationship()?)
+                    .execute(connection)
+                    .map_err(|e| {
+                        LoreCoreError::SqlError(
+                            "Writing relationship to database failed: ".to_string()
+                                + &e.to_string(),
+                        )
+                    })?;
+            }
+            log_change(
+                connection,
+                author,
+                ChangeOperation::Reparent,
+                ChangedRecords::Relationships(report.removed.clone()),
+                ChangedRecords::Relationships(report.added.clone()),
+            )?;
+            Ok(report)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rel(parent: &str, child: &str) -> EntityRelationship {
+        EntityRelationship {
+            parent: parent.into(),
+            child: child.into(),
+            role: "location".into(),
+            ordinal: Ordinal::NONE,
+        }
+    }
+
+    fn example() -> Vec<EntityRelationship> {
+        vec![
+            rel("kingdom1", "city"),
+            rel("city", "tavern"),
+            rel("tavern", "cellar"),
+            rel("kingdom2", "village"),
+        ]
+    }
+
+    #[test]
+    fn moving_with_descendants_only_touches_one_relationship() {
+        let report = plan_reparenting(
+            &example(),
+            &"city".into(),
+            &"location".into(),
+            &"kingdom2".into(),
+            true,
+        );
+        assert!(report.is_applied());
+        assert_eq!(report.removed, vec![rel("kingdom1", "city")]);
+        assert_eq!(report.added, vec![rel("kingdom2", "city")]);
+    }
+
+    #[test]
+    fn moving_without_descendants_hands_children_to_former_parent() {
+        let report = plan_reparenting(
+            &example(),
+            &"city".into(),
+            &"location".into(),
+            &"kingdom2".into(),
+            false,
+        );
+        assert!(report.is_applied());
+        assert_eq!(
+            report.removed,
+            vec![rel("kingdom1", "city"), rel("city", "tavern")]
+        );
+        assert_eq!(
+            report.added,
+            vec![rel("kingdom2", "city"), rel("kingdom1", "tavern")]
+        );
+    }
+
+    #[test]
+    fn moving_parentless_child_without_descendants_keeps_its_children() {
+        let rels = vec![rel("C", "G")];
+        let report = plan_reparenting(&rels, &"C".into(), &"location".into(), &"N".into(), false);
+        assert!(report.is_applied());
+        assert!(report.removed.is_empty());
+        assert_eq!(report.added, vec![rel("N", "C")]);
+    }
+
+    #[test]
+    fn moving_below_own_descendant_is_a_conflict() {
+        let report = plan_reparenting(
+            &example(),
+            &"city".into(),
+            &"location".into(),
+            &"cellar".into(),
+            true,
+        );
+        assert_eq!(
+            report.conflicts,
+            vec![ReparentConflict::WouldCreateCycle("cellar".into())]
+        );
+
+        let report = plan_reparenting(
+            &example(),
+            &"city".into(),
+            &"location".into(),
+            &"cellar".into(),
+            false,
+        );
+        assert!(report.is_applied());
+    }
+
+    #[test]
+    fn moving_parentless_child_below_own_descendant_is_a_conflict() {
+        let rels = vec![rel("C", "G")];
+        let report = plan_reparenting(&rels, &"C".into(), &"location".into(), &"G".into(), false);
+        assert_eq!(
+            report.conflicts,
+            vec![ReparentConflict::WouldCreateCycle("G".into())]
+        );
+    }
+
+    #[test]
+    fn handing_over_to_existing_relationship_is_a_conflict() {
+        let mut rels = example();
+        rels.push(rel("kingdom1", "tavern"));
+        let report = plan_reparenting(
+            &rels,
+            &"city".into(),
+            &"location".into(),
+            &"kingdom2".into(),
+            false,
+        );
+        assert_eq!(
+            report.conflicts,
+            vec![ReparentConflict::RelationshipExists(rel(
+                "kingdom1", "tavern"
+            ))]
+        );
+    }
+}
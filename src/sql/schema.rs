@@ -1,5 +1,17 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    change_log (id) {
+        id -> BigInt,
+        timestamp -> BigInt,
+        author -> Nullable<Text>,
+        operation -> Text,
+        old_values -> Text,
+        new_values -> Text,
+        state -> Integer,
+    }
+}
+
 diesel::table! {
     entities (label, descriptor) {
         label -> Text,
@@ -23,7 +35,8 @@ diesel::table! {
         parent -> Text,
         child -> Text,
         role -> Text,
+        ordinal -> Integer,
     }
 }
 
-diesel::allow_tables_to_appear_in_same_query!(entities, history_items, relationships,);
+diesel::allow_tables_to_appear_in_same_query!(change_log, entities, history_items, relationships,);
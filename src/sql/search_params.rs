@@ -1,6 +1,6 @@
 use crate::types::*;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SqlSearchText {
     text: Option<String>,
     pub(crate) is_exact: bool,
@@ -28,10 +28,12 @@ impl SqlSearchText {
         }
     }
 
+    #[cfg(feature = "sqlite")]
     pub(crate) fn is_some(&self) -> bool {
         self.text.is_some()
     }
 
+    #[cfg(feature = "sqlite")]
     pub(crate) fn exact_text(&self) -> String {
         match &self.text {
             Some(text) => text.to_string(),
@@ -45,6 +47,40 @@ impl SqlSearchText {
             None => "%".to_string(),
         }
     }
+
+    /// Evaluates the search without a database, with the same results as the SQL query.
+    pub(crate) fn matches(&self, value: &str) -> bool {
+        match &self.text {
+            None => true,
+            Some(text) if self.is_exact => text == value,
+            Some(_) => like(&self.search_pattern(), value),
+        }
+    }
+}
+
+/// Mirrors SQLite's `LIKE`: `%` matches any sequence, `_` any single character, and ASCII letters
+/// are compared case-insensitively.
+fn like(pattern: &str, value: &str) -> bool {
+    let value: Vec<char> = value.chars().collect();
+    // matched[j] states whether the pattern so far matches the first j characters of value.
+    let mut matched = vec![false; value.len() + 1];
+    matched[0] = true;
+    for p in pattern.chars() {
+        let mut next = vec![false; value.len() + 1];
+        if p == '%' {
+            let mut any = false;
+            for j in 0..=value.len() {
+                any |= matched[j];
+                next[j] = any;
+            }
+        } else {
+            for j in 1..=value.len() {
+                next[j] = matched[j - 1] && (p == '_' || p.eq_ignore_ascii_case(&value[j - 1]));
+            }
+        }
+        matched = next;
+    }
+    matched[value.len()]
 }
 
 #[derive(Debug)]
@@ -72,6 +108,10 @@ impl EntityColumnSearchParams {
             descriptor: SqlSearchText::empty(),
         }
     }
+
+    pub(crate) fn matches(&self, col: &EntityColumn) -> bool {
+        self.label.matches(col.label.to_str()) && self.descriptor.matches(col.descriptor.to_str())
+    }
 }
 
 #[derive(Debug)]
@@ -109,6 +149,15 @@ impl HistoryItemSearchParams {
             content: SqlSearchText::empty(),
         }
     }
+
+    pub(crate) fn matches(&self, item: &HistoryItem) -> bool {
+        self.year.is_none_or(|year| year == item.year)
+            && self.day.is_none_or(|day| day.to_int() == item.day.to_int())
+            && self
+                .timestamp
+                .is_none_or(|timestamp| timestamp == item.timestamp)
+            && self.content.matches(item.content.to_str())
+    }
 }
 
 #[derive(Debug)]
@@ -136,4 +185,38 @@ impl RelationshipSearchParams {
             child: SqlSearchText::empty(),
         }
     }
+
+    pub(crate) fn matches(&self, rel: &EntityRelationship) -> bool {
+        self.parent.matches(rel.parent.to_str()) && self.child.matches(rel.child.to_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_search_behaves_like_sql() {
+        let search = SqlSearchText::partial("He*o");
+        assert!(search.matches("hello"));
+        assert!(search.matches("Say HEO!"));
+        assert!(!search.matches("hell"));
+        assert!(SqlSearchText::partial("_b").matches("ab"));
+        assert!(!SqlSearchText::partial("_b").matches("b"));
+        assert!(SqlSearchText::partial("").matches(""));
+    }
+
+    #[test]
+    fn exact_search_is_case_sensitive() {
+        let search = SqlSearchText::exact("Hello");
+        assert!(search.matches("Hello"));
+        assert!(!search.matches("hello"));
+        assert!(!search.matches("Hello!"));
+    }
+
+    #[test]
+    fn empty_search_matches_everything() {
+        assert!(SqlSearchText::empty().matches("anything"));
+        assert!(SqlSearchText::empty().matches(""));
+    }
 }
@@ -0,0 +1,27 @@
+use diesel::{Insertable, Queryable};
+
+use crate::sql::schema::change_log;
+
+#[derive(Clone, Debug, PartialEq, Eq, Queryable)]
+#[diesel(table_name = change_log)]
+pub(crate) struct SqlChangeLogEntry {
+    pub id: i64,
+    pub timestamp: i64,
+    pub author: Option<String>,
+    pub operation: String,
+    pub old_values: String,
+    pub new_values: String,
+    pub state: i32,
+}
+
+/// A change log entry before insertion, when the database has not yet assigned its id and it is
+/// in the default state.
+#[derive(Clone, Debug, PartialEq, Eq, Insertable)]
+#[diesel(table_name = change_log)]
+pub(crate) struct NewSqlChangeLogEntry {
+    pub timestamp: i64,
+    pub author: Option<String>,
+    pub operation: String,
+    pub old_values: String,
+    pub new_values: String,
+}
@@ -1,10 +1,12 @@
 //! This module contains the types used for reading and writing to and from the database.
 //! The only types for members are integers, Strings, and Optionals of these.
 
+pub(crate) mod change_log;
 pub(crate) mod entity;
 pub(crate) mod history;
 pub(crate) mod relationship;
 
+pub(crate) use change_log::{NewSqlChangeLogEntry, SqlChangeLogEntry};
 pub(crate) use entity::SqlEntityColumn;
 pub(crate) use history::SqlHistoryItem;
-pub(crate) use relationship::SqlEntityRelationship;
+pub(crate) use relationship::{SqlEntityRelationship, sql_ordinal};
@@ -1,6 +1,6 @@
 use diesel::{Insertable, Queryable};
 
-use crate::{sql::schema::relationships, types::*};
+use crate::{errors::LoreCoreError, sql::schema::relationships, types::*};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Insertable, Queryable)]
 #[diesel(table_name = relationships)]
@@ -8,24 +8,34 @@ pub(crate) struct SqlEntityRelationship {
     pub parent: String,
     pub child: String,
     pub role: String,
+    pub ordinal: i32,
 }
 
 impl EntityRelationship {
-    pub(crate) fn to_sql_entity_relationship(&self) -> SqlEntityRelationship {
-        SqlEntityRelationship {
+    pub(crate) fn to_sql_entity_relationship(
+        &self,
+    ) -> Result<SqlEntityRelationship, LoreCoreError> {
+        Ok(SqlEntityRelationship {
             parent: self.parent.to_string(),
             child: self.child.to_string(),
             role: self.role.to_string(),
-        }
+            ordinal: sql_ordinal(self.ordinal)?,
+        })
     }
 }
 
+pub(crate) fn sql_ordinal(ordinal: Ordinal) -> Result<i32, LoreCoreError> {
+    ordinal.check_range()?;
+    Ok(ordinal.to_int() as i32)
+}
+
 impl SqlEntityRelationship {
     pub(crate) fn to_relationship(&self) -> EntityRelationship {
         EntityRelationship {
             parent: self.parent.as_str().into(),
             child: self.child.as_str().into(),
             role: self.role.as_str().into(),
+            ordinal: self.ordinal.into(),
         }
     }
 }
@@ -0,0 +1,160 @@
+use crate::{errors::LoreCoreError, store::LoreStore, types::*};
+
+use super::{
+    lore_database::LoreDatabase,
+    search_params::{EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams},
+};
+
+/// Forwards to the inherent functions of `LoreDatabase`, which remain available without
+/// importing the trait. `read_entity`, `read_entities`, `read_relationship_graph` and
+/// `read_family_tree` only exist as default functions of the trait.
+impl LoreStore for LoreDatabase {
+    fn begin_transaction(&self) -> Result<(), LoreCoreError> {
+        LoreDatabase::begin_transaction(self)
+    }
+
+    fn commit_transaction(&self) -> Result<(), LoreCoreError> {
+        LoreDatabase::commit_transaction(self)
+    }
+
+    fn rollback_transaction(&self) -> Result<(), LoreCoreError> {
+        LoreDatabase::rollback_transaction(self)
+    }
+
+    fn write_entity_columns(&self, cols: Vec<EntityColumn>) -> Result<(), LoreCoreError> {
+        LoreDatabase::write_entity_columns(self, cols)
+    }
+
+    fn relabel_entity(&self, old_label: &Label, new_label: &Label) -> Result<(), LoreCoreError> {
+        LoreDatabase::relabel_entity(self, old_label, new_label)
+    }
+
+    fn delete_entity(&self, label: Label) -> Result<(), LoreCoreError> {
+        LoreDatabase::delete_entity(self, label)
+    }
+
+    fn change_entity_descriptor(
+        &self,
+        column: (&Label, Descriptor),
+        new_descriptor: &Descriptor,
+    ) -> Result<(), LoreCoreError> {
+        LoreDatabase::change_entity_descriptor(self, column, new_descriptor)
+    }
+
+    fn delete_entity_column(&self, column: (Label, Descriptor)) -> Result<(), LoreCoreError> {
+        LoreDatabase::delete_entity_column(self, column)
+    }
+
+    fn change_entity_description(
+        &self,
+        column: (&Label, &Descriptor),
+        new_description: &Description,
+    ) -> Result<(), LoreCoreError> {
+        LoreDatabase::change_entity_description(self, column, new_description)
+    }
+
+    fn read_entity_columns(
+        &self,
+        search_params: EntityColumnSearchParams,
+    ) -> Result<Vec<EntityColumn>, LoreCoreError> {
+        LoreDatabase::read_entity_columns(self, search_params)
+    }
+
+    fn write_history_items(&self, items: Vec<HistoryItem>) -> Result<(), LoreCoreError> {
+        LoreDatabase::write_history_items(self, items)
+    }
+
+    fn redate_history_item(
+        &self,
+        timestamp: Timestamp,
+        year: Year,
+        day: Day,
+    ) -> Result<(), LoreCoreError> {
+        LoreDatabase::redate_history_item(self, timestamp, year, day)
+    }
+
+    fn delete_history_item(&self, timestamp: Timestamp) -> Result<(), LoreCoreError> {
+        LoreDatabase::delete_history_item(self, timestamp)
+    }
+
+    fn change_history_item_content(
+        &self,
+        timestamp: Timestamp,
+        content: &HistoryItemContent,
+    ) -> Result<(), LoreCoreError> {
+        LoreDatabase::change_history_item_content(self, timestamp, content)
+    }
+
+    fn change_history_item_properties(
+        &self,
+        timestamp: Timestamp,
+        properties: &HistoryItemProperties,
+    ) -> Result<(), LoreCoreError> {
+        LoreDatabase::change_history_item_properties(self, timestamp, properties)
+    }
+
+    fn read_history_items(
+        &self,
+        search_params: HistoryItemSearchParams,
+    ) -> Result<Vec<HistoryItem>, LoreCoreError> {
+        LoreDatabase::read_history_items(self, search_params)
+    }
+
+    fn read_history_items_concerning(
+        &self,
+        label: &Label,
+    ) -> Result<Vec<HistoryItem>, LoreCoreError> {
+        LoreDatabase::read_history_items_concerning(self, label)
+    }
+
+    fn write_relationships(&self, rels: Vec<EntityRelationship>) -> Result<(), LoreCoreError> {
+        LoreDatabase::write_relationships(self, rels)
+    }
+
+    fn change_relationship_role(
+        &self,
+        old_relationship: EntityRelationship,
+        new_role: &Role,
+    ) -> Result<(), LoreCoreError> {
+        LoreDatabase::change_relationship_role(self, old_relationship, new_role)
+    }
+
+    fn delete_relationship(&self, relationship: EntityRelationship) -> Result<(), LoreCoreError> {
+        LoreDatabase::delete_relationship(self, relationship)
+    }
+
+    fn read_relationships(
+        &self,
+        search_params: RelationshipSearchParams,
+    ) -> Result<Vec<EntityRelationship>, LoreCoreError> {
+        LoreDatabase::read_relationships(self, search_params)
+    }
+
+    fn read_distinct_labels(&self) -> Result<Vec<Label>, LoreCoreError> {
+        LoreDatabase::read_distinct_labels(self)
+    }
+
+    fn read_distinct_descriptors(&self) -> Result<Vec<Descriptor>, LoreCoreError> {
+        LoreDatabase::read_distinct_descriptors(self)
+    }
+
+    fn read_distinct_years(&self) -> Result<Vec<Year>, LoreCoreError> {
+        LoreDatabase::read_distinct_years(self)
+    }
+
+    fn read_distinct_days(&self) -> Result<Vec<Day>, LoreCoreError> {
+        LoreDatabase::read_distinct_days(self)
+    }
+
+    fn read_distinct_parents(&self) -> Result<Vec<Parent>, LoreCoreError> {
+        LoreDatabase::read_distinct_parents(self)
+    }
+
+    fn read_distinct_children(&self) -> Result<Vec<Child>, LoreCoreError> {
+        LoreDatabase::read_distinct_children(self)
+    }
+
+    fn read_distinct_roles(&self) -> Result<Vec<Role>, LoreCoreError> {
+        LoreDatabase::read_distinct_roles(self)
+    }
+}
@@ -0,0 +1,317 @@
+//! The operations that every lore database backend offers.
+//! `LoreDatabase` stores the lore in an SQLite file, `MemoryDatabase` keeps it in memory.
+//! Code that only reads and writes lore can be written against `LoreStore` and then works with
+//! either of them.
+
+use crate::{
+    errors::LoreCoreError,
+    extractions::*,
+    genealogy::{FamilyTree, GenealogyRoles},
+    graph::{GraphExportParams, RelationshipGraph},
+    sql::search_params::{
+        EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams, SqlSearchText,
+    },
+    types::*,
+};
+
+pub trait LoreStore {
+    /// Starts a transaction that spans all following calls on this store, until it is
+    /// committed or rolled back.
+    fn begin_transaction(&self) -> Result<(), LoreCoreError>;
+
+    fn commit_transaction(&self) -> Result<(), LoreCoreError>;
+
+    fn rollback_transaction(&self) -> Result<(), LoreCoreError>;
+
+    /// Runs `f` in a transaction, which is committed if `f` succeeds and rolled back otherwise.
+    /// If rolling back fails as well, that failure is appended to the error returned by `f`.
+    fn in_transaction<T>(
+        &self,
+        f: impl FnOnce() -> Result<T, LoreCoreError>,
+    ) -> Result<T, LoreCoreError>
+    where
+        Self: Sized,
+    {
+        self.begin_transaction()?;
+        match f() {
+            Ok(value) => {
+                self.commit_transaction()?;
+                Ok(value)
+            }
+            Err(e) => match self.rollback_transaction() {
+                Ok(()) => Err(e),
+                Err(rollback_error) => Err(with_rollback_error(e, rollback_error)),
+            },
+        }
+    }
+
+    fn write_entity_columns(&self, cols: Vec<EntityColumn>) -> Result<(), LoreCoreError>;
+
+    fn relabel_entity(&self, old_label: &Label, new_label: &Label) -> Result<(), LoreCoreError>;
+
+    fn delete_entity(&self, label: Label) -> Result<(), LoreCoreError>;
+
+    fn change_entity_descriptor(
+        &self,
+        column: (&Label, Descriptor),
+        new_descriptor: &Descriptor,
+    ) -> Result<(), LoreCoreError>;
+
+    fn delete_entity_column(&self, column: (Label, Descriptor)) -> Result<(), LoreCoreError>;
+
+    fn change_entity_description(
+        &self,
+        column: (&Label, &Descriptor),
+        new_description: &Description,
+    ) -> Result<(), LoreCoreError>;
+
+    /// Returns the matching columns, sorted.
+    fn read_entity_columns(
+        &self,
+        search_params: EntityColumnSearchParams,
+    ) -> Result<Vec<EntityColumn>, LoreCoreError>;
+
+    fn write_history_items(&self, items: Vec<HistoryItem>) -> Result<(), LoreCoreError>;
+
+    fn redate_history_item(
+        &self,
+        timestamp: Timestamp,
+        year: Year,
+        day: Day,
+    ) -> Result<(), LoreCoreError>;
+
+    fn delete_history_item(&self, timestamp: Timestamp) -> Result<(), LoreCoreError>;
+
+    fn change_history_item_content(
+        &self,
+        timestamp: Timestamp,
+        content: &HistoryItemContent,
+    ) -> Result<(), LoreCoreError>;
+
+    fn change_history_item_properties(
+        &self,
+        timestamp: Timestamp,
+        properties: &HistoryItemProperties,
+    ) -> Result<(), LoreCoreError>;
+
+    /// Returns the matching items, sorted.
+    fn read_history_items(
+        &self,
+        search_params: HistoryItemSearchParams,
+    ) -> Result<Vec<HistoryItem>, LoreCoreError>;
+
+    fn write_relationships(&self, rels: Vec<EntityRelationship>) -> Result<(), LoreCoreError>;
+
+    fn change_relationship_role(
+        &self,
+        old_relationship: EntityRelationship,
+        new_role: &Role,
+    ) -> Result<(), LoreCoreError>;
+
+    fn delete_relationship(&self, relationship: EntityRelationship) -> Result<(), LoreCoreError>;
+
+    /// Returns the matching relationships, sorted.
+    fn read_relationships(
+        &self,
+        search_params: RelationshipSearchParams,
+    ) -> Result<Vec<EntityRelationship>, LoreCoreError>;
+
+    /// Returns the items whose content or properties concern `label`, sorted.
+    /// Backends that can narrow the search down without loading all items should override this.
+    fn read_history_items_concerning(
+        &self,
+        label: &Label,
+    ) -> Result<Vec<HistoryItem>, LoreCoreError> {
+        let mut items = self.read_history_items(HistoryItemSearchParams::empty())?;
+        items.retain(|item| item.concerns(label));
+        Ok(items)
+    }
+
+    fn read_entity(&self, label: &Label) -> Result<Option<Entity>, LoreCoreError> {
+        let label_search = || Some(SqlSearchText::exact(label.to_str()));
+        let cols = self.read_entity_columns(EntityColumnSearchParams::new(label_search(), None))?;
+        if cols.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Entity {
+            label: label.clone(),
+            descriptors: cols
+                .into_iter()
+                .map(|col| (col.descriptor, col.description))
+                .collect(),
+            parents: self
+                .read_relationships(RelationshipSearchParams::new(None, label_search()))?,
+            children: self
+                .read_relationships(RelationshipSearchParams::new(label_search(), None))?,
+            history: self.read_history_items_concerning(label)?,
+        }))
+    }
+
+    /// Reads all entities that have at least one column matching `search_params`.
+    /// The returned entities contain all of their descriptors, not only the matching ones.
+    fn read_entities(
+        &self,
+        search_params: EntityColumnSearchParams,
+    ) -> Result<Vec<Entity>, LoreCoreError> {
+        let mut labels: Vec<Label> = self
+            .read_entity_columns(search_params)?
+            .into_iter()
+            .map(|col| col.label)
+            .collect();
+        labels.dedup();
+        let mut entities = Vec::new();
+        for label in labels {
+            entities.extend(self.read_entity(&label)?);
+        }
+        Ok(entities)
+    }
+
+    fn read_relationship_graph(
+        &self,
+        params: GraphExportParams,
+    ) -> Result<RelationshipGraph, LoreCoreError> {
+        let rels = self.read_relationships(RelationshipSearchParams::empty())?;
+        let cols = if params.node_descriptors.is_empty() {
+            Vec::new()
+        } else {
+            self.read_entity_columns(EntityColumnSearchParams::empty())?
+        };
+        RelationshipGraph::build(&rels, &cols, &params)
+    }
+
+    fn read_family_tree(&self, roles: &GenealogyRoles) -> Result<FamilyTree, LoreCoreError> {
+        let rels = self.read_relationships(RelationshipSearchParams::empty())?;
+        Ok(FamilyTree::new(&rels, roles))
+    }
+
+    /// The `read_distinct_*` functions return sorted, deduplicated values.
+    /// Backends that can compute them without loading all records should override them.
+    fn read_distinct_labels(&self) -> Result<Vec<Label>, LoreCoreError> {
+        Ok(extract_labels(
+            &self.read_entity_columns(EntityColumnSearchParams::empty())?,
+        ))
+    }
+
+    fn read_distinct_descriptors(&self) -> Result<Vec<Descriptor>, LoreCoreError> {
+        Ok(extract_descriptors(
+            &self.read_entity_columns(EntityColumnSearchParams::empty())?,
+        ))
+    }
+
+    fn read_distinct_years(&self) -> Result<Vec<Year>, LoreCoreError> {
+        Ok(extract_years(
+            &self.read_history_items(HistoryItemSearchParams::empty())?,
+        ))
+    }
+
+    fn read_distinct_days(&self) -> Result<Vec<Day>, LoreCoreError> {
+        Ok(extract_days(
+            &self.read_history_items(HistoryItemSearchParams::empty())?,
+        ))
+    }
+
+    fn read_distinct_parents(&self) -> Result<Vec<Parent>, LoreCoreError> {
+        Ok(extract_parents(
+            &self.read_relationships(RelationshipSearchParams::empty())?,
+        ))
+    }
+
+    fn read_distinct_children(&self) -> Result<Vec<Child>, LoreCoreError> {
+        Ok(extract_children(
+            &self.read_relationships(RelationshipSearchParams::empty())?,
+        ))
+    }
+
+    fn read_distinct_roles(&self) -> Result<Vec<Role>, LoreCoreError> {
+        Ok(extract_roles(
+            &self.read_relationships(RelationshipSearchParams::empty())?,
+        ))
+    }
+}
+
+fn with_rollback_error(error: LoreCoreError, rollback_error: LoreCoreError) -> LoreCoreError {
+    let append = |message: String| {
+        format!(
+            "{} Rolling back the transaction failed as well: {}",
+            message, rollback_error
+        )
+    };
+    match error {
+        LoreCoreError::FileError(message) => LoreCoreError::FileError(append(message)),
+        LoreCoreError::InputError(message) => LoreCoreError::InputError(append(message)),
+        LoreCoreError::SqlError(message) => LoreCoreError::SqlError(append(message)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryDatabase;
+
+    #[test]
+    fn columns_are_grouped_by_label() {
+        let store = MemoryDatabase::new();
+        let cols = vec![
+            EntityColumn {
+                label: "a".into(),
+                descriptor: "x".into(),
+                description: "1".into(),
+            },
+            EntityColumn {
+                label: "a".into(),
+                descriptor: "y".into(),
+                description: "2".into(),
+            },
+            EntityColumn {
+                label: "b".into(),
+                descriptor: "x".into(),
+                description: "3".into(),
+            },
+        ];
+        let rels = vec![EntityRelationship {
+            parent: "a".into(),
+            child: "b".into(),
+            role: Role::NONE,
+            ordinal: Ordinal::NONE,
+        }];
+        let items = vec![HistoryItem {
+            timestamp: 1.into(),
+            year: 0.into(),
+            day: Day::NONE,
+            content: "\\entityref{b} was founded.".into(),
+            properties: HistoryItemProperties::none(),
+        }];
+        store.write_entity_columns(cols).unwrap();
+        store.write_relationships(rels.clone()).unwrap();
+        store.write_history_items(items.clone()).unwrap();
+
+        let entities = store
+            .read_entities(EntityColumnSearchParams::empty())
+            .unwrap();
+        assert_eq!(entities.len(), 2);
+        assert_eq!(
+            entities[0].descriptors,
+            vec![("x".into(), "1".into()), ("y".into(), "2".into())]
+        );
+        assert!(entities[0].parents.is_empty());
+        assert_eq!(entities[0].children, rels);
+        assert!(entities[0].history.is_empty());
+        assert_eq!(entities[1].parents, rels);
+        assert_eq!(entities[1].history, items);
+    }
+
+    #[test]
+    fn failed_rollback_keeps_the_error() {
+        let store = MemoryDatabase::new();
+        let result: Result<(), _> = store.in_transaction(|| {
+            store.commit_transaction()?;
+            Err(LoreCoreError::InputError("Writing failed.".to_string()))
+        });
+        match result {
+            Err(LoreCoreError::InputError(message)) => {
+                assert!(message.starts_with("Writing failed. Rolling back"))
+            }
+            _ => panic!("Expected the input error of the closure."),
+        }
+    }
+}
@@ -1,10 +1,27 @@
-use std::sync::atomic::{AtomicI64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::{
+    sync::atomic::{AtomicI64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use crate::types::*;
+use crate::{errors::LoreCoreError, types::*};
 
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
 static LAST_TIMESTAMP: AtomicI64 = AtomicI64::new(0);
 
+/// Like `current_timestamp`, but also available on targets without a system clock, such as
+/// `wasm32-unknown-unknown`, where it returns an error.
+pub fn try_current_timestamp() -> Result<Timestamp, LoreCoreError> {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    return Ok(current_timestamp());
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    return Err(LoreCoreError::InputError(
+        "There is no system clock on this platform, so timestamps have to be given explicitly."
+            .to_string(),
+    ));
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
 pub fn current_timestamp() -> Timestamp {
     let now = SystemTime::now();
     let mus_since_the_epoch = now
@@ -50,6 +67,12 @@ mod tests {
         }
     }
 
+    #[test]
+    fn timestamps_are_available_with_a_system_clock() {
+        let before = current_timestamp();
+        assert!(try_current_timestamp().unwrap() > before);
+    }
+
     #[test]
     fn test_current_timestamp_performance() {
         use std::time::Instant;
@@ -1,4 +1,7 @@
-use super::{description::Description, descriptor::Descriptor, label::Label};
+use super::{
+    description::Description, descriptor::Descriptor, history::HistoryItem, label::Label,
+    relationship::EntityRelationship,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct EntityColumn {
@@ -6,3 +9,16 @@ pub struct EntityColumn {
     pub descriptor: Descriptor,
     pub description: Description,
 }
+
+/// Everything the database knows about a single label.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entity {
+    pub label: Label,
+    pub descriptors: Vec<(Descriptor, Description)>,
+    /// Relationships in which the entity is the child.
+    pub parents: Vec<EntityRelationship>,
+    /// Relationships in which the entity is the parent.
+    pub children: Vec<EntityRelationship>,
+    /// History items that concern the entity.
+    pub history: Vec<HistoryItem>,
+}
@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use super::{
     day::Day, history_item_content::HistoryItemContent,
-    history_item_properties::HistoryItemProperties, timestamp::Timestamp, year::Year,
+    history_item_properties::HistoryItemProperties, label::Label, timestamp::Timestamp, year::Year,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -14,6 +14,42 @@ pub struct HistoryItem {
     pub properties: HistoryItemProperties,
 }
 
+pub fn entityref(label: &Label) -> String {
+    format!("\\entityref{{{}}}", label)
+}
+
+/// Replaces every `\entityref{label}` in `text` by `replacement(label)`.
+/// An `\entityref{` without closing brace is kept as it is.
+pub fn replace_entityrefs(text: &str, replacement: impl Fn(&Label) -> String) -> String {
+    const OPENING: &str = "\\entityref{";
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(OPENING) {
+        let after_opening = &rest[start + OPENING.len()..];
+        let Some(end) = after_opening.find('}') else {
+            break;
+        };
+        result += &rest[..start];
+        result += &replacement(&after_opening[..end].into());
+        rest = &after_opening[end + 1..];
+    }
+    result + rest
+}
+
+impl HistoryItem {
+    /// A history item concerns an entity if its content references the entity's label, or if the
+    /// label is listed among the additional concerns in its properties.
+    pub fn concerns(&self, label: &Label) -> bool {
+        let reference = entityref(label);
+        self.content.to_str().contains(&reference)
+            || self
+                .properties
+                .additional_concerns()
+                .iter()
+                .any(|concern| concern == &reference || concern == label.to_str())
+    }
+}
+
 impl PartialOrd for HistoryItem {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
@@ -43,3 +79,51 @@ impl Display for HistoryItem {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(content: &str, properties: &str) -> HistoryItem {
+        HistoryItem {
+            timestamp: 0.into(),
+            year: 0.into(),
+            day: Day::NONE,
+            content: content.into(),
+            properties: properties.into(),
+        }
+    }
+
+    #[test]
+    fn entityref_is_latex_macro() {
+        assert_eq!(entityref(&"some_label".into()), "\\entityref{some_label}");
+    }
+
+    #[test]
+    fn entityrefs_are_replaced() {
+        let replaced = replace_entityrefs(
+            "\\entityref{a} met \\entityref{b c}, \\entityref{broken",
+            |label| format!("<{}>", label),
+        );
+        assert_eq!(replaced, "<a> met <b c>, \\entityref{broken");
+    }
+
+    #[test]
+    fn item_concerns_referenced_labels() {
+        let item = item("\\entityref{alice} met \\entityref{bob}.", "");
+        assert!(item.concerns(&"alice".into()));
+        assert!(item.concerns(&"bob".into()));
+        assert!(!item.concerns(&"ali".into()));
+    }
+
+    #[test]
+    fn item_concerns_additional_concerns() {
+        let item = item(
+            "Something happened.",
+            "{\"additional_concerns\":[\"\\\\entityref{alice}\", \"bob\"]}",
+        );
+        assert!(item.concerns(&"alice".into()));
+        assert!(item.concerns(&"bob".into()));
+        assert!(!item.concerns(&"carol".into()));
+    }
+}
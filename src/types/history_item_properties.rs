@@ -13,6 +13,20 @@ impl HistoryItemProperties {
     pub fn to_map(&self) -> &HashMap<String, Value> {
         &self.0
     }
+
+    pub fn is_secret(&self) -> bool {
+        matches!(self.0.get("is_secret"), Some(Value::Bool(true)))
+    }
+
+    pub fn additional_concerns(&self) -> Vec<String> {
+        match self.0.get("additional_concerns") {
+            Some(Value::Array(concerns)) => concerns
+                .iter()
+                .filter_map(|concern| concern.as_str().map(|c| c.to_string()))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 impl From<HashMap<String, Value>> for HistoryItemProperties {
@@ -41,3 +55,30 @@ impl Display for HistoryItemProperties {
         serde_json::to_string(&self.0).unwrap_or_default().fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrecy_is_read_from_properties() {
+        assert!(HistoryItemProperties::from("{\"is_secret\":true}").is_secret());
+        assert!(!HistoryItemProperties::from("{\"is_secret\":false}").is_secret());
+        assert!(!HistoryItemProperties::none().is_secret());
+    }
+
+    #[test]
+    fn additional_concerns_are_read_from_properties() {
+        let properties =
+            HistoryItemProperties::from("{\"additional_concerns\":[\"\\\\entityref{a}\", \"b\"]}");
+        assert_eq!(
+            properties.additional_concerns(),
+            vec!["\\entityref{a}".to_string(), "b".to_string()]
+        );
+        assert!(
+            HistoryItemProperties::none()
+                .additional_concerns()
+                .is_empty()
+        );
+    }
+}
@@ -13,6 +13,21 @@ impl Label {
     pub fn is_protected(&self) -> bool {
         self.0.starts_with('_')
     }
+
+    /// Writes characters other than ASCII letters and digits as `-XX`, one escape per UTF-8 byte.
+    /// The result differs for different labels and can be used as an identifier in links and
+    /// LaTeX references without further encoding.
+    pub fn to_alphanumeric_key(&self) -> String {
+        let mut key = String::new();
+        for byte in self.0.bytes() {
+            if byte.is_ascii_alphanumeric() {
+                key.push(byte as char);
+            } else {
+                key += &format!("-{:02X}", byte);
+            }
+        }
+        key
+    }
 }
 
 impl From<&str> for Label {
@@ -7,6 +7,7 @@ pub mod history;
 pub mod history_item_content;
 pub mod history_item_properties;
 pub mod label;
+pub mod ordinal;
 pub mod parent;
 pub mod relationship;
 pub mod role;
@@ -17,11 +18,12 @@ pub use child::Child;
 pub use day::Day;
 pub use description::Description;
 pub use descriptor::Descriptor;
-pub use entity::EntityColumn;
+pub use entity::{Entity, EntityColumn};
 pub use history::HistoryItem;
 pub use history_item_content::HistoryItemContent;
 pub use history_item_properties::HistoryItemProperties;
 pub use label::Label;
+pub use ordinal::Ordinal;
 pub use parent::Parent;
 pub use relationship::EntityRelationship;
 pub use role::Role;
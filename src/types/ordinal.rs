@@ -0,0 +1,116 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use crate::errors::LoreCoreError;
+
+/// The position of a relationship among the other relationships of the same parent, starting at 1.
+/// Relationships without an explicit ordinal are sorted after those that have one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ordinal(pub(crate) Option<u32>);
+
+impl Ordinal {
+    pub const NONE: Ordinal = Ordinal(None);
+    /// The largest ordinal that every backend can store, as SQLite keeps ordinals in a 32 bit
+    /// signed integer column.
+    pub(crate) const MAX: Ordinal = Ordinal(Some(i32::MAX as u32));
+
+    pub fn to_int(&self) -> u32 {
+        self.0.unwrap_or(0)
+    }
+
+    pub fn to_optional_int(&self) -> Option<u32> {
+        self.0
+    }
+
+    pub fn is_some(&self) -> bool {
+        self.0.is_some()
+    }
+
+    pub(crate) fn check_range(&self) -> Result<(), LoreCoreError> {
+        if self.to_int() > Self::MAX.to_int() {
+            return Err(LoreCoreError::InputError(format!(
+                "Ordinal {} exceeds the largest storable ordinal {}.",
+                self,
+                Self::MAX
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl PartialOrd for Ordinal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ordinal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.0, other.0) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+impl From<u32> for Ordinal {
+    fn from(value: u32) -> Self {
+        if value > 0 {
+            Self(Some(value))
+        } else {
+            Self(None)
+        }
+    }
+}
+
+impl From<i32> for Ordinal {
+    fn from(value: i32) -> Self {
+        if value > 0 {
+            Self(Some(value as u32))
+        } else {
+            Self(None)
+        }
+    }
+}
+
+impl From<Option<u32>> for Ordinal {
+    fn from(value: Option<u32>) -> Self {
+        value.map(Self::from).unwrap_or(Self::NONE)
+    }
+}
+
+impl Display for Ordinal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(value) => value.fmt(f),
+            None => "".fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_ordinals_come_first() {
+        let mut ordinals: Vec<Ordinal> = vec![Ordinal::NONE, 2.into(), 1.into()];
+        ordinals.sort();
+        assert_eq!(ordinals, vec![1.into(), 2.into(), Ordinal::NONE]);
+    }
+
+    #[test]
+    fn zero_is_no_ordinal() {
+        assert_eq!(Ordinal::from(0), Ordinal::NONE);
+        assert_eq!(Ordinal::NONE.to_int(), 0);
+        assert_eq!(Ordinal::from(Some(0)), Ordinal::NONE);
+    }
+
+    #[test]
+    fn ordinals_above_max_are_out_of_range() {
+        assert!(Ordinal::MAX.check_range().is_ok());
+        assert!(Ordinal::NONE.check_range().is_ok());
+        assert!(Ordinal::from(u32::MAX).check_range().is_err());
+    }
+}
@@ -1,8 +1,25 @@
-use super::{child::Child, parent::Parent, role::Role};
+use super::{child::Child, ordinal::Ordinal, parent::Parent, role::Role};
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct EntityRelationship {
     pub parent: Parent,
     pub child: Child,
     pub role: Role,
+    pub ordinal: Ordinal,
+}
+
+impl PartialOrd for EntityRelationship {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for EntityRelationship {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.parent
+            .cmp(&other.parent)
+            .then_with(|| self.ordinal.cmp(&other.ordinal))
+            .then_with(|| self.child.cmp(&other.child))
+            .then_with(|| self.role.cmp(&other.role))
+    }
 }
@@ -42,104 +42,328 @@ class CHistoryItem(ctypes.Structure):
 class CEntityRelationship(ctypes.Structure):
     _fields_ = [("parent", ctypes.c_char_p),
                 ("child", ctypes.c_char_p),
-                ("role", ctypes.c_char_p)]
+                ("role", ctypes.c_char_p),
+                ("ordinal", ctypes.c_uint)]
+
+class CEntity(ctypes.Structure):
+    _fields_ = [("label", ctypes.c_char_p),
+                ("columns", ctypes.POINTER(CEntityColumn)),
+                ("num_columns", ctypes.c_ssize_t),
+                ("parents", ctypes.POINTER(CEntityRelationship)),
+                ("num_parents", ctypes.c_ssize_t),
+                ("children", ctypes.POINTER(CEntityRelationship)),
+                ("num_children", ctypes.c_ssize_t),
+                ("history", ctypes.POINTER(CHistoryItem)),
+                ("num_history", ctypes.c_ssize_t)]
+
+class CSqlSearchText(ctypes.Structure):
+    _fields_ = [("text", ctypes.c_char_p),
+                ("is_exact", ctypes.c_bool)]
+
+class CEntityColumnSearchParams(ctypes.Structure):
+    _fields_ = [("label", CSqlSearchText),
+                ("descriptor", CSqlSearchText)]
+
+class CHistoryItemSearchParams(ctypes.Structure):
+    _fields_ = [("has_year", ctypes.c_bool),
+                ("year", ctypes.c_int),
+                ("has_day", ctypes.c_bool),
+                ("day", ctypes.c_uint),
+                ("has_timestamp", ctypes.c_bool),
+                ("timestamp", ctypes.c_longlong),
+                ("content", CSqlSearchText)]
+
+class CRelationshipSearchParams(ctypes.Structure):
+    _fields_ = [("parent", CSqlSearchText),
+                ("child", CSqlSearchText)]
+
+LORE_OK = 0
+LORE_FILE_ERROR = 1
+LORE_INPUT_ERROR = 2
+LORE_SQL_ERROR = 3
+
+class LoreDatabaseHandle(ctypes.Structure):
+    pass
 
 print("Define the Rust functions")
+lore_open = rust_lib.lore_open
+lore_open.argtypes = [ctypes.c_char_p, ctypes.POINTER(ctypes.POINTER(LoreDatabaseHandle))]
+lore_open.restype = ctypes.c_int
+
+lore_close = rust_lib.lore_close
+lore_close.argtypes = [ctypes.POINTER(LoreDatabaseHandle)]
+lore_close.restype = None
+
+lore_begin_transaction = rust_lib.lore_begin_transaction
+lore_begin_transaction.argtypes = [ctypes.POINTER(LoreDatabaseHandle)]
+lore_begin_transaction.restype = ctypes.c_int
+
+lore_commit_transaction = rust_lib.lore_commit_transaction
+lore_commit_transaction.argtypes = [ctypes.POINTER(LoreDatabaseHandle)]
+lore_commit_transaction.restype = ctypes.c_int
+
+lore_rollback_transaction = rust_lib.lore_rollback_transaction
+lore_rollback_transaction.argtypes = [ctypes.POINTER(LoreDatabaseHandle)]
+lore_rollback_transaction.restype = ctypes.c_int
+
 write_entity_columns = rust_lib.write_entity_columns
-write_entity_columns.argtypes = [ctypes.c_char_p, ctypes.POINTER(CEntityColumn), ctypes.c_int]
-write_entity_columns.restype = ctypes.c_char_p
+write_entity_columns.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(CEntityColumn), ctypes.c_ssize_t]
+write_entity_columns.restype = ctypes.c_int
 
 get_number_of_entity_columns = rust_lib.get_number_of_entity_columns
-get_number_of_entity_columns.argtypes = [ctypes.c_char_p, ctypes.POINTER(ctypes.c_int)]
-get_number_of_entity_columns.restype = ctypes.c_char_p
+get_number_of_entity_columns.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(CEntityColumnSearchParams), ctypes.POINTER(ctypes.c_ssize_t)]
+get_number_of_entity_columns.restype = ctypes.c_int
 
 read_entity_columns = rust_lib.read_entity_columns
-read_entity_columns.argtypes = [ctypes.c_char_p, ctypes.POINTER(CEntityColumn)]
-read_entity_columns.restype = ctypes.c_char_p
+read_entity_columns.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(CEntityColumnSearchParams), ctypes.POINTER(CEntityColumn), ctypes.c_ssize_t, ctypes.POINTER(ctypes.c_ssize_t)]
+read_entity_columns.restype = ctypes.c_int
+
+read_entity = rust_lib.read_entity
+read_entity.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.c_char_p, ctypes.POINTER(CEntity)]
+read_entity.restype = ctypes.c_int
 
 write_history_items = rust_lib.write_history_items
-write_history_items.argtypes = [ctypes.c_char_p, ctypes.POINTER(CHistoryItem), ctypes.c_int]
-write_history_items.restype = ctypes.c_char_p
+write_history_items.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(CHistoryItem), ctypes.c_ssize_t]
+write_history_items.restype = ctypes.c_int
 
 get_number_of_history_items = rust_lib.get_number_of_history_items
-get_number_of_history_items.argtypes = [ctypes.c_char_p, ctypes.POINTER(ctypes.c_int)]
-get_number_of_history_items.restype = ctypes.c_char_p
+get_number_of_history_items.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(CHistoryItemSearchParams), ctypes.POINTER(ctypes.c_ssize_t)]
+get_number_of_history_items.restype = ctypes.c_int
 
 read_history_items = rust_lib.read_history_items
-read_history_items.argtypes = [ctypes.c_char_p, ctypes.POINTER(CHistoryItem)]
-read_history_items.restype = ctypes.c_char_p
+read_history_items.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(CHistoryItemSearchParams), ctypes.POINTER(CHistoryItem), ctypes.c_ssize_t, ctypes.POINTER(ctypes.c_ssize_t)]
+read_history_items.restype = ctypes.c_int
 
 write_relationships = rust_lib.write_relationships
-write_relationships.argtypes = [ctypes.c_char_p, ctypes.POINTER(CEntityRelationship), ctypes.c_int]
-write_relationships.restype = ctypes.c_char_p
+write_relationships.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(CEntityRelationship), ctypes.c_ssize_t]
+write_relationships.restype = ctypes.c_int
 
 get_number_of_relationships = rust_lib.get_number_of_relationships
-get_number_of_relationships.argtypes = [ctypes.c_char_p, ctypes.POINTER(ctypes.c_int)]
-get_number_of_relationships.restype = ctypes.c_char_p
+get_number_of_relationships.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(CRelationshipSearchParams), ctypes.POINTER(ctypes.c_ssize_t)]
+get_number_of_relationships.restype = ctypes.c_int
 
 read_relationships = rust_lib.read_relationships
-read_relationships.argtypes = [ctypes.c_char_p, ctypes.POINTER(CEntityRelationship)]
-read_relationships.restype = ctypes.c_char_p
+read_relationships.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(CRelationshipSearchParams), ctypes.POINTER(CEntityRelationship), ctypes.c_ssize_t, ctypes.POINTER(ctypes.c_ssize_t)]
+read_relationships.restype = ctypes.c_int
 
 get_current_timestamp = rust_lib.get_current_timestamp
 get_current_timestamp.argtypes = []
 get_current_timestamp.restype = ctypes.c_longlong
 
+lore_last_error = rust_lib.lore_last_error
+lore_last_error.argtypes = []
+lore_last_error.restype = ctypes.c_char_p
+
+free_string = rust_lib.free_string
+free_string.argtypes = [ctypes.c_void_p]
+free_string.restype = None
+
+free_entity_columns = rust_lib.free_entity_columns
+free_entity_columns.argtypes = [ctypes.POINTER(CEntityColumn), ctypes.c_ssize_t]
+free_entity_columns.restype = None
+
+free_history_items = rust_lib.free_history_items
+free_history_items.argtypes = [ctypes.POINTER(CHistoryItem), ctypes.c_ssize_t]
+free_history_items.restype = None
+
+free_relationships = rust_lib.free_relationships
+free_relationships.argtypes = [ctypes.POINTER(CEntityRelationship), ctypes.c_ssize_t]
+free_relationships.restype = None
+
+free_entity = rust_lib.free_entity
+free_entity.argtypes = [ctypes.POINTER(CEntity)]
+free_entity.restype = None
+
+read_distinct_labels = rust_lib.read_distinct_labels
+read_distinct_labels.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(ctypes.c_char_p), ctypes.c_ssize_t, ctypes.POINTER(ctypes.c_ssize_t)]
+read_distinct_labels.restype = ctypes.c_int
+
+read_distinct_descriptors = rust_lib.read_distinct_descriptors
+read_distinct_descriptors.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(ctypes.c_char_p), ctypes.c_ssize_t, ctypes.POINTER(ctypes.c_ssize_t)]
+read_distinct_descriptors.restype = ctypes.c_int
+
+read_distinct_parents = rust_lib.read_distinct_parents
+read_distinct_parents.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(ctypes.c_char_p), ctypes.c_ssize_t, ctypes.POINTER(ctypes.c_ssize_t)]
+read_distinct_parents.restype = ctypes.c_int
+
+read_distinct_children = rust_lib.read_distinct_children
+read_distinct_children.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(ctypes.c_char_p), ctypes.c_ssize_t, ctypes.POINTER(ctypes.c_ssize_t)]
+read_distinct_children.restype = ctypes.c_int
+
+read_distinct_roles = rust_lib.read_distinct_roles
+read_distinct_roles.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(ctypes.c_char_p), ctypes.c_ssize_t, ctypes.POINTER(ctypes.c_ssize_t)]
+read_distinct_roles.restype = ctypes.c_int
+
+read_distinct_years = rust_lib.read_distinct_years
+read_distinct_years.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(ctypes.c_int), ctypes.c_ssize_t, ctypes.POINTER(ctypes.c_ssize_t)]
+read_distinct_years.restype = ctypes.c_int
+
+read_distinct_days = rust_lib.read_distinct_days
+read_distinct_days.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(ctypes.c_uint), ctypes.c_ssize_t, ctypes.POINTER(ctypes.c_ssize_t)]
+read_distinct_days.restype = ctypes.c_int
+
+free_strings = rust_lib.free_strings
+free_strings.argtypes = [ctypes.POINTER(ctypes.c_char_p), ctypes.c_ssize_t]
+free_strings.restype = None
+
+relabel_entity = rust_lib.relabel_entity
+relabel_entity.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.c_char_p, ctypes.c_char_p]
+relabel_entity.restype = ctypes.c_int
+
+delete_entity = rust_lib.delete_entity
+delete_entity.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.c_char_p]
+delete_entity.restype = ctypes.c_int
+
+change_entity_descriptor = rust_lib.change_entity_descriptor
+change_entity_descriptor.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.c_char_p, ctypes.c_char_p, ctypes.c_char_p]
+change_entity_descriptor.restype = ctypes.c_int
+
+delete_entity_column = rust_lib.delete_entity_column
+delete_entity_column.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.c_char_p, ctypes.c_char_p]
+delete_entity_column.restype = ctypes.c_int
+
+change_entity_description = rust_lib.change_entity_description
+change_entity_description.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.c_char_p, ctypes.c_char_p, ctypes.c_char_p]
+change_entity_description.restype = ctypes.c_int
+
+redate_history_item = rust_lib.redate_history_item
+redate_history_item.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.c_longlong, ctypes.c_int, ctypes.c_uint]
+redate_history_item.restype = ctypes.c_int
+
+delete_history_item = rust_lib.delete_history_item
+delete_history_item.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.c_longlong]
+delete_history_item.restype = ctypes.c_int
+
+change_history_item_content = rust_lib.change_history_item_content
+change_history_item_content.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.c_longlong, ctypes.c_char_p]
+change_history_item_content.restype = ctypes.c_int
+
+change_history_item_properties = rust_lib.change_history_item_properties
+change_history_item_properties.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.c_longlong, ctypes.c_char_p]
+change_history_item_properties.restype = ctypes.c_int
+
+change_relationship_role = rust_lib.change_relationship_role
+change_relationship_role.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(CEntityRelationship), ctypes.c_char_p]
+change_relationship_role.restype = ctypes.c_int
+
+delete_relationship = rust_lib.delete_relationship
+delete_relationship.argtypes = [ctypes.POINTER(LoreDatabaseHandle), ctypes.POINTER(CEntityRelationship)]
+delete_relationship.restype = ctypes.c_int
+
+def open_database(path):
+    handle = ctypes.POINTER(LoreDatabaseHandle)()
+    result = lore_open(path, ctypes.byref(handle))
+    assert result == LORE_OK
+    return handle
+
 def test_write_entity_column():
     print("Running the write_entity_column test")
     
     temp_path = tempfile.NamedTemporaryFile(delete=False)
     print("Created a temporary file at: " + temp_path.name)
 
-    db_path = temp_path.name.encode('utf-8')
+    db = open_database(temp_path.name.encode('utf-8'))
     column1 = CEntityColumn(b"testlabel1", b"testdescriptor1", b"testdescription1")
     column2 = CEntityColumn(b"testlabel2", b"testdescriptor2", b"testdescription2")
     columns = (CEntityColumn * 2)(column1, column2)
 
     print("Writing the entity columns to the database")
-    result = write_entity_columns(db_path, columns, len(columns))
-    assert result.decode('utf-8') == ""
+    result = write_entity_columns(db, columns, len(columns))
+    assert result == LORE_OK
 
     print("Getting the number of entity columns in the database")
-    size = ctypes.c_int(0)
-    result = get_number_of_entity_columns(db_path, ctypes.byref(size))
-    assert result.decode('utf-8') == ""
+    size = ctypes.c_ssize_t(0)
+    result = get_number_of_entity_columns(db, None, ctypes.byref(size))
+    assert result == LORE_OK
     assert size.value == len(columns)
 
     print("Reading the entity columns from the database")
     read_columns = (CEntityColumn * size.value)()
-    result = read_entity_columns(db_path, read_columns)
-    assert result.decode('utf-8') == ""
+    read_size = ctypes.c_ssize_t(0)
+    result = read_entity_columns(db, None, read_columns, size.value, ctypes.byref(read_size))
+    assert result == LORE_OK
+    assert read_size.value == size.value
+    assert read_columns[0].label == b"testlabel1"
 
+    print("Freeing the entity columns")
+    free_entity_columns(read_columns, size.value)
+
+    lore_close(db)
     temp_path.close()
 test_write_entity_column()
 
+def test_read_entity():
+    print("Running the read_entity test")
+
+    temp_path = tempfile.NamedTemporaryFile(delete=False)
+    print("Created a temporary file at: " + temp_path.name)
+
+    db = open_database(temp_path.name.encode('utf-8'))
+    column1 = CEntityColumn(b"testlabel", b"testdescriptor1", b"testdescription1")
+    column2 = CEntityColumn(b"testlabel", b"testdescriptor2", b"testdescription2")
+    columns = (CEntityColumn * 2)(column1, column2)
+    result = write_entity_columns(db, columns, len(columns))
+    assert result == LORE_OK
+    relationships = (CEntityRelationship * 1)(CEntityRelationship(b"testparent", b"testlabel", b"", 0))
+    result = write_relationships(db, relationships, len(relationships))
+    assert result == LORE_OK
+
+    print("Reading the entity from the database")
+    entity = CEntity()
+    result = read_entity(db, b"testlabel", ctypes.byref(entity))
+    assert result == LORE_OK
+    assert entity.label == b"testlabel"
+    assert entity.num_columns == 2
+    assert entity.columns[1].description == b"testdescription2"
+    assert entity.num_parents == 1
+    assert entity.parents[0].parent == b"testparent"
+    assert entity.num_children == 0
+    assert entity.num_history == 0
+    free_entity(ctypes.byref(entity))
+    assert entity.num_columns == 0
+
+    print("Reading a non-existing entity from the database")
+    result = read_entity(db, b"fununu", ctypes.byref(entity))
+    assert result == LORE_INPUT_ERROR
+    assert b"fununu" in lore_last_error()
+
+    lore_close(db)
+    temp_path.close()
+test_read_entity()
+
 def test_write_history_items():
     print("Running the write_history_items test")
 
     temp_path = tempfile.NamedTemporaryFile(delete=False)
     print("Created a temporary file at: " + temp_path.name)
 
-    db_path = temp_path.name.encode('utf-8')
+    db = open_database(temp_path.name.encode('utf-8'))
     item1 = CHistoryItem(get_current_timestamp(), 2021, 29, b"testcontent1", b"testproperties1")
     item2 = CHistoryItem(get_current_timestamp(), 2021, 30, b"testcontent2", b"testproperties2")
     items = (CHistoryItem * 2)(item1, item2)
 
     print("Writing the history items to the database")
-    result = write_history_items(db_path, items, len(items))
-    assert result.decode('utf-8') == ""
+    result = write_history_items(db, items, len(items))
+    assert result == LORE_OK
 
     print("Getting the number of history items in the database")
-    size = ctypes.c_int(0)
-    result = get_number_of_history_items(db_path, ctypes.byref(size))
-    assert result.decode('utf-8') == ""
+    size = ctypes.c_ssize_t(0)
+    result = get_number_of_history_items(db, None, ctypes.byref(size))
+    assert result == LORE_OK
     assert size.value == len(items)
 
     print("Reading the history items from the database")
     read_items = (CHistoryItem * size.value)()
-    result = read_history_items(db_path, read_items)
-    assert result.decode('utf-8') == ""
+    read_size = ctypes.c_ssize_t(0)
+    result = read_history_items(db, None, read_items, size.value, ctypes.byref(read_size))
+    assert result == LORE_OK
+    assert read_size.value == size.value
+    assert read_items[0].content == b"testcontent1"
+
+    print("Freeing the history items")
+    free_history_items(read_items, size.value)
 
+    lore_close(db)
     temp_path.close()
 test_write_history_items()
 
@@ -149,29 +373,313 @@ def test_write_relationships():
     temp_path = tempfile.NamedTemporaryFile(delete=False)
     print("Created a temporary file at: " + temp_path.name)
 
-    db_path = temp_path.name.encode('utf-8')
-    relationship1 = CEntityRelationship(b"testparent1", b"testchild1", b"testrole1")
-    relationship2 = CEntityRelationship(b"testparent2", b"testchild2", b"testrole2")
+    db = open_database(temp_path.name.encode('utf-8'))
+    relationship1 = CEntityRelationship(b"testparent1", b"testchild1", b"testrole1", 0)
+    relationship2 = CEntityRelationship(b"testparent2", b"testchild2", b"testrole2", 1)
     relationships = (CEntityRelationship * 2)(relationship1, relationship2)
 
     print("Writing the relationships to the database")
-    result = write_relationships(db_path, relationships, len(relationships))
-    assert result.decode('utf-8') == ""
+    result = write_relationships(db, relationships, len(relationships))
+    assert result == LORE_OK
 
     print("Getting the number of relationships in the database")
-    size = ctypes.c_int(0)
-    result = get_number_of_relationships(db_path, ctypes.byref(size))
-    assert result.decode('utf-8') == ""
+    size = ctypes.c_ssize_t(0)
+    result = get_number_of_relationships(db, None, ctypes.byref(size))
+    assert result == LORE_OK
     assert size.value == len(relationships)
 
     print("Reading the relationships from the database")
     read_in_relationships = (CEntityRelationship * size.value)()
-    result = read_relationships(db_path, read_in_relationships)
-    assert result.decode('utf-8') == ""
+    read_size = ctypes.c_ssize_t(0)
+    result = read_relationships(db, None, read_in_relationships, size.value, ctypes.byref(read_size))
+    assert result == LORE_OK
+    assert read_size.value == size.value
+    assert read_in_relationships[0].parent == b"testparent1"
 
+    print("Freeing the relationships")
+    free_relationships(read_in_relationships, size.value)
+
+    lore_close(db)
     temp_path.close()
 test_write_relationships()
 
+def test_transactions():
+    print("Running the transactions test")
+
+    temp_path = tempfile.NamedTemporaryFile(delete=False)
+    print("Created a temporary file at: " + temp_path.name)
+
+    db = open_database(temp_path.name.encode('utf-8'))
+    columns = (CEntityColumn * 1)(CEntityColumn(b"testlabel", b"testdescriptor", b"testdescription"))
+
+    print("Rolling back a write")
+    assert lore_begin_transaction(db) == LORE_OK
+    assert write_entity_columns(db, columns, len(columns)) == LORE_OK
+    assert lore_rollback_transaction(db) == LORE_OK
+    size = ctypes.c_ssize_t(0)
+    assert get_number_of_entity_columns(db, None, ctypes.byref(size)) == LORE_OK
+    assert size.value == 0
+
+    print("Committing a write")
+    assert lore_begin_transaction(db) == LORE_OK
+    assert write_entity_columns(db, columns, len(columns)) == LORE_OK
+    assert lore_commit_transaction(db) == LORE_OK
+    assert get_number_of_entity_columns(db, None, ctypes.byref(size)) == LORE_OK
+    assert size.value == 1
+
+    print("Committing without a transaction")
+    assert lore_commit_transaction(db) != LORE_OK
+
+    lore_close(db)
+    temp_path.close()
+test_transactions()
+
+def test_read_into_too_small_buffer():
+    print("Running the read_into_too_small_buffer test")
+
+    temp_path = tempfile.NamedTemporaryFile(delete=False)
+    print("Created a temporary file at: " + temp_path.name)
+
+    db = open_database(temp_path.name.encode('utf-8'))
+    column1 = CEntityColumn(b"testlabel1", b"testdescriptor1", b"testdescription1")
+    column2 = CEntityColumn(b"testlabel2", b"testdescriptor2", b"testdescription2")
+    columns = (CEntityColumn * 2)(column1, column2)
+    assert write_entity_columns(db, columns, len(columns)) == LORE_OK
+
+    print("Reading into a buffer that is too small")
+    read_columns = (CEntityColumn * 1)()
+    read_size = ctypes.c_ssize_t(0)
+    result = read_entity_columns(db, None, read_columns, len(read_columns), ctypes.byref(read_size))
+    assert result == LORE_INPUT_ERROR
+    assert b"too small" in lore_last_error()
+    assert read_size.value == 2
+    assert read_columns[0].label is None
+
+    print("Retrying with the reported size")
+    read_columns = (CEntityColumn * read_size.value)()
+    result = read_entity_columns(db, None, read_columns, len(read_columns), ctypes.byref(read_size))
+    assert result == LORE_OK
+    assert read_columns[1].label == b"testlabel2"
+    free_entity_columns(read_columns, read_size.value)
+
+    lore_close(db)
+    temp_path.close()
+test_read_into_too_small_buffer()
+
+def test_read_with_search_params():
+    print("Running the read_with_search_params test")
+
+    temp_path = tempfile.NamedTemporaryFile(delete=False)
+    print("Created a temporary file at: " + temp_path.name)
+
+    db = open_database(temp_path.name.encode('utf-8'))
+    column1 = CEntityColumn(b"testlabel1", b"testdescriptor1", b"testdescription1")
+    column2 = CEntityColumn(b"testlabel2", b"testdescriptor2", b"testdescription2")
+    columns = (CEntityColumn * 2)(column1, column2)
+    assert write_entity_columns(db, columns, len(columns)) == LORE_OK
+    item1 = CHistoryItem(1, 2020, 0, b"testcontent1", b"{}")
+    item2 = CHistoryItem(2, 2021, 3, b"testcontent2", b"{}")
+    items = (CHistoryItem * 2)(item1, item2)
+    assert write_history_items(db, items, len(items)) == LORE_OK
+    relationship1 = CEntityRelationship(b"testparent1", b"testchild1", b"testrole1", 0)
+    relationship2 = CEntityRelationship(b"testparent2", b"testchild2", b"testrole2", 0)
+    relationships = (CEntityRelationship * 2)(relationship1, relationship2)
+    assert write_relationships(db, relationships, len(relationships)) == LORE_OK
+
+    print("Reading the entity columns with a given label")
+    params = CEntityColumnSearchParams(CSqlSearchText(b"testlabel2", True), CSqlSearchText(None, False))
+    size = ctypes.c_ssize_t(0)
+    assert get_number_of_entity_columns(db, ctypes.byref(params), ctypes.byref(size)) == LORE_OK
+    assert size.value == 1
+    read_columns = (CEntityColumn * size.value)()
+    result = read_entity_columns(db, ctypes.byref(params), read_columns, size.value, ctypes.byref(size))
+    assert result == LORE_OK
+    assert read_columns[0].label == b"testlabel2"
+    free_entity_columns(read_columns, size.value)
+
+    print("Reading the history items of a given year")
+    params = CHistoryItemSearchParams(True, 2021, False, 0, False, 0, CSqlSearchText(None, False))
+    read_items = (CHistoryItem * 2)()
+    result = read_history_items(db, ctypes.byref(params), read_items, len(read_items), ctypes.byref(size))
+    assert result == LORE_OK
+    assert size.value == 1
+    assert read_items[0].content == b"testcontent2"
+    free_history_items(read_items, size.value)
+
+    print("Reading the relationships of a partially matching child")
+    params = CRelationshipSearchParams(CSqlSearchText(None, False), CSqlSearchText(b"child1", False))
+    read_in_relationships = (CEntityRelationship * 2)()
+    result = read_relationships(db, ctypes.byref(params), read_in_relationships, len(read_in_relationships), ctypes.byref(size))
+    assert result == LORE_OK
+    assert size.value == 1
+    assert read_in_relationships[0].child == b"testchild1"
+    free_relationships(read_in_relationships, size.value)
+
+    lore_close(db)
+    temp_path.close()
+test_read_with_search_params()
+
+def read_all_entity_columns(db):
+    size = ctypes.c_ssize_t(0)
+    assert get_number_of_entity_columns(db, None, ctypes.byref(size)) == LORE_OK
+    columns = (CEntityColumn * size.value)()
+    assert read_entity_columns(db, None, columns, size.value, ctypes.byref(size)) == LORE_OK
+    result = [(c.label, c.descriptor, c.description) for c in columns]
+    free_entity_columns(columns, size.value)
+    return result
+
+def test_update_entity_columns():
+    print("Running the update_entity_columns test")
+
+    temp_path = tempfile.NamedTemporaryFile(delete=False)
+    print("Created a temporary file at: " + temp_path.name)
+
+    db = open_database(temp_path.name.encode('utf-8'))
+    column1 = CEntityColumn(b"testlabel", b"testdescriptor1", b"testdescription1")
+    column2 = CEntityColumn(b"testlabel", b"testdescriptor2", b"testdescription2")
+    columns = (CEntityColumn * 2)(column1, column2)
+    assert write_entity_columns(db, columns, len(columns)) == LORE_OK
+
+    print("Relabeling the entity")
+    assert relabel_entity(db, b"testlabel", b"newlabel") == LORE_OK
+    assert [c[0] for c in read_all_entity_columns(db)] == [b"newlabel", b"newlabel"]
+
+    print("Changing a descriptor and a description")
+    assert change_entity_descriptor(db, b"newlabel", b"testdescriptor1", b"newdescriptor") == LORE_OK
+    assert change_entity_description(db, b"newlabel", b"newdescriptor", b"newdescription") == LORE_OK
+    assert (b"newlabel", b"newdescriptor", b"newdescription") in read_all_entity_columns(db)
+
+    print("Deleting a column and the entity")
+    assert delete_entity_column(db, b"newlabel", b"testdescriptor2") == LORE_OK
+    assert len(read_all_entity_columns(db)) == 1
+    assert delete_entity(db, b"newlabel") == LORE_OK
+    assert read_all_entity_columns(db) == []
+
+    print("Passing a null label")
+    assert delete_entity(db, None) != LORE_OK
+
+    lore_close(db)
+    temp_path.close()
+test_update_entity_columns()
+
+def test_update_history_items():
+    print("Running the update_history_items test")
+
+    temp_path = tempfile.NamedTemporaryFile(delete=False)
+    print("Created a temporary file at: " + temp_path.name)
+
+    db = open_database(temp_path.name.encode('utf-8'))
+    items = (CHistoryItem * 1)(CHistoryItem(1, 2020, 1, b"testcontent", b"{}"))
+    assert write_history_items(db, items, len(items)) == LORE_OK
+
+    print("Changing the history item")
+    assert redate_history_item(db, 1, 2021, 0) == LORE_OK
+    assert change_history_item_content(db, 1, b"newcontent") == LORE_OK
+    assert change_history_item_properties(db, 1, b"{\"is_secret\":true}") == LORE_OK
+    read_items = (CHistoryItem * 1)()
+    size = ctypes.c_ssize_t(0)
+    assert read_history_items(db, None, read_items, 1, ctypes.byref(size)) == LORE_OK
+    assert read_items[0].year == 2021
+    assert read_items[0].day == 0
+    assert read_items[0].content == b"newcontent"
+    assert b"is_secret" in read_items[0].properties
+    free_history_items(read_items, size.value)
+
+    print("Deleting the history item")
+    assert delete_history_item(db, 1) == LORE_OK
+    assert get_number_of_history_items(db, None, ctypes.byref(size)) == LORE_OK
+    assert size.value == 0
+
+    lore_close(db)
+    temp_path.close()
+test_update_history_items()
+
+def test_update_relationships():
+    print("Running the update_relationships test")
+
+    temp_path = tempfile.NamedTemporaryFile(delete=False)
+    print("Created a temporary file at: " + temp_path.name)
+
+    db = open_database(temp_path.name.encode('utf-8'))
+    relationship = CEntityRelationship(b"testparent", b"testchild", b"testrole", 0)
+    relationships = (CEntityRelationship * 1)(relationship)
+    assert write_relationships(db, relationships, len(relationships)) == LORE_OK
+
+    print("Changing the role")
+    assert change_relationship_role(db, ctypes.byref(relationship), b"newrole") == LORE_OK
+    read_in_relationships = (CEntityRelationship * 1)()
+    size = ctypes.c_ssize_t(0)
+    assert read_relationships(db, None, read_in_relationships, 1, ctypes.byref(size)) == LORE_OK
+    assert read_in_relationships[0].role == b"newrole"
+    free_relationships(read_in_relationships, size.value)
+
+    print("Deleting the relationship")
+    relationship.role = b"newrole"
+    assert delete_relationship(db, ctypes.byref(relationship)) == LORE_OK
+    assert get_number_of_relationships(db, None, ctypes.byref(size)) == LORE_OK
+    assert size.value == 0
+
+    print("Passing a null relationship")
+    assert delete_relationship(db, None) == LORE_INPUT_ERROR
+    assert lore_last_error() == b"Relationship pointer is null."
+    assert get_number_of_relationships(db, None, ctypes.byref(size)) == LORE_OK
+    assert lore_last_error() == b""
+
+    lore_close(db)
+    temp_path.close()
+test_update_relationships()
+
+def read_distinct_strings(db, function):
+    size = ctypes.c_ssize_t(0)
+    strings = (ctypes.c_char_p * 0)()
+    assert function(db, strings, 0, ctypes.byref(size)) != LORE_OK
+    strings = (ctypes.c_char_p * size.value)()
+    assert function(db, strings, size.value, ctypes.byref(size)) == LORE_OK
+    result = list(strings)
+    free_strings(strings, size.value)
+    return result
+
+def test_read_distinct_values():
+    print("Running the read_distinct_values test")
+
+    temp_path = tempfile.NamedTemporaryFile(delete=False)
+    print("Created a temporary file at: " + temp_path.name)
+
+    db = open_database(temp_path.name.encode('utf-8'))
+    column1 = CEntityColumn(b"testlabel2", b"testdescriptor", b"testdescription")
+    column2 = CEntityColumn(b"testlabel1", b"testdescriptor", b"testdescription")
+    columns = (CEntityColumn * 2)(column1, column2)
+    assert write_entity_columns(db, columns, len(columns)) == LORE_OK
+    item1 = CHistoryItem(1, 2021, 0, b"testcontent1", b"{}")
+    item2 = CHistoryItem(2, 2020, 5, b"testcontent2", b"{}")
+    item3 = CHistoryItem(3, 2021, 5, b"testcontent3", b"{}")
+    items = (CHistoryItem * 3)(item1, item2, item3)
+    assert write_history_items(db, items, len(items)) == LORE_OK
+    relationship1 = CEntityRelationship(b"testparent", b"testchild1", b"", 0)
+    relationship2 = CEntityRelationship(b"testparent", b"testchild2", b"testrole", 0)
+    relationships = (CEntityRelationship * 2)(relationship1, relationship2)
+    assert write_relationships(db, relationships, len(relationships)) == LORE_OK
+
+    print("Reading the distinct strings")
+    assert read_distinct_strings(db, read_distinct_labels) == [b"testlabel1", b"testlabel2"]
+    assert read_distinct_strings(db, read_distinct_descriptors) == [b"testdescriptor"]
+    assert read_distinct_strings(db, read_distinct_parents) == [b"testparent"]
+    assert read_distinct_strings(db, read_distinct_children) == [b"testchild1", b"testchild2"]
+    assert read_distinct_strings(db, read_distinct_roles) == [b"testrole"]
+
+    print("Reading the distinct years and days")
+    size = ctypes.c_ssize_t(0)
+    years = (ctypes.c_int * 3)()
+    assert read_distinct_years(db, years, len(years), ctypes.byref(size)) == LORE_OK
+    assert list(years)[:size.value] == [2020, 2021]
+    days = (ctypes.c_uint * 3)()
+    assert read_distinct_days(db, days, len(days), ctypes.byref(size)) == LORE_OK
+    assert list(days)[:size.value] == [0, 5]
+
+    lore_close(db)
+    temp_path.close()
+test_read_distinct_values()
+
 def test_get_current_timestamp():
     print("Running the get_current_timestamp test")
     timestamp = get_current_timestamp()
@@ -1,3 +1,5 @@
+#![cfg(feature = "sqlite")]
+
 #[cfg(test)]
 mod tests {
     use std::process::Command;
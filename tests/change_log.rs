@@ -0,0 +1,200 @@
+#![cfg(feature = "sqlite")]
+
+use lorecore::{
+    sql::{
+        change_log::{ChangeLogSearchParams, ChangeOperation, ChangedRecords},
+        lore_database::LoreDatabase,
+    },
+    timestamp::current_timestamp,
+    types::*,
+};
+use tempfile::NamedTempFile;
+
+fn column(label: &str, descriptor: &str) -> EntityColumn {
+    EntityColumn {
+        label: label.into(),
+        descriptor: descriptor.into(),
+        description: "description".into(),
+    }
+}
+
+#[test]
+fn every_change_is_logged_with_old_and_new_values() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    let before = current_timestamp();
+    db.write_entity_columns(vec![column("Hero", "Age"), column("Hero", "Home")])
+        .unwrap();
+    db.relabel_entity(&"Hero".into(), &"Heroine".into())
+        .unwrap();
+    db.delete_entity_column(("Heroine".into(), "Age".into()))
+        .unwrap();
+
+    let log = db.read_change_log(ChangeLogSearchParams::empty()).unwrap();
+    let operations: Vec<_> = log.iter().map(|entry| entry.operation).collect();
+    assert_eq!(
+        operations,
+        vec![
+            ChangeOperation::WriteEntityColumns,
+            ChangeOperation::RelabelEntity,
+            ChangeOperation::DeleteEntityColumn,
+        ]
+    );
+    assert!(log.windows(2).all(|w| w[0].id < w[1].id));
+    assert!(log.iter().all(|entry| entry.timestamp > before));
+    assert_eq!(
+        log[1].old,
+        ChangedRecords::EntityColumns(vec![column("Hero", "Age"), column("Hero", "Home")])
+    );
+    assert_eq!(
+        log[1].new,
+        ChangedRecords::EntityColumns(vec![column("Heroine", "Age"), column("Heroine", "Home")])
+    );
+    assert_eq!(
+        log[2].old,
+        ChangedRecords::EntityColumns(vec![column("Heroine", "Age")])
+    );
+    assert!(log[2].new.is_empty());
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn history_and_relationship_changes_are_logged() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    let item = HistoryItem {
+        timestamp: 1.into(),
+        year: 10.into(),
+        day: Day::NONE,
+        content: "Born.".into(),
+        properties: HistoryItemProperties::none(),
+    };
+    db.write_history_items(vec![item.clone()]).unwrap();
+    db.redate_history_item(1.into(), 11.into(), 2.into())
+        .unwrap();
+    let rels: Vec<EntityRelationship> = ["Cat", "Dog"]
+        .iter()
+        .map(|child| EntityRelationship {
+            parent: "Hero".into(),
+            child: (*child).into(),
+            role: Role::NONE,
+            ordinal: Ordinal::NONE,
+        })
+        .collect();
+    db.write_relationships(rels.clone()).unwrap();
+    db.reorder_children(&"Hero".into(), &["Dog".into()])
+        .unwrap();
+
+    let log = db.read_change_log(ChangeLogSearchParams::empty()).unwrap();
+    assert_eq!(log.len(), 4);
+    assert_eq!(log[1].old, ChangedRecords::HistoryItems(vec![item.clone()]));
+    assert_eq!(
+        log[1].new,
+        ChangedRecords::HistoryItems(vec![HistoryItem {
+            year: 11.into(),
+            day: 2.into(),
+            ..item
+        }])
+    );
+    // Only the relationship whose ordinal changed is part of the entry.
+    assert_eq!(log[3].operation, ChangeOperation::ReorderChildren);
+    assert_eq!(
+        log[3].old,
+        ChangedRecords::Relationships(vec![rels[1].clone()])
+    );
+    assert_eq!(
+        log[3].new,
+        ChangedRecords::Relationships(vec![EntityRelationship {
+            ordinal: 1.into(),
+            ..rels[1].clone()
+        }])
+    );
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn log_can_be_searched_by_author_and_operation() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    db.write_entity_columns(vec![column("Hero", "Age")])
+        .unwrap();
+    db.set_author(Some("Alex".to_string())).unwrap();
+    db.change_entity_description((&"Hero".into(), &"Age".into()), &"12".into())
+        .unwrap();
+    db.delete_entity("Hero".into()).unwrap();
+
+    let by_alex = db
+        .read_change_log(ChangeLogSearchParams::new(
+            Some("Alex".to_string()),
+            None,
+            None,
+            None,
+        ))
+        .unwrap();
+    assert_eq!(by_alex.len(), 2);
+    assert!(
+        by_alex
+            .iter()
+            .all(|entry| entry.author.as_deref() == Some("Alex"))
+    );
+
+    let deletions = db
+        .read_change_log(ChangeLogSearchParams::new(
+            None,
+            Some(ChangeOperation::DeleteEntity),
+            None,
+            None,
+        ))
+        .unwrap();
+    assert_eq!(deletions.len(), 1);
+
+    let all = db.read_change_log(ChangeLogSearchParams::empty()).unwrap();
+    let since_second = db
+        .read_change_log(ChangeLogSearchParams::new(
+            None,
+            None,
+            Some(all[1].timestamp),
+            None,
+        ))
+        .unwrap();
+    assert_eq!(since_second, all[1..].to_vec());
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn failed_and_ineffective_changes_are_not_logged() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    db.write_entity_columns(vec![column("Hero", "Age")])
+        .unwrap();
+    assert!(
+        db.write_entity_columns(vec![column("Dog", "Age"), column("Hero", "Age")])
+            .is_err()
+    );
+    db.delete_entity("Nobody".into()).unwrap();
+    assert!(
+        db.reorder_children(&"Hero".into(), &["Nobody".into()])
+            .is_err()
+    );
+
+    let log = db.read_change_log(ChangeLogSearchParams::empty()).unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].operation, ChangeOperation::WriteEntityColumns);
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn changes_in_rolled_back_transactions_are_not_logged() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    db.begin_transaction().unwrap();
+    db.write_entity_columns(vec![column("Hero", "Age")])
+        .unwrap();
+    db.rollback_transaction().unwrap();
+    assert!(
+        db.read_change_log(ChangeLogSearchParams::empty())
+            .unwrap()
+            .is_empty()
+    );
+    temp_path.close().unwrap();
+}
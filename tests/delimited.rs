@@ -0,0 +1,154 @@
+use lorecore::{
+    delimited::*,
+    memory::MemoryDatabase,
+    sql::search_params::{HistoryItemSearchParams, RelationshipSearchParams},
+    store::LoreStore,
+    types::*,
+};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test as test;
+
+fn example_store() -> MemoryDatabase {
+    let store = MemoryDatabase::new();
+    store
+        .write_entity_columns(vec![
+            EntityColumn {
+                label: "Hero".into(),
+                descriptor: "Biography".into(),
+                description: "Born in a village,\nleft it \"early\".\tThe end.".into(),
+            },
+            EntityColumn {
+                label: "Villain".into(),
+                descriptor: "Age".into(),
+                description: Description::NONE,
+            },
+        ])
+        .unwrap();
+    store
+        .write_history_items(vec![HistoryItem {
+            timestamp: 5.into(),
+            year: (-20).into(),
+            day: 2.into(),
+            content: "\\entityref{Hero} was born.".into(),
+            properties: "{\"is_secret\":true,\"additional_concerns\":[\"Villain\"]}".into(),
+        }])
+        .unwrap();
+    store
+        .write_relationships(vec![
+            EntityRelationship {
+                parent: "Hero".into(),
+                child: "Villain".into(),
+                role: "nemesis".into(),
+                ordinal: 1.into(),
+            },
+            EntityRelationship {
+                parent: "Villain".into(),
+                child: "Hero".into(),
+                role: Role::NONE,
+                ordinal: Ordinal::NONE,
+            },
+        ])
+        .unwrap();
+    store
+}
+
+#[test]
+fn tables_round_trip_through_csv_and_tsv() {
+    let original = example_store();
+    for params in [DelimitedParams::csv(), DelimitedParams::tsv()] {
+        let cols = export_entity_columns(&original, &params).unwrap();
+        let items = export_history_items(&original, &params).unwrap();
+        let rels = export_relationships(&original, &params).unwrap();
+
+        let copy = MemoryDatabase::new();
+        let report = import_entity_columns(&copy, &cols, &params, false).unwrap();
+        assert_eq!(report.rows, 2);
+        assert!(report.conflicts.is_empty());
+        import_history_items(&copy, &items, &params, false).unwrap();
+        import_relationships(&copy, &rels, &params, false).unwrap();
+
+        assert_eq!(export_entity_columns(&copy, &params).unwrap(), cols);
+        assert_eq!(export_history_items(&copy, &params).unwrap(), items);
+        assert_eq!(export_relationships(&copy, &params).unwrap(), rels);
+        assert_eq!(
+            copy.read_history_items(HistoryItemSearchParams::empty())
+                .unwrap(),
+            original
+                .read_history_items(HistoryItemSearchParams::empty())
+                .unwrap()
+        );
+    }
+}
+
+#[test]
+fn dry_run_reports_conflicts_without_writing() {
+    let store = example_store();
+    let text = "label,descriptor,description\n\
+                Hero,Biography,Changed\n\
+                Sidekick,Age,Young\n\
+                Sidekick,Age,Old\n";
+    let report = import_entity_columns(&store, text, &DelimitedParams::csv(), true).unwrap();
+    assert_eq!(report.rows, 3);
+    let rows: Vec<usize> = report.conflicts.iter().map(|c| c.row).collect();
+    assert_eq!(rows, vec![2, 4]);
+    assert!(report.conflicts[1].message.contains("row 3"));
+    assert!(store.read_entity(&"Sidekick".into()).unwrap().is_none());
+
+    assert!(import_entity_columns(&store, text, &DelimitedParams::csv(), false).is_err());
+    assert!(store.read_entity(&"Sidekick".into()).unwrap().is_none());
+}
+
+// `try_current_timestamp` needs the system clock.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn spreadsheet_without_timestamps_and_with_custom_headers() {
+    let store = MemoryDatabase::new();
+    let params = DelimitedParams::new(
+        Delimiter::Comma,
+        None,
+        vec![
+            ("year".to_string(), "Year".to_string()),
+            ("content".to_string(), "What happened".to_string()),
+        ],
+    );
+    let text = "Year,What happened,Comment\n12,First,ignored\n12,Second,\n";
+    import_history_items(&store, text, &params, false).unwrap();
+    let items = store
+        .read_history_items(HistoryItemSearchParams::empty())
+        .unwrap();
+    assert_eq!(items.len(), 2);
+    assert_ne!(items[0].timestamp, items[1].timestamp);
+    assert!(items.iter().all(|item| item.year == 12.into()));
+}
+
+#[test]
+fn tables_without_headers_use_the_field_order() {
+    let store = MemoryDatabase::new();
+    let params = DelimitedParams::new(Delimiter::Tab, Some(false), Vec::new());
+    import_relationships(
+        &store,
+        "Anna\tBert\tspouse\nAnna\tClara\t\t2\n",
+        &params,
+        false,
+    )
+    .unwrap();
+    let rels = store
+        .read_relationships(RelationshipSearchParams::empty())
+        .unwrap();
+    assert_eq!(rels.len(), 2);
+    assert_eq!(rels[0].ordinal, 2.into());
+    assert_eq!(rels[1].role, "spouse".into());
+}
+
+#[test]
+fn unparsable_cells_name_their_row() {
+    let store = MemoryDatabase::new();
+    let text = "timestamp,year,content\n1,12,fine\n2,twelve,broken\n";
+    let result = import_history_items(&store, text, &DelimitedParams::csv(), true);
+    match result {
+        Err(lorecore::errors::LoreCoreError::InputError(message)) => {
+            assert!(message.starts_with("Row 3:"), "{}", message)
+        }
+        _ => panic!("Expected an input error"),
+    }
+}
@@ -0,0 +1,159 @@
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test as test;
+
+use lorecore::{
+    diff::{Change, diff_json, diff_stores},
+    json::export_json,
+    memory::MemoryDatabase,
+    store::LoreStore,
+    types::*,
+};
+
+fn column(label: &str, description: &str) -> EntityColumn {
+    EntityColumn {
+        label: label.into(),
+        descriptor: "Age".into(),
+        description: description.into(),
+    }
+}
+
+fn item(timestamp: i64, content: &str) -> HistoryItem {
+    HistoryItem {
+        timestamp: timestamp.into(),
+        year: 12.into(),
+        day: Day::NONE,
+        content: content.into(),
+        properties: HistoryItemProperties::none(),
+    }
+}
+
+fn relationship(child: &str, ordinal: Ordinal) -> EntityRelationship {
+    EntityRelationship {
+        parent: "Hero".into(),
+        child: child.into(),
+        role: Role::NONE,
+        ordinal,
+    }
+}
+
+fn old_and_new() -> (MemoryDatabase, MemoryDatabase) {
+    let old = MemoryDatabase::new();
+    old.write_entity_columns(vec![column("Hero", "12"), column("Villain", "40")])
+        .unwrap();
+    old.write_history_items(vec![item(1, "Born."), item(2, "Lost.")])
+        .unwrap();
+    old.write_relationships(vec![relationship("Dog", 1.into())])
+        .unwrap();
+
+    let new = MemoryDatabase::new();
+    new.write_entity_columns(vec![column("Hero", "13"), column("Sidekick", "10")])
+        .unwrap();
+    new.write_history_items(vec![item(1, "Born."), item(3, "Found.")])
+        .unwrap();
+    new.write_relationships(vec![
+        relationship("Dog", 2.into()),
+        relationship("Cat", Ordinal::NONE),
+    ])
+    .unwrap();
+    (old, new)
+}
+
+#[test]
+fn identical_stores_have_no_differences() {
+    let (old, _) = old_and_new();
+    let diff = diff_stores(&old, &old).unwrap();
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "No differences.\n");
+}
+
+#[test]
+fn added_removed_and_modified_records_are_found() {
+    let (old, new) = old_and_new();
+    let diff = diff_stores(&old, &new).unwrap();
+    assert_eq!(
+        diff.entity_columns,
+        vec![
+            Change::Modified {
+                old: column("Hero", "12"),
+                new: column("Hero", "13"),
+            },
+            Change::Added(column("Sidekick", "10")),
+            Change::Removed(column("Villain", "40")),
+        ]
+    );
+    assert_eq!(
+        diff.history_items,
+        vec![
+            Change::Removed(item(2, "Lost.")),
+            Change::Added(item(3, "Found.")),
+        ]
+    );
+    assert_eq!(
+        diff.relationships,
+        vec![
+            Change::Added(relationship("Cat", Ordinal::NONE)),
+            Change::Modified {
+                old: relationship("Dog", 1.into()),
+                new: relationship("Dog", 2.into()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn diff_is_rendered_for_humans() {
+    let (old, new) = old_and_new();
+    let expected = r#"~ entity "Hero", descriptor "Age": "12" -> "13"
++ entity "Sidekick", descriptor "Age": "10"
+- entity "Villain", descriptor "Age": "40"
+- history item 2 in year 12: "Lost."
++ history item 3 in year 12: "Found."
++ relationship "Hero" -> "Cat"
+~ relationship "Hero" -> "Dog": ordinal Some(1) -> Some(2)
+"#;
+    assert_eq!(diff_stores(&old, &new).unwrap().to_string(), expected);
+}
+
+#[test]
+fn diff_is_rendered_as_json() {
+    let (old, new) = old_and_new();
+    let json = diff_stores(&old, &new).unwrap().to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let cols = value["entity_columns"].as_array().unwrap();
+    assert_eq!(cols.len(), 3);
+    assert_eq!(cols[0]["change"], "modified");
+    assert_eq!(cols[0]["old"]["description"], "12");
+    assert_eq!(cols[0]["new"]["description"], "13");
+    assert_eq!(cols[1]["change"], "added");
+    assert_eq!(cols[1]["new"]["label"], "Sidekick");
+    assert_eq!(cols[2]["change"], "removed");
+    assert_eq!(cols[2]["old"]["label"], "Villain");
+}
+
+#[test]
+fn json_export_can_be_compared_to_a_store() {
+    let (old, new) = old_and_new();
+    let json = export_json(&old).unwrap();
+    assert_eq!(
+        diff_json(&json, &new).unwrap(),
+        diff_stores(&old, &new).unwrap()
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_database_can_be_compared_to_memory() {
+    use lorecore::{json::import_json, sql::lore_database::LoreDatabase};
+    use tempfile::NamedTempFile;
+
+    let (old, new) = old_and_new();
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    import_json(&db, &export_json(&new).unwrap()).unwrap();
+    assert!(diff_stores(&new, &db).unwrap().is_empty());
+    assert_eq!(
+        diff_stores(&old, &db).unwrap(),
+        diff_stores(&old, &new).unwrap()
+    );
+    temp_path.close().unwrap();
+}
@@ -0,0 +1,223 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use lorecore::{
+    directory::{export_directory, import_directory},
+    errors::LoreCoreError,
+    json::export_json,
+    memory::MemoryDatabase,
+    store::LoreStore,
+    types::*,
+};
+use tempfile::tempdir;
+
+fn example_store() -> MemoryDatabase {
+    let store = MemoryDatabase::new();
+    store
+        .write_entity_columns(vec![
+            EntityColumn {
+                label: "Hero".into(),
+                descriptor: "Age".into(),
+                description: "Young".into(),
+            },
+            EntityColumn {
+                label: "Hero".into(),
+                descriptor: "Biography".into(),
+                description: "Born in a village.\nLeft it \"early\".\n".into(),
+            },
+            EntityColumn {
+                label: "hero's dog/cat".into(),
+                descriptor: "_secret".into(),
+                description: "It is a cat.".into(),
+            },
+            EntityColumn {
+                label: "hero".into(),
+                descriptor: "Age".into(),
+                description: "Old".into(),
+            },
+            EntityColumn {
+                label: "".into(),
+                descriptor: "Note".into(),
+                description: "Nobody in particular.".into(),
+            },
+        ])
+        .unwrap();
+    store
+        .write_history_items(vec![
+            HistoryItem {
+                timestamp: 2.into(),
+                year: (-5).into(),
+                day: 3.into(),
+                content: "\\entityref{Hero} was born.".into(),
+                properties: "{\"is_secret\":true,\"additional_concerns\":[\"a\",\"b\"]}".into(),
+            },
+            HistoryItem {
+                timestamp: 1.into(),
+                year: 12.into(),
+                day: Day::NONE,
+                content: "Something happened.".into(),
+                properties: HistoryItemProperties::none(),
+            },
+        ])
+        .unwrap();
+    store
+        .write_relationships(vec![
+            EntityRelationship {
+                parent: "Hero".into(),
+                child: "hero's dog/cat".into(),
+                role: "owner".into(),
+                ordinal: 1.into(),
+            },
+            EntityRelationship {
+                parent: "Hero".into(),
+                child: "Village".into(),
+                role: Role::NONE,
+                ordinal: Ordinal::NONE,
+            },
+        ])
+        .unwrap();
+    store
+}
+
+fn read_tree(dir: &Path) -> BTreeMap<String, String> {
+    let mut files = BTreeMap::new();
+    for sub in ["", "entities", "history"] {
+        for entry in fs::read_dir(dir.join(sub)).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_file() {
+                let name = path.strip_prefix(dir).unwrap().display().to_string();
+                files.insert(name, fs::read_to_string(&path).unwrap());
+            }
+        }
+    }
+    files
+}
+
+#[test]
+fn directory_round_trip_is_lossless() {
+    let dir = tempdir().unwrap();
+    let original = example_store();
+    export_directory(&original, dir.path()).unwrap();
+
+    let copy = MemoryDatabase::new();
+    import_directory(&copy, dir.path()).unwrap();
+    assert_eq!(export_json(&copy).unwrap(), export_json(&original).unwrap());
+}
+
+#[test]
+fn null_properties_are_rejected() {
+    let dir = tempdir().unwrap();
+    let store = MemoryDatabase::new();
+    store
+        .write_history_items(vec![HistoryItem {
+            timestamp: 1.into(),
+            year: 12.into(),
+            day: Day::NONE,
+            content: "Something happened.".into(),
+            properties: "{\"witness\":null}".into(),
+        }])
+        .unwrap();
+    let result = export_directory(&store, dir.path());
+    assert!(matches!(result, Err(LoreCoreError::InputError(_))));
+    assert!(!dir.path().join("lore.toml").exists());
+}
+
+#[test]
+fn regenerating_produces_identical_files() {
+    let dir = tempdir().unwrap();
+    export_directory(&example_store(), dir.path()).unwrap();
+    let first = read_tree(dir.path());
+    assert!(first.contains_key("entities/hero's dog%2Fcat.toml"));
+    assert!(first.contains_key("entities/%48ero.toml"));
+    assert!(first.contains_key("entities/hero.toml"));
+    assert!(first.contains_key("entities/%.toml"));
+    assert!(first.contains_key("history/-5.toml"));
+
+    let copy = MemoryDatabase::new();
+    import_directory(&copy, dir.path()).unwrap();
+    export_directory(&copy, dir.path()).unwrap();
+    assert_eq!(read_tree(dir.path()), first);
+}
+
+#[test]
+fn files_of_removed_labels_and_years_are_deleted() {
+    let dir = tempdir().unwrap();
+    let store = example_store();
+    export_directory(&store, dir.path()).unwrap();
+    fs::write(dir.path().join("entities/notes.txt"), "keep me").unwrap();
+
+    store.delete_entity("Hero".into()).unwrap();
+    store.delete_history_item(1.into()).unwrap();
+    export_directory(&store, dir.path()).unwrap();
+    let files = read_tree(dir.path());
+    assert!(!files.contains_key("entities/%48ero.toml"));
+    assert!(!files.contains_key("history/12.toml"));
+    assert!(files.contains_key("entities/notes.txt"));
+}
+
+#[test]
+fn hand_written_files_are_compiled() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("entities")).unwrap();
+    fs::create_dir_all(dir.path().join("history")).unwrap();
+    fs::write(dir.path().join("lore.toml"), "version = 1\n").unwrap();
+    fs::write(
+        dir.path().join("entities/%48ero.toml"),
+        "Age = \"Young\"\nBiography = \"\"\"\nBorn somewhere.\n\"\"\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("history/3.toml"),
+        "[[item]]\ntimestamp = 7\nday = 1\ncontent = \"\\\\entityref{Hero} left.\"\n",
+    )
+    .unwrap();
+
+    let store = MemoryDatabase::new();
+    import_directory(&store, dir.path()).unwrap();
+    let hero = store.read_entity(&"Hero".into()).unwrap().unwrap();
+    assert_eq!(
+        hero.descriptors,
+        vec![
+            ("Age".into(), "Young".into()),
+            ("Biography".into(), "Born somewhere.\n".into())
+        ]
+    );
+    assert_eq!(hero.history.len(), 1);
+    assert_eq!(hero.history[0].year, 3.into());
+}
+
+#[test]
+fn unescaped_file_names_are_rejected() {
+    let dir = tempdir().unwrap();
+    export_directory(&example_store(), dir.path()).unwrap();
+    fs::write(dir.path().join("entities/Villain.toml"), "Age = \"Old\"\n").unwrap();
+
+    let store = MemoryDatabase::new();
+    assert!(import_directory(&store, dir.path()).is_err());
+    assert!(store.read_distinct_labels().unwrap().is_empty());
+}
+
+#[test]
+fn unsupported_versions_are_rejected() {
+    let dir = tempdir().unwrap();
+    export_directory(&example_store(), dir.path()).unwrap();
+    fs::write(dir.path().join("lore.toml"), "version = 2\n").unwrap();
+    assert!(import_directory(&MemoryDatabase::new(), dir.path()).is_err());
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn directory_can_be_compiled_into_sqlite() {
+    use lorecore::sql::lore_database::LoreDatabase;
+    use tempfile::NamedTempFile;
+
+    let dir = tempdir().unwrap();
+    let original = example_store();
+    export_directory(&original, dir.path()).unwrap();
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    import_directory(&db, dir.path()).unwrap();
+    assert_eq!(export_json(&db).unwrap(), export_json(&original).unwrap());
+    temp_path.close().unwrap();
+}
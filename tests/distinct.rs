@@ -0,0 +1,127 @@
+#![cfg(feature = "sqlite")]
+
+use lorecore::extractions::{extract_days, extract_descriptors, extract_labels, extract_years};
+use lorecore::sql::lore_database::LoreDatabase;
+use lorecore::sql::relationship::{extract_children, extract_parents, extract_roles};
+use lorecore::sql::search_params::{
+    EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams,
+};
+use lorecore::types::*;
+use std::path::PathBuf;
+use tempfile::NamedTempFile;
+
+fn create_example() -> (tempfile::TempPath, LoreDatabase) {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let path_in: PathBuf = temp_path.as_os_str().into();
+    let db = LoreDatabase::open(path_in.clone()).unwrap();
+
+    let mut cols = Vec::new();
+    for label in ["zeta", "Alpha", "_beta"] {
+        for descriptor in ["name", "Age", "_secret"] {
+            cols.push(EntityColumn {
+                label: label.into(),
+                descriptor: descriptor.into(),
+                description: Description::NONE,
+            });
+        }
+    }
+    db.write_entity_columns(cols).unwrap();
+
+    let mut items = Vec::new();
+    let mut timestamp = 0;
+    for year in [2021, -13, 0, 2021] {
+        for day in [Day::NONE, 12.into(), 3.into()] {
+            timestamp += 1;
+            items.push(HistoryItem {
+                timestamp: timestamp.into(),
+                year: year.into(),
+                day,
+                content: "testcontent".into(),
+                properties: HistoryItemProperties::none(),
+            });
+        }
+    }
+    db.write_history_items(items).unwrap();
+
+    let rels = [
+        ("b", "c", ""),
+        ("a", "b", "daughter"),
+        ("a", "c", "_secret"),
+        ("B", "a", "daughter"),
+    ]
+    .iter()
+    .map(|(parent, child, role)| EntityRelationship {
+        parent: (*parent).into(),
+        child: (*child).into(),
+        role: (*role).into(),
+        ordinal: Ordinal::NONE,
+    })
+    .collect();
+    db.write_relationships(rels).unwrap();
+
+    (temp_path, db)
+}
+
+#[test]
+fn distinct_entity_values_match_extractions() {
+    let (temp_path, db) = create_example();
+    let cols = db
+        .read_entity_columns(EntityColumnSearchParams::empty())
+        .unwrap();
+    assert_eq!(db.read_distinct_labels().unwrap(), extract_labels(&cols));
+    assert_eq!(
+        db.read_distinct_descriptors().unwrap(),
+        extract_descriptors(&cols)
+    );
+    assert_eq!(db.read_distinct_labels().unwrap().len(), 3);
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn distinct_history_values_match_extractions() {
+    let (temp_path, db) = create_example();
+    let items = db
+        .read_history_items(HistoryItemSearchParams::empty())
+        .unwrap();
+    assert_eq!(db.read_distinct_years().unwrap(), extract_years(&items));
+    assert_eq!(db.read_distinct_days().unwrap(), extract_days(&items));
+    assert_eq!(
+        db.read_distinct_years().unwrap(),
+        vec![(-13).into(), 0.into(), 2021.into()]
+    );
+    assert_eq!(
+        db.read_distinct_days().unwrap(),
+        vec![Day::NONE, 3.into(), 12.into()]
+    );
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn distinct_relationship_values_match_extractions() {
+    let (temp_path, db) = create_example();
+    let rels = db
+        .read_relationships(RelationshipSearchParams::empty())
+        .unwrap();
+    assert_eq!(db.read_distinct_parents().unwrap(), extract_parents(&rels));
+    assert_eq!(
+        db.read_distinct_children().unwrap(),
+        extract_children(&rels)
+    );
+    assert_eq!(db.read_distinct_roles().unwrap(), extract_roles(&rels));
+    assert_eq!(
+        db.read_distinct_roles().unwrap(),
+        vec!["_secret".into(), "daughter".into()]
+    );
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn distinct_values_of_empty_database_are_empty() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let path_in: PathBuf = temp_path.as_os_str().into();
+    let db = LoreDatabase::open(path_in.clone()).unwrap();
+    assert!(db.read_distinct_labels().unwrap().is_empty());
+    assert!(db.read_distinct_years().unwrap().is_empty());
+    assert!(db.read_distinct_roles().unwrap().is_empty());
+    temp_path.close().unwrap();
+}
@@ -1,8 +1,11 @@
+#![cfg(feature = "sqlite")]
+
 use lorecore::{
     sql::{
         lore_database::LoreDatabase,
         search_params::{EntityColumnSearchParams, SqlSearchText},
     },
+    store::LoreStore,
     types::*,
 };
 use std::path::PathBuf;
@@ -33,8 +36,8 @@ fn write_many_entity_columns() {
     let path_in: PathBuf = temp_path.as_os_str().into();
     let db = LoreDatabase::open(path_in.clone()).unwrap();
 
-    let labels = vec!["testlabel1".to_string(), "testlabel2".to_string()];
-    let descriptors = vec!["testdescriptor1".to_string(), "testdescriptor2".to_string()];
+    let labels = ["testlabel1".to_string(), "testlabel2".to_string()];
+    let descriptors = ["testdescriptor1".to_string(), "testdescriptor2".to_string()];
     let mut entities: Vec<EntityColumn> = Vec::new();
     for label in labels.iter() {
         for descriptor in descriptors.iter() {
@@ -82,8 +85,8 @@ fn create_example() -> (tempfile::TempPath, LoreDatabase, Vec<EntityColumn>) {
     let path_in: PathBuf = temp_path.as_os_str().into();
     let db = LoreDatabase::open(path_in.clone()).unwrap();
 
-    let labels = vec!["testlabel1".to_string(), "testlabel2and_stuff".to_string()];
-    let descriptors = vec![
+    let labels = ["testlabel1".to_string(), "testlabel2and_stuff".to_string()];
+    let descriptors = [
         "testdescriptor1".to_string(),
         "testdescriptor2and_stuff".to_string(),
     ];
@@ -500,7 +503,7 @@ fn test_relabel_entity() {
     // Read the entity back from the database
     let updated_entity = db
         .read_entity_columns(EntityColumnSearchParams::new(
-            Some(SqlSearchText::exact(&new_label.to_str())),
+            Some(SqlSearchText::exact(new_label.to_str())),
             None,
         ))
         .unwrap();
@@ -522,7 +525,7 @@ fn test_delete_entity() {
     // Verify the entity exists
     let entity_out = db
         .read_entity_columns(EntityColumnSearchParams::new(
-            Some(SqlSearchText::exact(&entity.label.to_str())),
+            Some(SqlSearchText::exact(entity.label.to_str())),
             None,
         ))
         .unwrap();
@@ -535,7 +538,7 @@ fn test_delete_entity() {
     // Verify the entity no longer exists
     let entity_out = db
         .read_entity_columns(EntityColumnSearchParams::new(
-            Some(SqlSearchText::exact(&entity.label.to_str())),
+            Some(SqlSearchText::exact(entity.label.to_str())),
             None,
         ))
         .unwrap();
@@ -557,7 +560,7 @@ fn test_change_entity_descriptor() {
     // Read the entity back from the database
     let updated_entity = db
         .read_entity_columns(EntityColumnSearchParams::new(
-            Some(SqlSearchText::exact(&old_entity.label.to_str())),
+            Some(SqlSearchText::exact(old_entity.label.to_str())),
             None,
         ))
         .unwrap();
@@ -579,7 +582,7 @@ fn test_delete_entity_column() {
     // Verify the entity exists
     let entity_out = db
         .read_entity_columns(EntityColumnSearchParams::new(
-            Some(SqlSearchText::exact(&entity.label.to_str())),
+            Some(SqlSearchText::exact(entity.label.to_str())),
             None,
         ))
         .unwrap();
@@ -593,8 +596,8 @@ fn test_delete_entity_column() {
     // Verify the entity column no longer exists
     let entity_out = db
         .read_entity_columns(EntityColumnSearchParams::new(
-            Some(SqlSearchText::exact(&entity.label.to_str())),
-            Some(SqlSearchText::exact(&entity.descriptor.to_str())),
+            Some(SqlSearchText::exact(entity.label.to_str())),
+            Some(SqlSearchText::exact(entity.descriptor.to_str())),
         ))
         .unwrap();
     assert!(entity_out.is_empty());
@@ -618,7 +621,7 @@ fn test_change_entity_description() {
     // Read the entity back from the database
     let updated_entity = db
         .read_entity_columns(EntityColumnSearchParams::new(
-            Some(SqlSearchText::exact(&old_entity.label.to_str())),
+            Some(SqlSearchText::exact(old_entity.label.to_str())),
             None,
         ))
         .unwrap();
@@ -648,7 +651,7 @@ fn test_change_entity_description_to_none() {
     // Read the entity back from the database
     let updated_entity = db
         .read_entity_columns(EntityColumnSearchParams::new(
-            Some(SqlSearchText::exact(&old_entity.label.to_str())),
+            Some(SqlSearchText::exact(old_entity.label.to_str())),
             None,
         ))
         .unwrap();
@@ -661,3 +664,63 @@ fn test_change_entity_description_to_none() {
 
     temp_path.close().unwrap();
 }
+
+#[test]
+fn read_entity_collects_descriptors_relationships_and_history() {
+    let (temp_path, db, entities) = create_example();
+    let label: Label = "testlabel1".into();
+    let rel = EntityRelationship {
+        parent: "testparent".into(),
+        child: label.clone().into(),
+        role: Role::NONE,
+        ordinal: Ordinal::NONE,
+    };
+    db.write_relationships(vec![rel.clone()]).unwrap();
+    let item = HistoryItem {
+        timestamp: 1.into(),
+        year: 2021.into(),
+        day: Day::NONE,
+        content: "\\entityref{testlabel1} was founded.".into(),
+        properties: HistoryItemProperties::none(),
+    };
+    db.write_history_items(vec![item.clone()]).unwrap();
+
+    let entity = db.read_entity(&label).unwrap().unwrap();
+    let expected_descriptors: Vec<_> = entities
+        .iter()
+        .filter(|col| col.label == label)
+        .map(|col| (col.descriptor.clone(), col.description.clone()))
+        .collect();
+    assert_eq!(entity.label, label);
+    assert_eq!(entity.descriptors, expected_descriptors);
+    assert_eq!(entity.parents, vec![rel]);
+    assert!(entity.children.is_empty());
+    assert_eq!(entity.history, vec![item]);
+
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn read_non_existing_entity_returns_none() {
+    let (temp_path, db, _) = create_example();
+    let entity = db.read_entity(&"fununu".into()).unwrap();
+    assert!(entity.is_none());
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn read_entities_returns_all_descriptors_of_matching_labels() {
+    let (temp_path, db, entities) = create_example();
+
+    let out = db
+        .read_entities(EntityColumnSearchParams::new(
+            None,
+            Some(SqlSearchText::exact("testdescriptor1")),
+        ))
+        .unwrap();
+    assert_eq!(out.len(), 2);
+    let num_descriptors: usize = out.iter().map(|e| e.descriptors.len()).sum();
+    assert_eq!(num_descriptors, entities.len());
+
+    temp_path.close().unwrap();
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "sqlite")]
+
+use lorecore::{
+    genealogy::GenealogyRoles, sql::lore_database::LoreDatabase, store::LoreStore, types::*,
+};
+use std::path::PathBuf;
+use tempfile::NamedTempFile;
+
+#[test]
+fn family_tree_is_built_from_database() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let path_in: PathBuf = temp_path.as_os_str().into();
+    let db = LoreDatabase::open(path_in.clone()).unwrap();
+    let rels = vec![
+        EntityRelationship {
+            parent: "queen".into(),
+            child: "prince".into(),
+            role: "mother".into(),
+            ordinal: Ordinal::NONE,
+        },
+        EntityRelationship {
+            parent: "queen".into(),
+            child: "princess".into(),
+            role: "mother".into(),
+            ordinal: Ordinal::NONE,
+        },
+        EntityRelationship {
+            parent: "castle".into(),
+            child: "queen".into(),
+            role: "resident".into(),
+            ordinal: Ordinal::NONE,
+        },
+    ];
+    db.write_relationships(rels).unwrap();
+
+    let roles = GenealogyRoles::new(vec!["mother".into()], vec!["spouse".into()]);
+    let tree = db.read_family_tree(&roles).unwrap();
+    assert_eq!(tree.siblings(&"prince".into()), vec!["princess".into()]);
+    assert!(tree.parents(&"queen".into()).is_empty());
+    assert_eq!(
+        tree.persons(),
+        vec!["prince".into(), "princess".into(), "queen".into()]
+    );
+
+    temp_path.close().unwrap();
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "sqlite")]
+
+use lorecore::{
+    graph::GraphExportParams, sql::lore_database::LoreDatabase, store::LoreStore, types::*,
+};
+use std::path::PathBuf;
+use tempfile::NamedTempFile;
+
+fn create_example() -> (tempfile::TempPath, LoreDatabase) {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let path_in: PathBuf = temp_path.as_os_str().into();
+    let db = LoreDatabase::open(path_in.clone()).unwrap();
+
+    let rels = vec![
+        EntityRelationship {
+            parent: "kingdom".into(),
+            child: "city".into(),
+            role: "capital".into(),
+            ordinal: Ordinal::NONE,
+        },
+        EntityRelationship {
+            parent: "city".into(),
+            child: "tavern".into(),
+            role: Role::NONE,
+            ordinal: Ordinal::NONE,
+        },
+        EntityRelationship {
+            parent: "tavern".into(),
+            child: "cellar".into(),
+            role: Role::NONE,
+            ordinal: Ordinal::NONE,
+        },
+    ];
+    db.write_relationships(rels).unwrap();
+    let cols = vec![EntityColumn {
+        label: "city".into(),
+        descriptor: "name".into(),
+        description: "Big City".into(),
+    }];
+    db.write_entity_columns(cols).unwrap();
+    (temp_path, db)
+}
+
+#[test]
+fn graph_of_whole_database_contains_all_relationships() {
+    let (temp_path, db) = create_example();
+
+    let graph = db
+        .read_relationship_graph(GraphExportParams::empty())
+        .unwrap();
+    assert_eq!(graph.nodes.len(), 4);
+    assert_eq!(graph.edges.len(), 3);
+    assert!(graph.nodes.iter().all(|node| node.attributes.is_empty()));
+
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn graph_around_label_contains_descriptors() {
+    let (temp_path, db) = create_example();
+
+    let graph = db
+        .read_relationship_graph(GraphExportParams::new(
+            Some(("kingdom".into(), 1)),
+            vec!["name".into()],
+        ))
+        .unwrap();
+    let dot = graph.to_dot();
+    assert!(dot.contains("\"city\" [\"name\"=\"Big City\"];"));
+    assert!(dot.contains("\"kingdom\" -> \"city\" [label=\"capital\"];"));
+    assert!(!dot.contains("tavern"));
+
+    let graphml = graph.to_graphml();
+    assert!(graphml.contains("<data key=\"d0\">Big City</data>"));
+    assert!(!graphml.contains("tavern"));
+
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn graph_around_unknown_label_is_an_error() {
+    let (temp_path, db) = create_example();
+
+    let result = db.read_relationship_graph(GraphExportParams::new(
+        Some(("fununu".into(), 2)),
+        Vec::new(),
+    ));
+    assert!(result.is_err());
+
+    temp_path.close().unwrap();
+}
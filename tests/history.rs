@@ -1,3 +1,5 @@
+#![cfg(feature = "sqlite")]
+
 use lorecore::sql::lore_database::LoreDatabase;
 use lorecore::sql::search_params::{HistoryItemSearchParams, SqlSearchText};
 use lorecore::timestamp::current_timestamp;
@@ -31,10 +33,10 @@ fn create_example() -> (tempfile::TempPath, LoreDatabase, Vec<HistoryItem>) {
     let path_in: PathBuf = temp_path.as_os_str().into();
     let db = LoreDatabase::open(path_in.clone()).unwrap();
 
-    let years: Vec<Year> = vec![(-13 as i32).into(), 0.into(), 2021.into()];
+    let years: Vec<Year> = vec![(-13_i32).into(), 0.into(), 2021.into()];
     let days: Vec<Day> = vec![1.into(), Day::NONE];
-    let contents = vec!["testcontent1".to_string(), "testcontent2".to_string()];
-    let properties = vec![Some("{\"is_secret\": true}".to_string()), None];
+    let contents = ["testcontent1".to_string(), "testcontent2".to_string()];
+    let properties = [Some("{\"is_secret\": true}".to_string()), None];
     let mut items: Vec<HistoryItem> = Vec::new();
     for year in years.iter() {
         for day in days.iter() {
@@ -42,7 +44,7 @@ fn create_example() -> (tempfile::TempPath, LoreDatabase, Vec<HistoryItem>) {
                 for property in properties.iter() {
                     items.push(HistoryItem {
                         year: *year,
-                        day: day.clone(),
+                        day: *day,
                         timestamp: current_timestamp(),
                         content: content.as_str().into(),
                         properties: (&property.clone().unwrap_or_default()).into(),
@@ -225,7 +227,7 @@ fn search_for_non_existing_year() {
     let items_out = db
         .read_history_items(HistoryItemSearchParams::new(Some(year), None, None, None))
         .unwrap();
-    assert!(items_out.len() == 0);
+    assert!(items_out.is_empty());
 
     temp_path.close().unwrap();
 }
@@ -238,7 +240,7 @@ fn search_for_non_existing_day() {
     let items_out = db
         .read_history_items(HistoryItemSearchParams::new(None, day, None, None))
         .unwrap();
-    assert!(items_out.len() == 0);
+    assert!(items_out.is_empty());
 
     temp_path.close().unwrap();
 }
@@ -256,7 +258,7 @@ fn search_for_non_existing_timestamp() {
             None,
         ))
         .unwrap();
-    assert!(items_out.len() == 0);
+    assert!(items_out.is_empty());
 
     temp_path.close().unwrap();
 }
@@ -275,7 +277,7 @@ fn search_for_non_existing_content() {
             Some(content_search),
         ))
         .unwrap();
-    assert!(items_out.len() == 0);
+    assert!(items_out.is_empty());
 
     temp_path.close().unwrap();
 }
@@ -0,0 +1,177 @@
+#![cfg(feature = "sqlite")]
+
+use lorecore::{
+    sql::{
+        change_log::{ChangeLogSearchParams, JournalState},
+        lore_database::LoreDatabase,
+        search_params::{
+            EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams,
+        },
+    },
+    types::*,
+};
+use tempfile::NamedTempFile;
+
+fn column(label: &str, descriptor: &str, description: &str) -> EntityColumn {
+    EntityColumn {
+        label: label.into(),
+        descriptor: descriptor.into(),
+        description: description.into(),
+    }
+}
+
+fn columns(db: &LoreDatabase) -> Vec<EntityColumn> {
+    db.read_entity_columns(EntityColumnSearchParams::empty())
+        .unwrap()
+}
+
+#[test]
+fn multi_row_operations_are_undone_and_redone_as_a_whole() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    let original = vec![
+        column("Hero", "Age", "12"),
+        column("Hero", "Home", "Village"),
+    ];
+    db.write_entity_columns(original.clone()).unwrap();
+    db.relabel_entity(&"Hero".into(), &"Heroine".into())
+        .unwrap();
+    db.change_entity_description((&"Heroine".into(), &"Age".into()), &"13".into())
+        .unwrap();
+    let edited = columns(&db);
+
+    let undone = db.undo(2).unwrap();
+    assert_eq!(undone.len(), 2);
+    assert!(undone[0].id > undone[1].id);
+    assert_eq!(columns(&db), original);
+
+    let redone = db.redo(5).unwrap();
+    assert_eq!(redone.len(), 2);
+    assert!(redone[0].id < redone[1].id);
+    assert_eq!(columns(&db), edited);
+    assert!(db.redo(1).unwrap().is_empty());
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn history_items_and_relationships_are_restored() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    let item = HistoryItem {
+        timestamp: 1.into(),
+        year: 10.into(),
+        day: 3.into(),
+        content: "Born.".into(),
+        properties: "{\"is_secret\":true}".into(),
+    };
+    db.write_history_items(vec![item.clone()]).unwrap();
+    let rels: Vec<EntityRelationship> = ["Cat", "Dog"]
+        .iter()
+        .map(|child| EntityRelationship {
+            parent: "Hero".into(),
+            child: (*child).into(),
+            role: "owner".into(),
+            ordinal: Ordinal::NONE,
+        })
+        .collect();
+    db.write_relationships(rels.clone()).unwrap();
+    db.redate_history_item(1.into(), 11.into(), Day::NONE)
+        .unwrap();
+    db.delete_history_item(1.into()).unwrap();
+    db.reorder_children(&"Hero".into(), &["Dog".into(), "Cat".into()])
+        .unwrap();
+    db.reparent(&"Dog".into(), &"owner".into(), &"Villain".into(), true)
+        .unwrap();
+
+    assert_eq!(db.undo(4).unwrap().len(), 4);
+    assert_eq!(
+        db.read_history_items(HistoryItemSearchParams::empty())
+            .unwrap(),
+        vec![item]
+    );
+    assert_eq!(
+        db.read_relationships(RelationshipSearchParams::empty())
+            .unwrap(),
+        rels
+    );
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn new_changes_discard_what_could_be_redone() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    db.write_entity_columns(vec![column("Hero", "Age", "12")])
+        .unwrap();
+    db.write_entity_columns(vec![column("Dog", "Age", "3")])
+        .unwrap();
+    db.undo(1).unwrap();
+    db.write_entity_columns(vec![column("Cat", "Age", "5")])
+        .unwrap();
+    assert!(db.redo(1).unwrap().is_empty());
+
+    let states: Vec<_> = db
+        .read_change_log(ChangeLogSearchParams::empty())
+        .unwrap()
+        .iter()
+        .map(|entry| entry.state)
+        .collect();
+    assert_eq!(
+        states,
+        vec![
+            JournalState::Applied,
+            JournalState::Discarded,
+            JournalState::Applied
+        ]
+    );
+
+    // Undo skips the discarded change.
+    db.undo(2).unwrap();
+    assert!(columns(&db).is_empty());
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn undo_survives_reopening_the_database() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    {
+        let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+        db.write_entity_columns(vec![column("Hero", "Age", "12")])
+            .unwrap();
+        db.delete_entity("Hero".into()).unwrap();
+    }
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    db.undo(1).unwrap();
+    assert_eq!(columns(&db), vec![column("Hero", "Age", "12")]);
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn failing_undo_changes_nothing() {
+    use diesel::{Connection, RunQueryDsl, SqliteConnection};
+
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    db.write_entity_columns(vec![column("Hero", "Age", "12")])
+        .unwrap();
+    db.delete_entity("Hero".into()).unwrap();
+    db.write_entity_columns(vec![column("Dog", "Age", "3")])
+        .unwrap();
+    // A row written around the journal blocks restoring the deleted entity.
+    let mut connection = SqliteConnection::establish(temp_path.to_str().unwrap()).unwrap();
+    diesel::sql_query(
+        "INSERT INTO entities (label, descriptor, description) VALUES ('Hero', 'Age', '99')",
+    )
+    .execute(&mut connection)
+    .unwrap();
+    let log_before = db.read_change_log(ChangeLogSearchParams::empty()).unwrap();
+    let columns_before = columns(&db);
+
+    assert!(db.undo(2).is_err());
+    assert_eq!(columns(&db), columns_before);
+    assert_eq!(
+        db.read_change_log(ChangeLogSearchParams::empty()).unwrap(),
+        log_before
+    );
+    temp_path.close().unwrap();
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "sqlite")]
+
+use lorecore::{
+    json::{export_json, import_json},
+    memory::MemoryDatabase,
+    sql::lore_database::LoreDatabase,
+    types::*,
+};
+use tempfile::NamedTempFile;
+
+#[test]
+fn sqlite_export_can_be_imported_into_memory_and_back() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    db.write_entity_columns(vec![EntityColumn {
+        label: "testlabel".into(),
+        descriptor: "testdescriptor".into(),
+        description: "testdescription".into(),
+    }])
+    .unwrap();
+    db.write_history_items(vec![HistoryItem {
+        timestamp: 1.into(),
+        year: 2020.into(),
+        day: 5.into(),
+        content: "testcontent".into(),
+        properties: "{\"is_secret\":false}".into(),
+    }])
+    .unwrap();
+    db.write_relationships(vec![EntityRelationship {
+        parent: "testlabel".into(),
+        child: "otherlabel".into(),
+        role: Role::NONE,
+        ordinal: 1.into(),
+    }])
+    .unwrap();
+    let json = export_json(&db).unwrap();
+
+    let memory = MemoryDatabase::new();
+    import_json(&memory, &json).unwrap();
+    assert_eq!(export_json(&memory).unwrap(), json);
+
+    let other_path = NamedTempFile::new().unwrap().into_temp_path();
+    let other = LoreDatabase::open(other_path.as_os_str().into()).unwrap();
+    import_json(&other, &export_json(&memory).unwrap()).unwrap();
+    assert_eq!(export_json(&other).unwrap(), json);
+
+    temp_path.close().unwrap();
+    other_path.close().unwrap();
+}
+
+#[test]
+fn invalid_json_is_an_input_error() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    let result = import_json(&db, "{\"version\": 1}");
+    assert!(matches!(
+        result,
+        Err(lorecore::errors::LoreCoreError::InputError(_))
+    ));
+    temp_path.close().unwrap();
+}
@@ -0,0 +1,56 @@
+use lorecore::{
+    latex::{LatexExportParams, LatexHeading, export_latex},
+    memory::MemoryDatabase,
+    sql::search_params::{EntityColumnSearchParams, SqlSearchText},
+    store::LoreStore,
+    types::*,
+};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test as test;
+
+#[test]
+fn only_exported_entities_are_hyperlinked() {
+    let store = MemoryDatabase::new();
+    store
+        .write_entity_columns(vec![
+            EntityColumn {
+                label: "Anna".into(),
+                descriptor: "Friends".into(),
+                description: "\\entityref{Bert} and \\entityref{_Clara}".into(),
+            },
+            EntityColumn {
+                label: "Bert".into(),
+                descriptor: "Friends".into(),
+                description: "\\entityref{Anna}".into(),
+            },
+            EntityColumn {
+                label: "_Clara".into(),
+                descriptor: "Friends".into(),
+                description: "Nobody".into(),
+            },
+        ])
+        .unwrap();
+    store
+        .write_relationships(vec![EntityRelationship {
+            parent: "Anna".into(),
+            child: "Bert".into(),
+            role: "sister".into(),
+            ordinal: Ordinal::NONE,
+        }])
+        .unwrap();
+
+    let latex = export_latex(&store, LatexExportParams::empty()).unwrap();
+    assert!(latex.contains("\\section{\\_Clara}\\label{lore:-5FClara}"));
+    assert!(latex.contains("\\hyperref[lore:Bert]{Bert} and \\hyperref[lore:-5FClara]{\\_Clara}"));
+    assert!(latex.contains("\\item Child of \\hyperref[lore:Anna]{Anna} (sister)"));
+
+    let params = LatexExportParams::new(
+        EntityColumnSearchParams::new(Some(SqlSearchText::exact("Anna")), None),
+        LatexHeading::Subsection,
+    );
+    let latex = export_latex(&store, params).unwrap();
+    assert!(latex.contains("\\subsection{Anna}"));
+    assert!(latex.contains("\\subsubsection{Friends}\nBert and \\_Clara\n"));
+    assert!(latex.contains("\\item Parent of Bert (sister)"));
+    assert!(!latex.contains("\\subsection{Bert}"));
+}
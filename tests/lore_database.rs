@@ -1,4 +1,9 @@
-use lorecore::sql::lore_database::LoreDatabase;
+#![cfg(feature = "sqlite")]
+
+use lorecore::{
+    sql::{lore_database::LoreDatabase, search_params::EntityColumnSearchParams},
+    types::*,
+};
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
 
@@ -8,6 +13,56 @@ fn path_as_string() {
     let path_in: PathBuf = temp_path.as_os_str().into();
     let db = LoreDatabase::open(path_in.clone()).unwrap();
     let path_out = db.path_as_string();
-    assert!(path_in == PathBuf::from(path_out));
+    assert!(path_in == path_out);
+    temp_path.close().unwrap();
+}
+
+fn example_column() -> EntityColumn {
+    EntityColumn {
+        label: "testlabel".into(),
+        descriptor: "testdescriptor".into(),
+        description: "testdescription".into(),
+    }
+}
+
+#[test]
+fn rolled_back_transaction_leaves_no_trace() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let path_in: PathBuf = temp_path.as_os_str().into();
+    let db = LoreDatabase::open(path_in.clone()).unwrap();
+
+    db.begin_transaction().unwrap();
+    db.write_entity_columns(vec![example_column()]).unwrap();
+    let cols = db
+        .read_entity_columns(EntityColumnSearchParams::empty())
+        .unwrap();
+    assert_eq!(cols.len(), 1);
+    db.rollback_transaction().unwrap();
+
+    let cols = db
+        .read_entity_columns(EntityColumnSearchParams::empty())
+        .unwrap();
+    assert!(cols.is_empty());
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn committed_transaction_is_persisted() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let path_in: PathBuf = temp_path.as_os_str().into();
+    let db = LoreDatabase::open(path_in.clone()).unwrap();
+
+    db.begin_transaction().unwrap();
+    db.write_entity_columns(vec![example_column()]).unwrap();
+    db.begin_transaction().unwrap();
+    db.delete_entity(example_column().label).unwrap();
+    db.rollback_transaction().unwrap();
+    db.commit_transaction().unwrap();
+
+    let other_db = LoreDatabase::open(path_in).unwrap();
+    let cols = other_db
+        .read_entity_columns(EntityColumnSearchParams::empty())
+        .unwrap();
+    assert_eq!(cols, vec![example_column()]);
     temp_path.close().unwrap();
 }
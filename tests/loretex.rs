@@ -0,0 +1,66 @@
+// Importing assigns timestamps from the system clock, which wasm32-unknown-unknown lacks.
+#![cfg(not(target_arch = "wasm32"))]
+
+use lorecore::{
+    loretex::import_loretex, memory::MemoryDatabase, sql::search_params::HistoryItemSearchParams,
+    store::LoreStore,
+};
+
+const CAMPAIGN: &str = r"\documentclass{article}
+\usepackage{loretex}
+\begin{document}
+
+\newentity{Kingdom}
+\setdescriptor{Ruler}{\entityref{Queen}}
+
+\newentity{Queen}
+\setdescriptor{Age}{54}
+\addparent[ruler]{Kingdom}
+\addhistory[12]{-30}{\entityref{Queen} is crowned.}
+\addhistory{-31}{The old king dies.}
+\addhistory{next year}{Unknown future.}
+\npc{Guard}
+
+\end{document}
+";
+
+#[test]
+fn campaign_is_imported_with_report() {
+    let store = MemoryDatabase::new();
+    let unparsed = import_loretex(&store, CAMPAIGN).unwrap();
+    let lines: Vec<usize> = unparsed.iter().map(|u| u.line).collect();
+    assert_eq!(lines, vec![13, 14]);
+    assert_eq!(unparsed[0].reason, "Unable to parse \"next year\" as year");
+    assert_eq!(unparsed[1].reason, "Unknown macro");
+
+    let queen = store.read_entity(&"Queen".into()).unwrap().unwrap();
+    assert_eq!(queen.descriptors, vec![("Age".into(), "54".into())]);
+    assert_eq!(queen.parents.len(), 1);
+    assert_eq!(queen.history.len(), 2);
+    let kingdom = store.read_entity(&"Kingdom".into()).unwrap().unwrap();
+    assert_eq!(kingdom.children, queen.parents);
+    assert_eq!(
+        store
+            .read_history_items(HistoryItemSearchParams::empty())
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn repeated_parents_are_reported() {
+    let source = r"\newentity{Queen}
+\setdescriptor{Age}{54}
+\addparent[ruler]{Kingdom}
+\addparent{Kingdom}
+\addparent[ruler]{Kingdom}
+";
+    let store = MemoryDatabase::new();
+    let unparsed = import_loretex(&store, source).unwrap();
+    assert_eq!(unparsed.len(), 1);
+    assert_eq!(unparsed[0].line, 5);
+
+    let queen = store.read_entity(&"Queen".into()).unwrap().unwrap();
+    assert_eq!(queen.parents.len(), 2);
+}
@@ -0,0 +1,163 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::fs;
+
+use lorecore::{markdown::export_markdown, memory::MemoryDatabase, store::LoreStore, types::*};
+use tempfile::tempdir;
+
+fn example_store() -> MemoryDatabase {
+    let store = MemoryDatabase::new();
+    store
+        .write_entity_columns(vec![
+            EntityColumn {
+                label: "Hero".into(),
+                descriptor: "Biography".into(),
+                description: "Raised by \\entityref{Old Mage}, hunted by \\entityref{_Villain}."
+                    .into(),
+            },
+            EntityColumn {
+                label: "Hero".into(),
+                descriptor: "_True name".into(),
+                description: "Kept hidden.".into(),
+            },
+            EntityColumn {
+                label: "Old Mage".into(),
+                descriptor: "Age".into(),
+                description: "Very old".into(),
+            },
+            EntityColumn {
+                label: "_Villain".into(),
+                descriptor: "Plan".into(),
+                description: "Destroy everything.".into(),
+            },
+        ])
+        .unwrap();
+    store
+        .write_history_items(vec![
+            HistoryItem {
+                timestamp: 1.into(),
+                year: 100.into(),
+                day: 4.into(),
+                content: "\\entityref{Hero} meets \\entityref{Old Mage}.".into(),
+                properties: HistoryItemProperties::none(),
+            },
+            HistoryItem {
+                timestamp: 2.into(),
+                year: 90.into(),
+                day: Day::NONE,
+                content: "\\entityref{Hero} is secretly born.".into(),
+                properties: "{\"is_secret\":true}".into(),
+            },
+            HistoryItem {
+                timestamp: 3.into(),
+                year: 80.into(),
+                day: Day::NONE,
+                content: "\\entityref{Old Mage} is born.".into(),
+                properties: HistoryItemProperties::none(),
+            },
+        ])
+        .unwrap();
+    store
+        .write_relationships(vec![
+            EntityRelationship {
+                parent: "Old Mage".into(),
+                child: "Hero".into(),
+                role: "mentor".into(),
+                ordinal: Ordinal::NONE,
+            },
+            EntityRelationship {
+                parent: "_Villain".into(),
+                child: "Hero".into(),
+                role: "enemy".into(),
+                ordinal: Ordinal::NONE,
+            },
+            EntityRelationship {
+                parent: "Hero".into(),
+                child: "Old Mage".into(),
+                role: "_traitor".into(),
+                ordinal: Ordinal::NONE,
+            },
+        ])
+        .unwrap();
+    store
+}
+
+#[test]
+fn public_pages_link_to_each_other() {
+    let dir = tempdir().unwrap();
+    export_markdown(&example_store(), dir.path()).unwrap();
+
+    let index = fs::read_to_string(dir.path().join("index.md")).unwrap();
+    assert_eq!(
+        index,
+        "# Index\n\n[History](history.md)\n\n- [Hero](entities/Hero.md)\n- [Old Mage](entities/Old-20Mage.md)\n"
+    );
+
+    let hero = fs::read_to_string(dir.path().join("entities/Hero.md")).unwrap();
+    assert_eq!(
+        hero,
+        "# Hero\n\n\
+         ## Biography\n\n\
+         Raised by [Old Mage](Old-20Mage.md), hunted by (unknown).\n\n\
+         ## Relationships\n\n\
+         - Child of [Old Mage](Old-20Mage.md) (mentor)\n\n\
+         ## History\n\n\
+         - **100, day 4**: [Hero](Hero.md) meets [Old Mage](Old-20Mage.md).\n"
+    );
+
+    let history = fs::read_to_string(dir.path().join("history.md")).unwrap();
+    assert_eq!(
+        history,
+        "# History\n\n\
+         ## Year 80\n\n\
+         - **80**: [Old Mage](entities/Old-20Mage.md) is born.\n\n\
+         ## Year 100\n\n\
+         - **100, day 4**: [Hero](entities/Hero.md) meets [Old Mage](entities/Old-20Mage.md).\n"
+    );
+}
+
+#[test]
+fn protected_entities_get_no_page() {
+    let dir = tempdir().unwrap();
+    let store = example_store();
+    export_markdown(&store, dir.path()).unwrap();
+    assert!(!dir.path().join("entities/-5FVillain.md").exists());
+
+    store
+        .relabel_entity(&"Old Mage".into(), &"_Old Mage".into())
+        .unwrap();
+    export_markdown(&store, dir.path()).unwrap();
+    assert!(!dir.path().join("entities/Old-20Mage.md").exists());
+    let pages: Vec<_> = fs::read_dir(dir.path().join("entities"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(pages, vec!["Hero.md"]);
+}
+
+#[test]
+fn protected_labels_appear_on_no_page() {
+    let dir = tempdir().unwrap();
+    let store = example_store();
+    store
+        .write_history_items(vec![HistoryItem {
+            timestamp: 4.into(),
+            year: 101.into(),
+            day: Day::NONE,
+            content: "\\entityref{_Villain} attacks \\entityref{Hero}.".into(),
+            properties: HistoryItemProperties::none(),
+        }])
+        .unwrap();
+    export_markdown(&store, dir.path()).unwrap();
+
+    let history = fs::read_to_string(dir.path().join("history.md")).unwrap();
+    assert!(history.contains("(unknown) attacks [Hero](entities/Hero.md)."));
+    let pages = fs::read_dir(dir.path().join("entities"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .chain([dir.path().join("index.md"), dir.path().join("history.md")]);
+    for page in pages {
+        let content = fs::read_to_string(&page).unwrap();
+        assert!(!content.contains("Villain"), "{}", page.display());
+    }
+}
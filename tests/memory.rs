@@ -0,0 +1,291 @@
+use lorecore::graph::GraphExportParams;
+use lorecore::memory::MemoryDatabase;
+use lorecore::sql::search_params::{
+    EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams, SqlSearchText,
+};
+use lorecore::store::LoreStore;
+use lorecore::types::*;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test as test;
+
+fn example_columns() -> Vec<EntityColumn> {
+    let mut cols = Vec::new();
+    for label in ["Hero", "_villain", "hero's dog"] {
+        for descriptor in ["Age", "_secret"] {
+            cols.push(EntityColumn {
+                label: label.into(),
+                descriptor: descriptor.into(),
+                description: (label.to_string() + " " + descriptor).into(),
+            });
+        }
+    }
+    cols
+}
+
+fn example_items() -> Vec<HistoryItem> {
+    let mut items = Vec::new();
+    for (i, (year, day)) in [(2020, 0), (2020, 3), (-12, 3)].into_iter().enumerate() {
+        items.push(HistoryItem {
+            timestamp: (i as i64).into(),
+            year: year.into(),
+            day: day.into(),
+            content: format!("Item {} about \\entityref{{Hero}}", i).into(),
+            properties: HistoryItemProperties::none(),
+        });
+    }
+    items
+}
+
+fn example_relationships() -> Vec<EntityRelationship> {
+    [
+        ("Hero", "hero's dog", "owner"),
+        ("_villain", "Hero", ""),
+        ("Hero", "Sidekick", "mentor"),
+    ]
+    .iter()
+    .map(|(parent, child, role)| EntityRelationship {
+        parent: (*parent).into(),
+        child: (*child).into(),
+        role: (*role).into(),
+        ordinal: Ordinal::NONE,
+    })
+    .collect()
+}
+
+fn example_database() -> MemoryDatabase {
+    let db = MemoryDatabase::new();
+    db.write_entity_columns(example_columns()).unwrap();
+    db.write_history_items(example_items()).unwrap();
+    db.write_relationships(example_relationships()).unwrap();
+    db
+}
+
+#[test]
+fn written_records_are_read_sorted() {
+    let db = example_database();
+    let mut cols = example_columns();
+    cols.sort();
+    assert_eq!(
+        db.read_entity_columns(EntityColumnSearchParams::empty())
+            .unwrap(),
+        cols
+    );
+    let mut rels = example_relationships();
+    rels.sort();
+    assert_eq!(
+        db.read_relationships(RelationshipSearchParams::empty())
+            .unwrap(),
+        rels
+    );
+    assert_eq!(
+        db.read_history_items(HistoryItemSearchParams::empty())
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn searching_entity_columns() {
+    let db = example_database();
+    let exact = EntityColumnSearchParams::new(Some(SqlSearchText::exact("Hero")), None);
+    assert_eq!(db.read_entity_columns(exact).unwrap().len(), 2);
+    let partial = EntityColumnSearchParams::new(
+        Some(SqlSearchText::partial("hero")),
+        Some(SqlSearchText::partial("AGE")),
+    );
+    let labels: Vec<Label> = db
+        .read_entity_columns(partial)
+        .unwrap()
+        .into_iter()
+        .map(|col| col.label)
+        .collect();
+    assert_eq!(labels, vec!["Hero".into(), "hero's dog".into()]);
+}
+
+#[test]
+fn searching_history_items() {
+    let db = example_database();
+    let params = HistoryItemSearchParams::new(Some(2020.into()), Some(Day::NONE), None, None);
+    let items = db.read_history_items(params).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].timestamp, 0.into());
+    let params =
+        HistoryItemSearchParams::new(None, None, None, Some(SqlSearchText::partial("item 2")));
+    assert_eq!(db.read_history_items(params).unwrap().len(), 1);
+}
+
+#[test]
+fn searching_relationships() {
+    let db = example_database();
+    let params = RelationshipSearchParams::new(Some(SqlSearchText::exact("Hero")), None);
+    assert_eq!(db.read_relationships(params).unwrap().len(), 2);
+}
+
+#[test]
+fn duplicates_are_rejected() {
+    let db = example_database();
+    assert!(db.write_entity_columns(example_columns()).is_err());
+    assert!(db.write_history_items(example_items()).is_err());
+    assert!(db.write_relationships(example_relationships()).is_err());
+}
+
+#[test]
+fn updating_and_deleting() {
+    let db = example_database();
+    db.relabel_entity(&"Hero".into(), &"Heroine".into())
+        .unwrap();
+    db.change_entity_descriptor((&"Heroine".into(), "Age".into()), &"Height".into())
+        .unwrap();
+    db.change_entity_description((&"Heroine".into(), &"Height".into()), &"tall".into())
+        .unwrap();
+    db.delete_entity_column(("Heroine".into(), "_secret".into()))
+        .unwrap();
+    let heroine = EntityColumnSearchParams::new(Some(SqlSearchText::exact("Heroine")), None);
+    assert_eq!(
+        db.read_entity_columns(heroine).unwrap(),
+        vec![EntityColumn {
+            label: "Heroine".into(),
+            descriptor: "Height".into(),
+            description: "tall".into(),
+        }]
+    );
+    db.delete_entity("Heroine".into()).unwrap();
+    assert_eq!(
+        db.read_entity_columns(EntityColumnSearchParams::empty())
+            .unwrap()
+            .len(),
+        4
+    );
+
+    db.redate_history_item(0.into(), 1999.into(), 9.into())
+        .unwrap();
+    db.change_history_item_content(0.into(), &"changed".into())
+        .unwrap();
+    db.delete_history_item(1.into()).unwrap();
+    let items = db
+        .read_history_items(HistoryItemSearchParams::empty())
+        .unwrap();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().any(|item| item.year == 1999.into()
+        && item.day == 9.into()
+        && item.content == "changed".into()));
+
+    let rel = example_relationships()[0].clone();
+    db.change_relationship_role(rel.clone(), &"friend".into())
+        .unwrap();
+    let changed = EntityRelationship {
+        role: "friend".into(),
+        ..rel
+    };
+    db.delete_relationship(changed).unwrap();
+    assert_eq!(
+        db.read_relationships(RelationshipSearchParams::empty())
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn rolled_back_transactions_leave_no_trace() {
+    let db = MemoryDatabase::new();
+    db.write_entity_columns(example_columns()[..1].to_vec())
+        .unwrap();
+    db.begin_transaction().unwrap();
+    db.write_entity_columns(example_columns()[1..2].to_vec())
+        .unwrap();
+    db.begin_transaction().unwrap();
+    db.write_entity_columns(example_columns()[2..3].to_vec())
+        .unwrap();
+    db.commit_transaction().unwrap();
+    db.rollback_transaction().unwrap();
+    assert_eq!(
+        db.read_entity_columns(EntityColumnSearchParams::empty())
+            .unwrap(),
+        example_columns()[..1].to_vec()
+    );
+    assert!(db.commit_transaction().is_err());
+    assert!(db.rollback_transaction().is_err());
+}
+
+#[test]
+fn distinct_values() {
+    let db = example_database();
+    assert_eq!(
+        db.read_distinct_labels().unwrap(),
+        vec!["Hero".into(), "_villain".into(), "hero's dog".into()]
+    );
+    assert_eq!(
+        db.read_distinct_years().unwrap(),
+        vec![(-12).into(), 2020.into()]
+    );
+    assert_eq!(
+        db.read_distinct_roles().unwrap(),
+        vec!["mentor".into(), "owner".into()]
+    );
+}
+
+#[test]
+fn building_a_graph_from_memory() {
+    let db = example_database();
+    let params = GraphExportParams::new(Some(("Sidekick".into(), 1)), Vec::new());
+    let graph = db.read_relationship_graph(params).unwrap();
+    assert_eq!(graph.nodes.len(), 2);
+    let params = GraphExportParams::new(Some(("Nobody".into(), 1)), Vec::new());
+    assert!(db.read_relationship_graph(params).is_err());
+}
+
+#[cfg(all(feature = "sqlite", not(target_arch = "wasm32")))]
+mod parity {
+    use super::*;
+    use lorecore::sql::lore_database::LoreDatabase;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn searches_agree_with_sqlite() {
+        let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+        let sqlite = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+        sqlite.write_entity_columns(example_columns()).unwrap();
+        sqlite.write_history_items(example_items()).unwrap();
+        sqlite.write_relationships(example_relationships()).unwrap();
+        let memory = example_database();
+
+        for text in ["hero", "HERO", "_v", "o*o", "'s", "", "x"] {
+            let searches = [
+                SqlSearchText::partial(text),
+                SqlSearchText::exact(text),
+                SqlSearchText::empty(),
+            ];
+            for search in searches {
+                let params = || EntityColumnSearchParams::new(Some(search.clone()), None);
+                assert_eq!(
+                    sqlite.read_entity_columns(params()).unwrap(),
+                    memory.read_entity_columns(params()).unwrap(),
+                    "search {:?}",
+                    search
+                );
+                let params = || RelationshipSearchParams::new(None, Some(search.clone()));
+                assert_eq!(
+                    sqlite.read_relationships(params()).unwrap(),
+                    memory.read_relationships(params()).unwrap(),
+                    "search {:?}",
+                    search
+                );
+                let params =
+                    || HistoryItemSearchParams::new(None, None, None, Some(search.clone()));
+                assert_eq!(
+                    sqlite.read_history_items(params()).unwrap(),
+                    memory.read_history_items(params()).unwrap(),
+                    "search {:?}",
+                    search
+                );
+            }
+        }
+        assert_eq!(
+            sqlite.read_distinct_days().unwrap(),
+            memory.read_distinct_days().unwrap()
+        );
+        temp_path.close().unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test as test;
+
+use lorecore::{
+    diff::diff_stores,
+    json::{export_json, import_json},
+    memory::MemoryDatabase,
+    merge::{MergeConflict, merge_stores},
+    sql::search_params::{
+        EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams,
+    },
+    store::LoreStore,
+    types::*,
+};
+
+fn column(label: &str, description: &str) -> EntityColumn {
+    EntityColumn {
+        label: label.into(),
+        descriptor: "Age".into(),
+        description: description.into(),
+    }
+}
+
+fn item(timestamp: i64, year: i32, content: &str) -> HistoryItem {
+    HistoryItem {
+        timestamp: timestamp.into(),
+        year: year.into(),
+        day: Day::NONE,
+        content: content.into(),
+        properties: HistoryItemProperties::none(),
+    }
+}
+
+fn base() -> MemoryDatabase {
+    let base = MemoryDatabase::new();
+    base.write_entity_columns(vec![column("Hero", "12"), column("Villain", "40")])
+        .unwrap();
+    base.write_history_items(vec![item(1, 10, "Born."), item(2, 20, "Lost.")])
+        .unwrap();
+    base.write_relationships(vec![EntityRelationship {
+        parent: "Hero".into(),
+        child: "Dog".into(),
+        role: Role::NONE,
+        ordinal: 1.into(),
+    }])
+    .unwrap();
+    base
+}
+
+fn copy(store: &MemoryDatabase) -> MemoryDatabase {
+    let copy = MemoryDatabase::new();
+    import_json(&copy, &export_json(store).unwrap()).unwrap();
+    copy
+}
+
+#[test]
+fn parallel_edits_of_different_records_are_combined() {
+    let base = base();
+    let ours = copy(&base);
+    let theirs = copy(&base);
+    ours.change_entity_description((&"Hero".into(), &"Age".into()), &"13".into())
+        .unwrap();
+    ours.write_history_items(vec![item(3, 30, "Found.")])
+        .unwrap();
+    theirs
+        .delete_entity_column(("Villain".into(), "Age".into()))
+        .unwrap();
+    theirs
+        .redate_history_item(2.into(), 21.into(), 4.into())
+        .unwrap();
+    theirs
+        .delete_relationship(EntityRelationship {
+            parent: "Hero".into(),
+            child: "Dog".into(),
+            role: Role::NONE,
+            ordinal: 1.into(),
+        })
+        .unwrap();
+
+    let report = merge_stores(&base, &ours, &theirs).unwrap();
+    assert!(!report.has_conflicts());
+    assert_eq!(report.applied, diff_stores(&base, &theirs).unwrap());
+
+    assert_eq!(
+        ours.read_entity_columns(EntityColumnSearchParams::empty())
+            .unwrap(),
+        vec![column("Hero", "13")]
+    );
+    let items = ours
+        .read_history_items(HistoryItemSearchParams::empty())
+        .unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[1].year, 21.into());
+    assert_eq!(items[1].day, 4.into());
+    assert!(
+        ours.read_relationships(RelationshipSearchParams::empty())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn identical_edits_are_not_conflicts() {
+    let base = base();
+    let ours = copy(&base);
+    let theirs = copy(&base);
+    for store in [&ours, &theirs] {
+        store
+            .change_history_item_content(1.into(), &"Born again.".into())
+            .unwrap();
+    }
+    let report = merge_stores(&base, &ours, &theirs).unwrap();
+    assert!(!report.has_conflicts());
+    assert!(report.applied.is_empty());
+    assert!(diff_stores(&ours, &theirs).unwrap().is_empty());
+}
+
+#[test]
+fn different_edits_of_the_same_record_are_reported() {
+    let base = base();
+    let ours = copy(&base);
+    let theirs = copy(&base);
+    ours.change_entity_description((&"Hero".into(), &"Age".into()), &"13".into())
+        .unwrap();
+    theirs
+        .change_entity_description((&"Hero".into(), &"Age".into()), &"14".into())
+        .unwrap();
+    ours.delete_history_item(2.into()).unwrap();
+    theirs
+        .change_history_item_content(2.into(), &"Found again.".into())
+        .unwrap();
+    ours.write_history_items(vec![item(5, 50, "Ours.")])
+        .unwrap();
+    theirs
+        .write_history_items(vec![item(5, 50, "Theirs.")])
+        .unwrap();
+    let before = export_json(&ours).unwrap();
+
+    let report = merge_stores(&base, &ours, &theirs).unwrap();
+    assert!(report.applied.is_empty());
+    assert_eq!(
+        report.entity_column_conflicts,
+        vec![MergeConflict {
+            base: Some(column("Hero", "12")),
+            ours: Some(column("Hero", "13")),
+            theirs: Some(column("Hero", "14")),
+        }]
+    );
+    assert_eq!(
+        report.history_item_conflicts,
+        vec![
+            MergeConflict {
+                base: Some(item(2, 20, "Lost.")),
+                ours: None,
+                theirs: Some(item(2, 20, "Found again.")),
+            },
+            MergeConflict {
+                base: None,
+                ours: Some(item(5, 50, "Ours.")),
+                theirs: Some(item(5, 50, "Theirs.")),
+            },
+        ]
+    );
+    assert_eq!(export_json(&ours).unwrap(), before);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_database_can_be_merged_into() {
+    use lorecore::sql::lore_database::LoreDatabase;
+    use tempfile::NamedTempFile;
+
+    let base = base();
+    let theirs = copy(&base);
+    theirs
+        .write_entity_columns(vec![column("Sidekick", "10")])
+        .unwrap();
+    theirs
+        .write_relationships(vec![EntityRelationship {
+            parent: "Hero".into(),
+            child: "Dog".into(),
+            role: "owner".into(),
+            ordinal: 2.into(),
+        }])
+        .unwrap();
+
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let ours = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+    import_json(&ours, &export_json(&base).unwrap()).unwrap();
+    let report = merge_stores(&base, &ours, &theirs).unwrap();
+    assert!(!report.has_conflicts());
+    assert!(diff_stores(&theirs, &ours).unwrap().is_empty());
+    temp_path.close().unwrap();
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "sqlite")]
+
 use lorecore::{
     sql::{
         lore_database::LoreDatabase,
@@ -17,6 +19,7 @@ fn write_single_relationship() {
         parent: "testparent".into(),
         child: "testchild".into(),
         role: "testrole".into(),
+        ordinal: Ordinal::NONE,
     };
     db.write_relationships(vec![rel.clone()]).unwrap();
     let rel_out = db
@@ -46,9 +49,9 @@ fn create_example() -> (tempfile::TempPath, LoreDatabase, Vec<EntityRelationship
     let path_in: PathBuf = temp_path.as_os_str().into();
     let db = LoreDatabase::open(path_in.clone()).unwrap();
 
-    let parents = vec!["testparent1".to_string(), "testparent2".to_string()];
-    let children = vec!["testchild1".to_string(), "testchild2".to_string()];
-    let roles = vec![Some("testrole".to_string()), None];
+    let parents = ["testparent1".to_string(), "testparent2".to_string()];
+    let children = ["testchild1".to_string(), "testchild2".to_string()];
+    let roles = [Some("testrole".to_string()), None];
     let mut rels: Vec<EntityRelationship> = Vec::new();
     for parent in parents.iter() {
         for child in children.iter() {
@@ -57,6 +60,7 @@ fn create_example() -> (tempfile::TempPath, LoreDatabase, Vec<EntityRelationship
                     parent: parent.as_str().into(),
                     child: child.as_str().into(),
                     role: role.clone().into(),
+                    ordinal: Ordinal::NONE,
                 });
             }
         }
@@ -75,13 +79,14 @@ fn writing_several_roles_to_same_relationship() {
 
     let parent = "testparent".to_string();
     let child = "testchild".to_string();
-    let roles = vec!["testrole1".to_string(), "testrole2".to_string()];
+    let roles = ["testrole1".to_string(), "testrole2".to_string()];
     let mut rels: Vec<EntityRelationship> = Vec::new();
     for role in roles.iter() {
         rels.push(EntityRelationship {
             parent: parent.as_str().into(),
             child: child.as_str().into(),
             role: role.clone().into(),
+            ordinal: Ordinal::NONE,
         });
     }
 
@@ -445,6 +450,7 @@ fn test_write_read_relationships_after_db_deletion() {
         parent: "testparent".into(),
         child: "testchild".into(),
         role: Role::NONE,
+        ordinal: Ordinal::NONE,
     }]);
     assert!(
         write_result.is_err(),
@@ -472,6 +478,7 @@ fn test_change_relationship_role_to_some() {
         parent: rel.parent,
         child: rel.child,
         role: new_role,
+        ordinal: Ordinal::NONE,
     }));
     temp_path.close().unwrap();
 }
@@ -490,6 +497,7 @@ fn test_change_relationship_role_to_none() {
         parent: rel.parent,
         child: rel.child,
         role: new_role,
+        ordinal: Ordinal::NONE,
     }));
     temp_path.close().unwrap();
 }
@@ -522,11 +530,13 @@ fn test_delete_relationship_without_role() {
         parent: parent.clone(),
         child: child.clone(),
         role,
+        ordinal: Ordinal::NONE,
     };
     let rel_without_role = EntityRelationship {
         parent,
         child,
         role: Role::NONE,
+        ordinal: Ordinal::NONE,
     };
 
     // Write the relationships to the database
@@ -564,11 +574,13 @@ fn test_delete_relationship_with_role() {
         parent: parent.clone(),
         child: child.clone(),
         role,
+        ordinal: Ordinal::NONE,
     };
     let rel_without_role = EntityRelationship {
         parent,
         child,
         role: Role::NONE,
+        ordinal: Ordinal::NONE,
     };
 
     // Write the relationships to the database
@@ -590,3 +602,179 @@ fn test_delete_relationship_with_role() {
     // Close the temporary path
     temp_path.close().unwrap();
 }
+
+#[test]
+fn read_relationships_honors_ordinals() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let path_in: PathBuf = temp_path.as_os_str().into();
+    let db = LoreDatabase::open(path_in.clone()).unwrap();
+
+    let children = ["charlie", "alice", "bob"];
+    let rels: Vec<EntityRelationship> = children
+        .iter()
+        .enumerate()
+        .map(|(i, child)| EntityRelationship {
+            parent: "parent".into(),
+            child: (*child).into(),
+            role: Role::NONE,
+            ordinal: (i as u32 + 1).into(),
+        })
+        .collect();
+    db.write_relationships(rels.clone()).unwrap();
+    db.write_relationships(vec![EntityRelationship {
+        parent: "parent".into(),
+        child: "aaron".into(),
+        role: Role::NONE,
+        ordinal: Ordinal::NONE,
+    }])
+    .unwrap();
+
+    let rels_out = db
+        .read_relationships(RelationshipSearchParams::empty())
+        .unwrap();
+    let children_out: Vec<_> = rels_out.iter().map(|rel| rel.child.to_str()).collect();
+    assert_eq!(children_out, vec!["charlie", "alice", "bob", "aaron"]);
+
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn writing_too_large_ordinal_is_an_error() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let path_in: PathBuf = temp_path.as_os_str().into();
+    let db = LoreDatabase::open(path_in.clone()).unwrap();
+
+    let result = db.write_relationships(vec![EntityRelationship {
+        parent: "parent".into(),
+        child: "child".into(),
+        role: Role::NONE,
+        ordinal: 3_000_000_000_u32.into(),
+    }]);
+    assert!(result.is_err());
+
+    let rels_out = db
+        .read_relationships(RelationshipSearchParams::empty())
+        .unwrap();
+    assert!(rels_out.is_empty());
+
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn test_reorder_children() {
+    let (temp_path, db, _) = create_example();
+    let parent: Parent = "testparent1".into();
+
+    db.reorder_children(&parent, &["testchild2".into(), "testchild1".into()])
+        .unwrap();
+
+    let rels_out = db
+        .read_relationships(RelationshipSearchParams::new(
+            Some(SqlSearchText::exact(parent.to_str())),
+            None,
+        ))
+        .unwrap();
+    assert_eq!(rels_out.len(), 4);
+    for rel in &rels_out[..2] {
+        assert_eq!(rel.child, "testchild2".into());
+        assert_eq!(rel.ordinal, 1.into());
+    }
+    for rel in &rels_out[2..] {
+        assert_eq!(rel.child, "testchild1".into());
+        assert_eq!(rel.ordinal, 2.into());
+    }
+
+    let other_rels = db
+        .read_relationships(RelationshipSearchParams::new(
+            Some(SqlSearchText::exact("testparent2")),
+            None,
+        ))
+        .unwrap();
+    assert!(other_rels.iter().all(|rel| rel.ordinal == Ordinal::NONE));
+
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn reorder_children_with_unknown_child_changes_nothing() {
+    let (temp_path, db, rels) = create_example();
+    let parent: Parent = "testparent1".into();
+
+    let result = db.reorder_children(&parent, &["testchild2".into(), "fununu".into()]);
+    assert!(result.is_err());
+
+    let rels_out = db
+        .read_relationships(RelationshipSearchParams::empty())
+        .unwrap();
+    assert_eq!(rels_out, rels);
+
+    temp_path.close().unwrap();
+}
+
+fn location(parent: &str, child: &str) -> EntityRelationship {
+    EntityRelationship {
+        parent: parent.into(),
+        child: child.into(),
+        role: "location".into(),
+        ordinal: Ordinal::NONE,
+    }
+}
+
+#[test]
+fn test_reparent_with_descendants() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let path_in: PathBuf = temp_path.as_os_str().into();
+    let db = LoreDatabase::open(path_in.clone()).unwrap();
+    let owner = EntityRelationship {
+        parent: "merchant".into(),
+        child: "city".into(),
+        role: "owner".into(),
+        ordinal: Ordinal::NONE,
+    };
+    db.write_relationships(vec![
+        location("kingdom1", "city"),
+        location("city", "tavern"),
+        owner.clone(),
+    ])
+    .unwrap();
+
+    let report = db
+        .reparent(&"city".into(), &"location".into(), &"kingdom2".into(), true)
+        .unwrap();
+    assert!(report.is_applied());
+
+    let mut expected = vec![
+        location("kingdom2", "city"),
+        location("city", "tavern"),
+        owner,
+    ];
+    expected.sort();
+    let rels_out = db
+        .read_relationships(RelationshipSearchParams::empty())
+        .unwrap();
+    assert_eq!(rels_out, expected);
+
+    temp_path.close().unwrap();
+}
+
+#[test]
+fn test_reparent_with_conflict_changes_nothing() {
+    let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+    let path_in: PathBuf = temp_path.as_os_str().into();
+    let db = LoreDatabase::open(path_in.clone()).unwrap();
+    let mut rels = vec![location("kingdom1", "city"), location("city", "tavern")];
+    rels.sort();
+    db.write_relationships(rels.clone()).unwrap();
+
+    let report = db
+        .reparent(&"city".into(), &"location".into(), &"tavern".into(), true)
+        .unwrap();
+    assert!(!report.is_applied());
+
+    let rels_out = db
+        .read_relationships(RelationshipSearchParams::empty())
+        .unwrap();
+    assert_eq!(rels_out, rels);
+
+    temp_path.close().unwrap();
+}
@@ -0,0 +1,326 @@
+use lorecore::{
+    genealogy::GenealogyRoles,
+    graph::GraphExportParams,
+    memory::MemoryDatabase,
+    sql::search_params::{
+        EntityColumnSearchParams, HistoryItemSearchParams, RelationshipSearchParams, SqlSearchText,
+    },
+    store::LoreStore,
+    types::*,
+};
+
+fn fill_store(store: &impl LoreStore) {
+    let cols = ["Anna", "Bert", "Clara"]
+        .iter()
+        .map(|label| EntityColumn {
+            label: (*label).into(),
+            descriptor: "Profession".into(),
+            description: format!("{}'s profession", label).into(),
+        })
+        .collect();
+    store.write_entity_columns(cols).unwrap();
+    let rels = [
+        ("Anna", "Bert", "spouse"),
+        ("Anna", "Clara", "mother"),
+        ("Bert", "Clara", "father"),
+    ]
+    .iter()
+    .map(|(parent, child, role)| EntityRelationship {
+        parent: (*parent).into(),
+        child: (*child).into(),
+        role: (*role).into(),
+        ordinal: Ordinal::NONE,
+    })
+    .collect();
+    store.write_relationships(rels).unwrap();
+    let item = HistoryItem {
+        timestamp: 1.into(),
+        year: 1990.into(),
+        day: Day::NONE,
+        content: "\\entityref{Clara} was born.".into(),
+        properties: HistoryItemProperties::none(),
+    };
+    store.write_history_items(vec![item]).unwrap();
+}
+
+fn check_aggregated_reads(store: &impl LoreStore) {
+    fill_store(store);
+
+    let clara = store.read_entity(&"Clara".into()).unwrap().unwrap();
+    assert_eq!(clara.parents.len(), 2);
+    assert_eq!(clara.history.len(), 1);
+    assert!(store.read_entity(&"Dora".into()).unwrap().is_none());
+    let entities = store
+        .read_entities(EntityColumnSearchParams::new(
+            None,
+            Some(SqlSearchText::partial("profession")),
+        ))
+        .unwrap();
+    assert_eq!(entities.len(), 3);
+
+    let graph = store
+        .read_relationship_graph(GraphExportParams::new(
+            Some(("Bert".into(), 1)),
+            vec!["Profession".into()],
+        ))
+        .unwrap();
+    assert_eq!(graph.nodes.len(), 3);
+    assert_eq!(
+        graph.nodes[0].attributes,
+        vec![("Profession".into(), "Anna's profession".into())]
+    );
+
+    let roles = GenealogyRoles::new(
+        vec!["father".into(), "mother".into()],
+        vec!["spouse".into()],
+    );
+    let tree = store.read_family_tree(&roles).unwrap();
+    assert_eq!(
+        tree.parents(&"Clara".into()),
+        vec!["Anna".into(), "Bert".into()]
+    );
+
+    assert_eq!(
+        store.read_distinct_roles().unwrap(),
+        vec!["father".into(), "mother".into(), "spouse".into()]
+    );
+}
+
+fn check_rollback(store: &impl LoreStore) {
+    store.begin_transaction().unwrap();
+    fill_store(store);
+    store.rollback_transaction().unwrap();
+    assert!(store.read_distinct_labels().unwrap().is_empty());
+    assert!(store.read_distinct_years().unwrap().is_empty());
+}
+
+fn check_failing_transaction_is_rolled_back(store: &impl LoreStore) {
+    let result = store.in_transaction(|| {
+        fill_store(store);
+        store.write_history_items(vec![HistoryItem {
+            timestamp: 1.into(),
+            year: 1990.into(),
+            day: Day::NONE,
+            content: "A second item at the same timestamp.".into(),
+            properties: HistoryItemProperties::none(),
+        }])
+    });
+    assert!(result.is_err());
+    assert!(store.read_distinct_labels().unwrap().is_empty());
+}
+
+fn column(label: &str, descriptor: &str) -> EntityColumn {
+    EntityColumn {
+        label: label.into(),
+        descriptor: descriptor.into(),
+        description: "description".into(),
+    }
+}
+
+fn relationship(parent: &str, child: &str, role: &str) -> EntityRelationship {
+    EntityRelationship {
+        parent: parent.into(),
+        child: child.into(),
+        role: role.into(),
+        ordinal: Ordinal::NONE,
+    }
+}
+
+fn item(timestamp: i64) -> HistoryItem {
+    HistoryItem {
+        timestamp: timestamp.into(),
+        year: 1990.into(),
+        day: Day::NONE,
+        content: "content".into(),
+        properties: HistoryItemProperties::none(),
+    }
+}
+
+fn check_conflicting_batch_writes_nothing(store: &impl LoreStore) {
+    store.write_entity_columns(vec![column("A", "d")]).unwrap();
+    let result = store.write_entity_columns(vec![column("C", "d"), column("A", "d")]);
+    assert!(result.is_err());
+    assert_eq!(store.read_distinct_labels().unwrap(), vec!["A".into()]);
+
+    store.write_history_items(vec![item(1)]).unwrap();
+    let result = store.write_history_items(vec![item(2), item(1)]);
+    assert!(result.is_err());
+    assert_eq!(store.read_distinct_years().unwrap().len(), 1);
+    let items = store
+        .read_history_items(HistoryItemSearchParams::empty())
+        .unwrap();
+    assert_eq!(items, vec![item(1)]);
+
+    store
+        .write_relationships(vec![relationship("P", "C", "r")])
+        .unwrap();
+    let result = store.write_relationships(vec![
+        relationship("P", "D", "r"),
+        relationship("P", "C", "r"),
+    ]);
+    assert!(result.is_err());
+    assert_eq!(store.read_distinct_children().unwrap(), vec!["C".into()]);
+}
+
+fn check_too_large_ordinal_writes_nothing(store: &impl LoreStore) {
+    let too_large = EntityRelationship {
+        ordinal: 3_000_000_000_u32.into(),
+        ..relationship("P", "D", "r")
+    };
+    let result = store.write_relationships(vec![relationship("P", "C", "r"), too_large]);
+    assert!(result.is_err());
+    assert!(store.read_distinct_children().unwrap().is_empty());
+}
+
+fn check_updates_refuse_existing_keys(store: &impl LoreStore) {
+    let cols = vec![column("A", "d"), column("A", "e"), column("B", "d")];
+    store.write_entity_columns(cols.clone()).unwrap();
+    assert!(store.relabel_entity(&"A".into(), &"B".into()).is_err());
+    assert!(
+        store
+            .change_entity_descriptor((&"A".into(), "d".into()), &"e".into())
+            .is_err()
+    );
+    let cols_out = store
+        .read_entity_columns(EntityColumnSearchParams::empty())
+        .unwrap();
+    assert_eq!(cols_out, cols);
+
+    let rels = vec![relationship("P", "C", "r1"), relationship("P", "C", "r2")];
+    store.write_relationships(rels.clone()).unwrap();
+    assert!(
+        store
+            .change_relationship_role(relationship("P", "C", "r1"), &"r2".into())
+            .is_err()
+    );
+    let rels_out = store
+        .read_relationships(RelationshipSearchParams::empty())
+        .unwrap();
+    assert_eq!(rels_out, rels);
+}
+
+fn check_history_of_entity(store: &impl LoreStore) {
+    let label = "Dora \"the_Bold\"";
+    let items = vec![
+        HistoryItem {
+            content: format!("\\entityref{{{}}} was born.", label).into(),
+            ..item(1)
+        },
+        HistoryItem {
+            properties: serde_json::json!({ "additional_concerns": [label] })
+                .to_string()
+                .as_str()
+                .into(),
+            ..item(2)
+        },
+        HistoryItem {
+            content: "\\entityref{dora \"theXBold\"} was born.".into(),
+            ..item(3)
+        },
+    ];
+    store.write_history_items(items.clone()).unwrap();
+    store
+        .write_entity_columns(vec![column(label, "d")])
+        .unwrap();
+
+    let entity = store.read_entity(&label.into()).unwrap().unwrap();
+    assert_eq!(entity.history, items[..2]);
+}
+
+#[test]
+fn aggregated_reads_in_memory() {
+    check_aggregated_reads(&MemoryDatabase::new());
+}
+
+#[test]
+fn rollback_in_memory() {
+    check_rollback(&MemoryDatabase::new());
+}
+
+#[test]
+fn failing_transaction_is_rolled_back_in_memory() {
+    check_failing_transaction_is_rolled_back(&MemoryDatabase::new());
+}
+
+#[test]
+fn conflicting_batch_writes_nothing_in_memory() {
+    check_conflicting_batch_writes_nothing(&MemoryDatabase::new());
+}
+
+#[test]
+fn too_large_ordinal_writes_nothing_in_memory() {
+    check_too_large_ordinal_writes_nothing(&MemoryDatabase::new());
+}
+
+#[test]
+fn updates_refuse_existing_keys_in_memory() {
+    check_updates_refuse_existing_keys(&MemoryDatabase::new());
+}
+
+#[test]
+fn history_of_entity_in_memory() {
+    check_history_of_entity(&MemoryDatabase::new());
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use lorecore::sql::lore_database::LoreDatabase;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn aggregated_reads_in_sqlite() {
+        let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+        let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+        check_aggregated_reads(&db);
+        temp_path.close().unwrap();
+    }
+
+    #[test]
+    fn rollback_in_sqlite() {
+        let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+        let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+        check_rollback(&db);
+        temp_path.close().unwrap();
+    }
+
+    #[test]
+    fn failing_transaction_is_rolled_back_in_sqlite() {
+        let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+        let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+        check_failing_transaction_is_rolled_back(&db);
+        temp_path.close().unwrap();
+    }
+
+    #[test]
+    fn conflicting_batch_writes_nothing_in_sqlite() {
+        let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+        let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+        check_conflicting_batch_writes_nothing(&db);
+        temp_path.close().unwrap();
+    }
+
+    #[test]
+    fn too_large_ordinal_writes_nothing_in_sqlite() {
+        let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+        let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+        check_too_large_ordinal_writes_nothing(&db);
+        temp_path.close().unwrap();
+    }
+
+    #[test]
+    fn updates_refuse_existing_keys_in_sqlite() {
+        let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+        let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+        check_updates_refuse_existing_keys(&db);
+        temp_path.close().unwrap();
+    }
+
+    #[test]
+    fn history_of_entity_in_sqlite() {
+        let temp_path = NamedTempFile::new().unwrap().into_temp_path();
+        let db = LoreDatabase::open(temp_path.as_os_str().into()).unwrap();
+        check_history_of_entity(&db);
+        temp_path.close().unwrap();
+    }
+}